@@ -0,0 +1,197 @@
+//! Benchmarks of the meshing of shapes, in a headless app.
+
+use bevy::{
+    asset::{AddAsset, AssetPlugin},
+    core::CorePlugin,
+    prelude::*,
+};
+use bevy_prototype_lyon::{entity::ShapeBundle, plugin::tessellate_with_config, prelude::*};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+/// Returns a headless app with the plugin.
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugin(CorePlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_plugin(ShapePlugin);
+    app
+}
+
+/// Marks the paths of all the shapes as changed, so that they are meshed
+/// again on the next update.
+fn touch_paths(app: &mut App) {
+    let mut paths = app.world.query::<&mut Path>();
+    for mut path in paths.iter_mut(&mut app.world) {
+        path.set_changed();
+    }
+}
+
+fn fill() -> DrawMode {
+    DrawMode::Fill(FillMode::color(Color::GREEN))
+}
+
+fn triangle(i: usize) -> Path {
+    let offset = Vec2::new(i as f32 * 10.0, 0.0);
+    ShapePath::build_as(&shapes::Polygon {
+        points: vec![offset, offset + Vec2::X * 8.0, offset + Vec2::Y * 8.0],
+        closed: true,
+    })
+}
+
+/// Places the shapes on a grid.
+fn position(i: usize) -> Vec2 {
+    Vec2::new((i % 40) as f32 * 20.0, (i / 40) as f32 * 20.0)
+}
+
+fn circle(i: usize) -> shapes::Circle {
+    shapes::Circle {
+        radius: 10.0 + (i % 100) as f32,
+        center: position(i),
+        ..shapes::Circle::default()
+    }
+}
+
+/// Re-meshes 1000 triangles as separate shapes and as a single batch.
+fn batching(c: &mut Criterion) {
+    const SHAPES: usize = 1000;
+    let mut group = c.benchmark_group("batching");
+
+    let mut separate = app();
+    for i in 0..SHAPES {
+        separate.world.spawn().insert_bundle(ShapeBundle {
+            path: triangle(i),
+            mode: fill(),
+            ..ShapeBundle::default()
+        });
+    }
+    separate.update();
+    group.bench_function("separate", |b| {
+        b.iter(|| {
+            touch_paths(&mut separate);
+            separate.update();
+        });
+    });
+
+    let mut batched = app();
+    let members = (0..SHAPES)
+        .map(|i| {
+            batched
+                .world
+                .spawn()
+                .insert_bundle((triangle(i), fill()))
+                .id()
+        })
+        .collect();
+    batched.world.spawn().insert_bundle(BatchedShapesBundle {
+        batch: BatchedShapes(members),
+        ..BatchedShapesBundle::default()
+    });
+    batched.update();
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            touch_paths(&mut batched);
+            batched.update();
+        });
+    });
+
+    group.finish();
+}
+
+/// Meshes 1000 circles with the plugin, which tessellates them in parallel,
+/// and one after the other with [`tessellate_with_config`].
+fn circles(c: &mut Criterion) {
+    const CIRCLES: usize = 1000;
+    let mut group = c.benchmark_group("circles");
+
+    let mut parallel = app();
+    for i in 0..CIRCLES {
+        parallel
+            .world
+            .spawn()
+            .insert_bundle(GeometryBuilder::build_as(
+                &circle(i),
+                fill(),
+                Transform::default(),
+            ));
+    }
+    parallel.update();
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            touch_paths(&mut parallel);
+            parallel.update();
+        });
+    });
+
+    let paths = (0..CIRCLES)
+        .map(|i| ShapePath::build_as(&circle(i)))
+        .collect::<Vec<_>>();
+    let config = MeshConfig::default();
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .map(|path| tessellate_with_config(path, &fill(), &config))
+                .collect::<Vec<_>>()
+        });
+    });
+
+    group.finish();
+}
+
+/// Spawns and meshes 1000 copies of a circle, as separate shapes and as the
+/// instances of a single shape.
+///
+/// The app has nothing to render to, so this only measures the cost in the main
+/// world, not the extraction, upload and drawing of the instances. The
+/// `instancing` example logs the frame times of both approaches for that.
+fn instancing(c: &mut Criterion) {
+    const COPIES: usize = 1000;
+    let mut group = c.benchmark_group("instancing");
+
+    group.bench_function("naive", |b| {
+        b.iter_batched(
+            app,
+            |mut app| {
+                for i in 0..COPIES {
+                    app.world.spawn().insert_bundle(GeometryBuilder::build_as(
+                        &circle(0),
+                        fill(),
+                        Transform::from_translation(position(i).extend(0.0)),
+                    ));
+                }
+                app.update();
+                app
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("instanced", |b| {
+        b.iter_batched(
+            app,
+            |mut app| {
+                let instances = (0..COPIES)
+                    .map(|i| ShapeInstance {
+                        position: position(i),
+                        ..ShapeInstance::default()
+                    })
+                    .collect();
+                app.world.spawn().insert_bundle(InstancedShapeBundle {
+                    path: ShapePath::build_as(&circle(0)),
+                    mode: fill(),
+                    instances: ShapeInstances(instances),
+                    ..InstancedShapeBundle::default()
+                });
+                app.update();
+                app
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, batching, circles, instancing);
+criterion_main!(benches);
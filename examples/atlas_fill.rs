@@ -0,0 +1,305 @@
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::{PrepareAssetError, RenderAsset, RenderAssets},
+        render_resource::{
+            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, Buffer,
+            BufferBindingType, BufferInitDescriptor, BufferSize, BufferUsages, Extent3d,
+            RenderPipelineDescriptor, SamplerBindingType, ShaderStages,
+            SpecializedMeshPipelineError, TextureDimension, TextureFormat, TextureSampleType,
+            TextureViewDimension,
+        },
+        renderer::RenderDevice,
+    },
+    sprite::{Material2d, Material2dPipeline, Material2dPlugin, Rect},
+};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    let mut app = App::new();
+    app.insert_resource(Msaa { samples: 4 })
+        // The 2d mesh pipeline of Bevy requires normals and UVs.
+        .insert_resource(MeshConfig {
+            include_uvs: true,
+            include_normals: true,
+            ..MeshConfig::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_plugin(Material2dPlugin::<AtlasMaterial>::default())
+        .add_startup_system(setup_system);
+
+    let mut shaders = app.world.get_resource_mut::<Assets<Shader>>().unwrap();
+    shaders.set_untracked(ATLAS_SHADER_HANDLE, Shader::from_wgsl(ATLAS_SHADER));
+
+    app.run();
+}
+
+/// The size of the atlas texture, in pixels.
+const ATLAS_SIZE: [u32; 2] = [128, 64];
+/// The size of a single brick tile, the left half of the atlas.
+const BRICK_SIZE: [u32; 2] = [64, 32];
+
+fn setup_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<AtlasMaterial>>,
+) {
+    let texture = images.add(atlas_image());
+    let mut atlas = TextureAtlas::new_empty(texture.clone(), atlas_size(ATLAS_SIZE));
+    let bricks = atlas.add_texture(Rect {
+        min: Vec2::ZERO,
+        max: atlas_size(BRICK_SIZE),
+    });
+    let material = materials.add(AtlasMaterial {
+        texture,
+        region: TextureTiling::atlas_region(&atlas, bricks).unwrap(),
+    });
+
+    let shape = shapes::RegularPolygon {
+        sides: 6,
+        feature: RegularPolygonFeature::Radius(250.0),
+        ..shapes::RegularPolygon::default()
+    };
+    // The bricks are twice as wide as they are tall, so the hexagon, about as
+    // wide as it is tall, repeats them twice as often vertically.
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands
+        .spawn_bundle(MaterialShapeBundle::new(
+            &shape,
+            DrawMode::Fill(FillMode::color(Color::WHITE)),
+            material,
+            Transform::default(),
+        ))
+        .insert(TextureTiling {
+            repeat: Vec2::new(4.0, 8.0),
+        });
+}
+
+fn atlas_size([width, height]: [u32; 2]) -> Vec2 {
+    Vec2::new(width as f32, height as f32)
+}
+
+/// Draws a brick pattern in the left half of the atlas, and a checkerboard
+/// that should never be sampled in its right half.
+fn atlas_image() -> Image {
+    let [width, height] = ATLAS_SIZE;
+    let [brick_width, brick_height] = BRICK_SIZE;
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let texel: [u8; 4] = if x >= brick_width {
+                if (x / 8 + y / 8) % 2 == 0 {
+                    [255, 0, 255, 255]
+                } else {
+                    [0, 0, 0, 255]
+                }
+            } else {
+                // Two rows of bricks, the second one shifted by half a brick.
+                let row = y / (brick_height / 2);
+                let shift = row * brick_width / 4;
+                let joint = y % (brick_height / 2) < 2 || (x + shift) % (brick_width / 2) < 2;
+                if joint {
+                    [200, 200, 190, 255]
+                } else {
+                    [150, 50, 40, 255]
+                }
+            };
+            data.extend(texel);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+const ATLAS_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 12_604_517_903_148_266_281);
+
+const ATLAS_SHADER: &str = r"
+#import bevy_sprite::mesh2d_view_bind_group
+[[group(0), binding(0)]]
+var<uniform> view: View;
+#import bevy_sprite::mesh2d_struct
+[[group(1), binding(0)]]
+var<uniform> mesh: Mesh2d;
+
+struct AtlasMaterial {
+    region: vec4<f32>;
+};
+[[group(2), binding(0)]]
+var<uniform> material: AtlasMaterial;
+[[group(2), binding(1)]]
+var texture: texture_2d<f32>;
+[[group(2), binding(2)]]
+var texture_sampler: sampler;
+
+struct Vertex {
+    [[location(0)]] position: vec3<f32>;
+    [[location(1)]] uv: vec2<f32>;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = view.view_proj * mesh.model * vec4<f32>(vertex.position, 1.0);
+    out.uv = vertex.uv;
+    return out;
+}
+
+[[stage(fragment)]]
+fn fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Wrap the tiled UVs into the atlas region.
+    let uv = material.region.xy + fract(in.uv) * (material.region.zw - material.region.xy);
+    return textureSample(texture, texture_sampler, uv);
+}
+";
+
+/// A material tiling a region of a texture atlas, in the UVs scaled by a
+/// `TextureTiling`.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "3b6e8f1d-52a4-4c7e-9d0b-a1f2c3e4d5b6"]
+struct AtlasMaterial {
+    texture: Handle<Image>,
+    region: Rect,
+}
+
+struct GpuAtlasMaterial {
+    _buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl RenderAsset for AtlasMaterial {
+    type ExtractedAsset = Self;
+    type PreparedAsset = GpuAtlasMaterial;
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<Material2dPipeline<Self>>,
+        SRes<RenderAssets<Image>>,
+    );
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline, images): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let image = match images.get(&material.texture) {
+            Some(image) => image,
+            None => return Err(PrepareAssetError::RetryNextUpdate(material)),
+        };
+        let region = [
+            material.region.min.x,
+            material.region.min.y,
+            material.region.max.x,
+            material.region.max.y,
+        ];
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("atlas_material_buffer"),
+            contents: &region.map(f32::to_ne_bytes).concat(),
+            usage: BufferUsages::UNIFORM,
+        });
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("atlas_material_bind_group"),
+            layout: &pipeline.material2d_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&image.sampler),
+                },
+            ],
+        });
+
+        Ok(GpuAtlasMaterial {
+            _buffer: buffer,
+            bind_group,
+        })
+    }
+}
+
+impl Material2d for AtlasMaterial {
+    fn vertex_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(ATLAS_SHADER_HANDLE.typed())
+    }
+
+    fn fragment_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(ATLAS_SHADER_HANDLE.typed())
+    }
+
+    fn bind_group(material: &GpuAtlasMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("atlas_material_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(16),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    // Read the UVs of the shape, scaled by its `TextureTiling`.
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_UV_0.at_shader_location(1),
+        ])?];
+        Ok(())
+    }
+}
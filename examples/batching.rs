@@ -0,0 +1,56 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_prototype_lyon::prelude::*;
+
+const GRID_SIZE: i32 = 100;
+const TILE_SIZE: f32 = 6.0;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+// Draws ten thousand tiles with a single mesh. Compare the logged frame time
+// against spawning each tile as its own `ShapeBundle`.
+fn setup_system(mut commands: Commands) {
+    let offset = GRID_SIZE as f32 * TILE_SIZE / 2.0;
+    let mut members = Vec::new();
+    for x in 0..GRID_SIZE {
+        for y in 0..GRID_SIZE {
+            let tile = shapes::Rectangle {
+                extents: Vec2::splat(TILE_SIZE - 1.0),
+                origin: RectangleOrigin::CustomCenter(Vec2::new(
+                    x as f32 * TILE_SIZE - offset,
+                    y as f32 * TILE_SIZE - offset,
+                )),
+            };
+            let color = Color::rgb(
+                x as f32 / GRID_SIZE as f32,
+                y as f32 / GRID_SIZE as f32,
+                0.5,
+            );
+            let member = commands
+                .spawn()
+                .insert_bundle((
+                    ShapePath::build_as(&tile),
+                    DrawMode::Fill(FillMode::color(color)),
+                ))
+                .id();
+            members.push(member);
+        }
+    }
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(BatchedShapesBundle {
+        batch: BatchedShapes(members),
+        ..BatchedShapesBundle::default()
+    });
+}
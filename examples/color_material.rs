@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        // The 2d mesh pipeline of Bevy requires normals and UVs.
+        .insert_resource(MeshConfig {
+            include_uvs: true,
+            include_normals: true,
+            ..MeshConfig::default()
+        })
+        .insert_resource(ShapeMeshCache::new())
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
+    let hexagon = RegularPolygon {
+        sides: 6,
+        feature: RegularPolygonFeature::Radius(40.0),
+        ..RegularPolygon::default()
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // The hexagons all share a single mesh, since their colors come from their
+    // materials instead of their vertices.
+    for i in 0..8 {
+        let material = materials.add(ColorMaterial::from(Color::hsl(i as f32 * 45.0, 0.8, 0.5)));
+        commands
+            .spawn_bundle(MaterialShapeBundle::new(
+                &hexagon,
+                DrawMode::Fill(FillMode::color(Color::WHITE)),
+                material,
+                Transform::from_xyz(i as f32 * 90.0 - 315.0, 0.0, 0.0),
+            ))
+            .insert(MaterialColor);
+    }
+}
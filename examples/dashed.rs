@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let circle = shapes::Circle {
+        radius: 150.0,
+        ..shapes::Circle::default()
+    };
+    let square = shapes::Rectangle {
+        extents: Vec2::splat(200.0),
+        ..shapes::Rectangle::default()
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &circle,
+        DrawMode::Stroke(
+            StrokeMode::new(Color::BLACK, 5.0).with_dash(DashPattern::new(20.0, 10.0)),
+        ),
+        Transform::default(),
+    ));
+    // Marching ants, like a selection highlight.
+    commands
+        .spawn_bundle(GeometryBuilder::build_as(
+            &square,
+            DrawMode::Stroke(
+                StrokeMode::new(Color::ORANGE_RED, 3.0).with_dash(DashPattern::new(4.0, 8.0)),
+            ),
+            Transform::default(),
+        ))
+        .insert(DashAnimation { speed: 24.0 });
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .add_system(crosshair_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+}
+
+// No entity is spawned or despawned while the cursor moves.
+fn crosshair_system(windows: Res<Windows>, mut gizmos: ResMut<ShapeGizmos>) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let cursor = match window.cursor_position() {
+        Some(position) => position,
+        None => return,
+    };
+    // The default 2d camera puts the origin of the world at the center of the
+    // window.
+    let position = cursor - Vec2::new(window.width(), window.height()) / 2.0;
+
+    gizmos.circle(position, 16.0, Color::RED);
+    gizmos.line(
+        position - Vec2::new(24.0, 0.0),
+        position + Vec2::new(24.0, 0.0),
+        Color::RED,
+    );
+    gizmos.line(
+        position - Vec2::new(0.0, 24.0),
+        position + Vec2::new(0.0, 24.0),
+        Color::RED,
+    );
+}
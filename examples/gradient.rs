@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let shape = shapes::Rectangle {
+        extents: Vec2::new(400.0, 200.0),
+        ..shapes::Rectangle::default()
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shape,
+        DrawMode::Fill(FillMode::linear_gradient(LinearGradient {
+            start: Vec2::new(-200.0, 0.0),
+            end: Vec2::new(200.0, 0.0),
+            start_color: Color::RED,
+            end_color: Color::BLUE,
+        })),
+        Transform::default(),
+    ));
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let grid = shapes::Grid {
+        cell_size: Vec2::splat(50.0),
+        count: UVec2::new(10, 10),
+        ..shapes::Grid::default()
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // All the lines of the grid are drawn by a single entity.
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &grid,
+        DrawMode::Stroke(StrokeMode::new(Color::GRAY, 2.0)),
+        Transform::default(),
+    ));
+}
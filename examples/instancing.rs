@@ -0,0 +1,94 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_prototype_lyon::prelude::*;
+
+const SHAPE_COUNT: usize = 50_000;
+
+// Run with `--release` to draw the triangles with `ShapeInstances`, or with
+// `--release -- --naive` to spawn an entity for each of them, and compare the
+// logged frame times.
+fn main() {
+    let naive = std::env::args().any(|arg| arg == "--naive");
+
+    let mut app = App::new();
+    app.insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_plugin(ShapePlugin);
+    if naive {
+        app.add_startup_system(spawn_entities_system)
+            .add_system(rotate_entities_system);
+    } else {
+        app.add_startup_system(spawn_instances_system)
+            .add_system(rotate_instances_system);
+    }
+    app.run();
+}
+
+fn triangle() -> RegularPolygon {
+    RegularPolygon {
+        sides: 3,
+        feature: RegularPolygonFeature::Radius(3.0),
+        ..RegularPolygon::default()
+    }
+}
+
+fn position(i: usize) -> Vec2 {
+    let (x, y) = ((i % 250) as f32, (i / 250) as f32);
+    Vec2::new(x * 5.0 - 625.0, y * 4.0 - 400.0)
+}
+
+fn color(i: usize) -> Color {
+    Color::hsl(i as f32 / SHAPE_COUNT as f32 * 360.0, 0.8, 0.6)
+}
+
+fn spawn_instances_system(mut commands: Commands) {
+    let instances = (0..SHAPE_COUNT)
+        .map(|i| ShapeInstance {
+            position: position(i),
+            color: color(i),
+            ..ShapeInstance::default()
+        })
+        .collect();
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(InstancedShapeBundle {
+        path: ShapePath::build_as(&triangle()),
+        mode: DrawMode::Fill(FillMode::color(Color::WHITE)),
+        instances: ShapeInstances(instances),
+        ..InstancedShapeBundle::default()
+    });
+}
+
+fn rotate_instances_system(mut query: Query<&mut ShapeInstances>, time: Res<Time>) {
+    let delta = time.delta_seconds();
+
+    for mut instances in query.iter_mut() {
+        for instance in &mut instances.0 {
+            instance.rotation += delta;
+        }
+    }
+}
+
+fn spawn_entities_system(mut commands: Commands) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    for i in 0..SHAPE_COUNT {
+        let position = position(i);
+        commands.spawn_bundle(GeometryBuilder::build_as(
+            &triangle(),
+            DrawMode::Fill(FillMode::color(color(i))),
+            Transform::from_xyz(position.x, position.y, 0.0),
+        ));
+    }
+}
+
+fn rotate_entities_system(mut query: Query<&mut Transform, With<Path>>, time: Res<Time>) {
+    let delta = time.delta_seconds();
+
+    for mut transform in query.iter_mut() {
+        transform.rotate(Quat::from_rotation_z(delta));
+    }
+}
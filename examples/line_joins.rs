@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let zig_zag = shapes::Polygon {
+        points: vec![
+            Vec2::new(-50.0, -50.0),
+            Vec2::new(0.0, 50.0),
+            Vec2::new(50.0, -50.0),
+        ],
+        closed: false,
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    for (i, join) in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel]
+        .into_iter()
+        .enumerate()
+    {
+        commands.spawn_bundle(GeometryBuilder::build_as(
+            &zig_zag,
+            DrawMode::Stroke(
+                StrokeMode::new(Color::BLACK, 20.0)
+                    .with_line_join(join)
+                    .with_line_cap(LineCap::Round),
+            ),
+            Transform::from_xyz((i as f32 - 1.0) * 200.0, 0.0, 0.0),
+        ));
+    }
+}
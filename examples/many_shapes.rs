@@ -0,0 +1,50 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_prototype_lyon::prelude::*;
+
+const SHAPE_COUNT: usize = 1000;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .add_system(pulse_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Pulse(f32);
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    for i in 0..SHAPE_COUNT {
+        let (x, y) = ((i % 40) as f32, (i / 40) as f32);
+        commands
+            .spawn_bundle(GeometryBuilder::build_as(
+                &shapes::Circle::default(),
+                DrawMode::Fill(FillMode::color(Color::TEAL)),
+                Transform::from_xyz(x * 25.0 - 500.0, y * 25.0 - 300.0, 0.0),
+            ))
+            .insert(Pulse(i as f32 * 0.1));
+    }
+}
+
+// Changes the path of every circle each frame, so that all of them are
+// re-tessellated. Run with `--release` and compare the logged frame time on
+// machines with a different number of cores.
+fn pulse_system(mut query: Query<(&mut Path, &Pulse)>, time: Res<Time>) {
+    let t = time.seconds_since_startup() as f32;
+    for (mut path, pulse) in query.iter_mut() {
+        let circle = shapes::Circle {
+            radius: 10.0 + 2.0 * (t * 3.0 + pulse.0).sin(),
+            ..shapes::Circle::default()
+        };
+        *path = ShapePath::build_as(&circle);
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::{PrepareAssetError, RenderAsset},
+        render_resource::{
+            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, Buffer,
+            BufferBindingType, BufferInitDescriptor, BufferSize, BufferUsages,
+            RenderPipelineDescriptor, ShaderStages, SpecializedMeshPipelineError,
+        },
+        renderer::RenderDevice,
+    },
+    sprite::{Material2d, Material2dPipeline, Material2dPlugin},
+};
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    let mut app = App::new();
+    app.insert_resource(Msaa { samples: 4 })
+        // The 2d mesh pipeline of Bevy requires normals and UVs.
+        .insert_resource(MeshConfig {
+            include_uvs: true,
+            include_normals: true,
+            color_format: MeshColorFormat::Float32x4,
+            ..MeshConfig::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_plugin(Material2dPlugin::<StripedMaterial>::default())
+        .add_startup_system(setup_system);
+
+    let mut shaders = app.world.get_resource_mut::<Assets<Shader>>().unwrap();
+    shaders.set_untracked(STRIPED_SHADER_HANDLE, Shader::from_wgsl(STRIPED_SHADER));
+
+    app.run();
+}
+
+fn setup_system(mut commands: Commands, mut materials: ResMut<Assets<StripedMaterial>>) {
+    let circle = shapes::Circle {
+        radius: 150.0,
+        center: Vec2::new(-180.0, 0.0),
+        ..shapes::Circle::default()
+    };
+    let square = shapes::Rectangle {
+        extents: Vec2::splat(260.0),
+        origin: RectangleOrigin::CustomCenter(Vec2::new(180.0, 0.0)),
+    };
+    let stripes = materials.add(StripedMaterial {
+        color: Color::ALICE_BLUE,
+    });
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(MaterialShapeBundle::new(
+        &circle,
+        DrawMode::Fill(FillMode::color(Color::TEAL)),
+        stripes.clone(),
+        Transform::default(),
+    ));
+    // The material sees the vertex colors of the gradient.
+    commands.spawn_bundle(MaterialShapeBundle::new(
+        &square,
+        DrawMode::Fill(FillMode::linear_gradient(LinearGradient {
+            start: Vec2::new(50.0, 0.0),
+            end: Vec2::new(310.0, 0.0),
+            start_color: Color::ORANGE_RED,
+            end_color: Color::PURPLE,
+        })),
+        stripes,
+        Transform::default(),
+    ));
+}
+
+const STRIPED_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 5_193_726_410_293_184_576);
+
+const STRIPED_SHADER: &str = r"
+#import bevy_sprite::mesh2d_view_bind_group
+[[group(0), binding(0)]]
+var<uniform> view: View;
+#import bevy_sprite::mesh2d_struct
+[[group(1), binding(0)]]
+var<uniform> mesh: Mesh2d;
+
+struct StripedMaterial {
+    color: vec4<f32>;
+};
+[[group(2), binding(0)]]
+var<uniform> material: StripedMaterial;
+
+struct Vertex {
+    [[location(0)]] position: vec3<f32>;
+    [[location(1)]] color: vec4<f32>;
+};
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] world_position: vec2<f32>;
+    [[location(1)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    let world_position = mesh.model * vec4<f32>(vertex.position, 1.0);
+    out.clip_position = view.view_proj * world_position;
+    out.world_position = world_position.xy;
+    out.color = vertex.color;
+    return out;
+}
+
+struct FragmentInput {
+    [[location(0)]] world_position: vec2<f32>;
+    [[location(1)]] color: vec4<f32>;
+};
+
+[[stage(fragment)]]
+fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+    // Alternate diagonal stripes of the vertex color and of the material color
+    if (fract((in.world_position.x + in.world_position.y) / 40.0) < 0.5) {
+        return in.color;
+    }
+    return material.color;
+}
+";
+
+/// A material drawing stripes of the vertex colors of a shape and of its own
+/// color.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "8f0bd0a4-3c5d-4b9e-a0c6-7d1e2f9b6a31"]
+struct StripedMaterial {
+    color: Color,
+}
+
+struct GpuStripedMaterial {
+    _buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl RenderAsset for StripedMaterial {
+    type ExtractedAsset = Self;
+    type PreparedAsset = GpuStripedMaterial;
+    type Param = (SRes<RenderDevice>, SRes<Material2dPipeline<Self>>);
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let color = material.color.as_linear_rgba_f32().map(f32::to_ne_bytes);
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("striped_material_buffer"),
+            contents: &color.concat(),
+            usage: BufferUsages::UNIFORM,
+        });
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("striped_material_bind_group"),
+            layout: &pipeline.material2d_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        Ok(GpuStripedMaterial {
+            _buffer: buffer,
+            bind_group,
+        })
+    }
+}
+
+impl Material2d for StripedMaterial {
+    fn vertex_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(STRIPED_SHADER_HANDLE.typed())
+    }
+
+    fn fragment_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(STRIPED_SHADER_HANDLE.typed())
+    }
+
+    fn bind_group(material: &GpuStripedMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("striped_material_layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(16),
+                },
+                count: None,
+            }],
+        })
+    }
+
+    // Read the colors of the shape instead of the normals and UVs.
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(1),
+        ])?];
+        Ok(())
+    }
+}
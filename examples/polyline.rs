@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let zig_zag = shapes::Polyline {
+        points: (0..10)
+            .map(|i| {
+                Vec2::new(
+                    i as f32 * 60.0 - 270.0,
+                    if i % 2 == 0 { -50.0 } else { 50.0 },
+                )
+            })
+            .collect(),
+        closed: false,
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &zig_zag,
+        DrawMode::Stroke(StrokeMode::new(Color::BLACK, 5.0)),
+        Transform::default(),
+    ));
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let wave = shapes::Polyline {
+        points: (0..=60)
+            .map(|i| {
+                let x = i as f32 * 10.0 - 300.0;
+                Vec2::new(x, (x / 40.0).sin() * 80.0)
+            })
+            .collect(),
+        closed: false,
+    };
+    let heat_map = shapes::Circle {
+        radius: 60.0,
+        center: Vec2::new(0.0, -180.0),
+        ..shapes::Circle::default()
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &wave,
+        DrawMode::Stroke(
+            StrokeMode::new(Color::BLACK, 8.0)
+                .with_line_join(LineJoin::Round)
+                .with_gradient(StrokeGradient::Custom(|t| Color::hsl(t * 300.0, 1.0, 0.5))),
+        ),
+        Transform::default(),
+    ));
+    // The two ends of the gradient meet where the circle starts.
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &heat_map,
+        DrawMode::Stroke(StrokeMode::new(Color::BLACK, 12.0).with_gradient(
+            StrokeGradient::Linear {
+                start_color: Color::GREEN,
+                end_color: Color::RED,
+            },
+        )),
+        Transform::default(),
+    ));
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .add_system(reveal_system)
+        .run();
+}
+
+fn reveal_system(mut query: Query<&mut ShapeReveal>, time: Res<Time>) {
+    // Draw the signature in three seconds, then hold it for one.
+    let fraction = (time.seconds_since_startup() % 4.0 / 3.0).min(1.0) as f32;
+
+    for mut reveal in query.iter_mut() {
+        reveal.fraction = fraction;
+    }
+}
+
+fn setup_system(mut commands: Commands) {
+    let mut path_builder = PathBuilder::new();
+    path_builder.move_to(Vec2::new(-250.0, -20.0));
+    path_builder.cubic_bezier_to(
+        Vec2::new(-230.0, 120.0),
+        Vec2::new(-150.0, 120.0),
+        Vec2::new(-170.0, 10.0),
+    );
+    path_builder.cubic_bezier_to(
+        Vec2::new(-190.0, -100.0),
+        Vec2::new(-120.0, -80.0),
+        Vec2::new(-80.0, 0.0),
+    );
+    path_builder.quadratic_bezier_to(Vec2::new(-40.0, 60.0), Vec2::new(-20.0, -10.0));
+    path_builder.quadratic_bezier_to(Vec2::new(0.0, -60.0), Vec2::new(40.0, 20.0));
+    path_builder.cubic_bezier_to(
+        Vec2::new(70.0, 80.0),
+        Vec2::new(110.0, 60.0),
+        Vec2::new(100.0, 0.0),
+    );
+    path_builder.cubic_bezier_to(
+        Vec2::new(90.0, -50.0),
+        Vec2::new(160.0, -50.0),
+        Vec2::new(190.0, 10.0),
+    );
+    path_builder.quadratic_bezier_to(Vec2::new(220.0, 60.0), Vec2::new(250.0, -40.0));
+    let signature = path_builder.build();
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands
+        .spawn_bundle(GeometryBuilder::build_as(
+            &signature,
+            DrawMode::Stroke(
+                StrokeMode::new(Color::MIDNIGHT_BLUE, 6.0)
+                    .with_line_cap(LineCap::Round)
+                    .with_line_join(LineJoin::Round),
+            ),
+            Transform::default(),
+        ))
+        .insert(ShapeReveal { fraction: 0.0 });
+}
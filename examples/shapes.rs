@@ -0,0 +1,137 @@
+//! A gallery of the shapes provided by the `shapes` module.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    let fill = DrawMode::Outlined {
+        fill_mode: FillMode::color(Color::CYAN),
+        outline_mode: StrokeMode::new(Color::BLACK, 4.0),
+    };
+    let stroke = DrawMode::Stroke(StrokeMode::new(Color::BLACK, 4.0));
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Circle {
+            radius: 50.0,
+            ..shapes::Circle::default()
+        },
+        fill,
+        Transform::from_xyz(-300.0, 150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Rectangle {
+            extents: Vec2::splat(100.0),
+            ..shapes::Rectangle::default()
+        },
+        fill,
+        Transform::from_xyz(-150.0, 150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::RegularPolygon {
+            sides: 5,
+            feature: shapes::RegularPolygonFeature::Radius(50.0),
+            ..shapes::RegularPolygon::default()
+        },
+        fill,
+        Transform::from_xyz(0.0, 150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Arc {
+            radius: 50.0,
+            start_angle: 0.0,
+            sweep_angle: 1.5 * PI,
+            ..shapes::Arc::default()
+        },
+        stroke,
+        Transform::from_xyz(150.0, 150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Sector {
+            radius: 50.0,
+            start_angle: 0.25 * PI,
+            sweep_angle: 1.5 * PI,
+            ..shapes::Sector::default()
+        },
+        fill,
+        Transform::from_xyz(300.0, 150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Star {
+            tips: 5,
+            inner_radius: 20.0,
+            outer_radius: 50.0,
+            ..shapes::Star::default()
+        },
+        fill,
+        Transform::from_xyz(-300.0, 0.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::RoundedRectangle {
+            extents: Vec2::new(100.0, 80.0),
+            radii: shapes::CornerRadii {
+                top_left: 30.0,
+                bottom_right: 10.0,
+                ..shapes::CornerRadii::default()
+            },
+            ..shapes::RoundedRectangle::default()
+        },
+        fill,
+        Transform::from_xyz(-150.0, 0.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Ellipse {
+            radii: Vec2::new(60.0, 30.0),
+            rotation: PI / 6.0,
+            ..shapes::Ellipse::default()
+        },
+        fill,
+        Transform::from_xyz(0.0, 0.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Capsule {
+            extents: Vec2::new(120.0, 40.0),
+            ..shapes::Capsule::default()
+        },
+        fill,
+        Transform::from_xyz(150.0, 0.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Heart {
+            size: 100.0,
+            ..shapes::Heart::default()
+        },
+        fill,
+        Transform::from_xyz(300.0, 0.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Ring {
+            inner_radius: 25.0,
+            outer_radius: 50.0,
+            ..shapes::Ring::default()
+        },
+        fill,
+        Transform::from_xyz(-300.0, -150.0, 0.0),
+    ));
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &shapes::Arrow {
+            start: Vec2::new(-50.0, -30.0),
+            end: Vec2::new(50.0, 30.0),
+            head_length: 30.0,
+            head_width: 25.0,
+        },
+        fill,
+        Transform::from_xyz(-150.0, -150.0, 0.0),
+    ));
+}
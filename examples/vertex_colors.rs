@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+const HALF_SIZE: f32 = 150.0;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(ShapePlugin)
+        .add_startup_system(setup_system)
+        .run();
+}
+
+// Blends the colors of the four corners of the quad.
+fn corner_colors(position: Vec2) -> Color {
+    let [bottom_left, bottom_right, top_left, top_right] =
+        [Color::RED, Color::GREEN, Color::BLUE, Color::YELLOW]
+            .map(|color| Vec4::from(color.as_linear_rgba_f32()));
+    let t = ((position + HALF_SIZE) / (2.0 * HALF_SIZE)).clamp(Vec2::ZERO, Vec2::ONE);
+    let bottom = bottom_left.lerp(bottom_right, t.x);
+    let top = top_left.lerp(top_right, t.x);
+    let [r, g, b, a] = bottom.lerp(top, t.y).to_array();
+
+    Color::rgba_linear(r, g, b, a)
+}
+
+fn setup_system(mut commands: Commands) {
+    let quad = shapes::Rectangle {
+        extents: Vec2::splat(2.0 * HALF_SIZE),
+        origin: RectangleOrigin::Center,
+    };
+
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands.spawn_bundle(GeometryBuilder::build_as(
+        &quad,
+        DrawMode::Fill(FillMode::per_vertex(corner_colors)),
+        Transform::default(),
+    ));
+}
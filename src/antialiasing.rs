@@ -0,0 +1,62 @@
+//! Anti-aliasing of fills with a feathered edge.
+
+use bevy::render::color::Color;
+use lyon_tessellation::{math::Point, path::Path};
+
+use crate::{
+    convert::ToVec2,
+    draw::FillMode,
+    utils::{flatten, outward_side, ring_points, vertex_normal},
+    vertex::{pack_color, Vertex, VertexBuffers},
+};
+
+/// The longest a feather vertex can be pushed out at a sharp corner, relative
+/// to the feather width.
+const MITER_LIMIT: f32 = 2.0;
+
+/// Appends to `buffers` a strip of triangles around each sub-path of the fill,
+/// going from the fill color at the edge to transparent at `width` outside of
+/// it.
+pub fn feather(path: &Path, mode: &FillMode, width: f32, buffers: &mut VertexBuffers) {
+    let sub_paths = flatten(path, mode.options.tolerance);
+    for sub_path in &sub_paths {
+        let ring = ring_points(sub_path);
+        if ring.len() < 3 {
+            continue;
+        }
+
+        let side = outward_side(&sub_paths, &ring, mode.options.fill_rule);
+        let base = buffers.vertices.len() as u32;
+        let count = ring.len();
+        for (i, &current) in ring.iter().enumerate() {
+            let prev = ring[(i + count - 1) % count];
+            let next = ring[(i + 1) % count];
+            let offset = vertex_normal(prev, current, next, MITER_LIMIT) * side * width;
+
+            let color = color_at(mode, current);
+            let mut transparent = color_at(mode, current + offset);
+            transparent.set_a(0.0);
+            buffers.vertices.push(Vertex {
+                position: current.to_array(),
+                color: pack_color(color),
+            });
+            buffers.vertices.push(Vertex {
+                position: (current + offset).to_array(),
+                color: pack_color(transparent),
+            });
+        }
+        for i in 0..count as u32 {
+            let j = (i + 1) % count as u32;
+            let (inner, outer) = (base + 2 * i, base + 2 * i + 1);
+            let (next_inner, next_outer) = (base + 2 * j, base + 2 * j + 1);
+            buffers
+                .indices
+                .extend([inner, outer, next_inner, next_inner, outer, next_outer]);
+        }
+    }
+}
+
+fn color_at(mode: &FillMode, position: Point) -> Color {
+    mode.gradient
+        .map_or(mode.color, |gradient| gradient.color_at(position.to_vec2()))
+}
@@ -0,0 +1,301 @@
+//! Boolean operations between the filled regions of paths.
+//!
+//! Both operands are tessellated into triangles, which are then clipped
+//! against each other. The resulting paths are therefore made of
+//! non-overlapping convex polygons: they are meant to be filled, since stroking
+//! them would also outline the edges between the polygons.
+
+use lyon_tessellation::{
+    math::{Point, Rect},
+    path::{traits::PathBuilder, Path, Polygon},
+    BuffersBuilder, FillOptions, FillTessellator, VertexBuffers,
+};
+
+use crate::vertex::PositionConstructor;
+
+/// Pieces with a smaller area are considered numerical noise and discarded.
+const AREA_EPSILON: f32 = 1e-5;
+
+/// Returns the region covered by either `a` or `b`, both filled with
+/// `options`.
+pub fn union(a: &Path, b: &Path, options: &FillOptions) -> Path {
+    let a_triangles = triangles(a, options);
+    let b_pieces = difference_pieces(triangles(b, options), &a_triangles);
+    let pieces = a_triangles.into_iter().chain(b_pieces);
+
+    build(pieces)
+}
+
+/// Returns the region covered by both `a` and `b`, both filled with `options`.
+pub fn intersection(a: &Path, b: &Path, options: &FillOptions) -> Path {
+    let b_triangles = triangles(b, options);
+    let mut pieces = Vec::new();
+    for a_triangle in triangles(a, options) {
+        for b_triangle in &b_triangles {
+            let piece = intersect(&a_triangle, b_triangle);
+            if area(&piece) > AREA_EPSILON {
+                pieces.push(piece);
+            }
+        }
+    }
+
+    build(pieces)
+}
+
+/// Returns the region covered by `a` but not by `b`, both filled with
+/// `options`.
+pub fn difference(a: &Path, b: &Path, options: &FillOptions) -> Path {
+    build(difference_pieces(
+        triangles(a, options),
+        &triangles(b, options),
+    ))
+}
+
+/// Returns the region covered by any of the `triangles`, which may overlap.
+pub fn merge_triangles(triangles: impl IntoIterator<Item = Vec<Point>>) -> Path {
+    let triangles = triangles
+        .into_iter()
+        .filter_map(counter_clockwise)
+        .collect::<Vec<_>>();
+    let bounds = triangles.iter().map(Rect::from_points).collect::<Vec<_>>();
+
+    let mut pieces = Vec::new();
+    for (i, triangle) in triangles.iter().enumerate() {
+        // Only the earlier triangles around this one may cover part of it.
+        let earlier = triangles[..i]
+            .iter()
+            .zip(&bounds)
+            .filter(|(_, earlier_bounds)| earlier_bounds.intersects(&bounds[i]))
+            .map(|(earlier, _)| earlier.clone())
+            .collect::<Vec<_>>();
+        pieces.extend(difference_pieces(vec![triangle.clone()], &earlier));
+    }
+
+    build(pieces)
+}
+
+/// Subtracts each of the `subtrahends` from each of the `pieces`.
+fn difference_pieces(mut pieces: Vec<Vec<Point>>, subtrahends: &[Vec<Point>]) -> Vec<Vec<Point>> {
+    for subtrahend in subtrahends {
+        pieces = pieces
+            .iter()
+            .flat_map(|piece| subtract(piece, subtrahend))
+            .collect();
+    }
+
+    pieces
+}
+
+/// Returns the intersection of the convex polygons `a` and `b`.
+fn intersect(a: &[Point], b: &[Point]) -> Vec<Point> {
+    let mut piece = a.to_vec();
+    for i in 0..b.len() {
+        piece = clip(&piece, b[i], b[(i + 1) % b.len()]);
+    }
+
+    piece
+}
+
+/// Subtracts the convex polygon `b` from the convex polygon `a`, returning the
+/// convex pieces of the result.
+fn subtract(a: &[Point], b: &[Point]) -> Vec<Vec<Point>> {
+    let mut pieces = Vec::new();
+    let mut remaining = a.to_vec();
+    for i in 0..b.len() {
+        let (from, to) = (b[i], b[(i + 1) % b.len()]);
+        // The part of `remaining` outside of this edge is outside of `b`.
+        let outside = clip(&remaining, to, from);
+        if area(&outside) > AREA_EPSILON {
+            pieces.push(outside);
+        }
+        remaining = clip(&remaining, from, to);
+        if area(&remaining) <= AREA_EPSILON {
+            break;
+        }
+    }
+
+    pieces
+}
+
+/// Clips a convex polygon, keeping the part on the left of the line going
+/// from `a` to `b`.
+fn clip(polygon: &[Point], a: Point, b: Point) -> Vec<Point> {
+    let side = |p: Point| (b - a).cross(p - a);
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, &current) in polygon.iter().enumerate() {
+        let previous = polygon[(i + polygon.len() - 1) % polygon.len()];
+        let (current_side, previous_side) = (side(current), side(previous));
+        let crossing = || previous.lerp(current, previous_side / (previous_side - current_side));
+
+        if current_side >= 0.0 {
+            if previous_side < 0.0 {
+                clipped.push(crossing());
+            }
+            clipped.push(current);
+        } else if previous_side > 0.0 {
+            clipped.push(crossing());
+        }
+    }
+
+    clipped
+}
+
+/// Returns the signed area of a polygon, positive if counter-clockwise.
+fn area(polygon: &[Point]) -> f32 {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+
+    let twice_area: f32 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.to_vector().cross(b.to_vector()))
+        .sum();
+
+    twice_area / 2.0
+}
+
+/// Tessellates the fill of a path into counter-clockwise triangles.
+fn triangles(path: &Path, options: &FillOptions) -> Vec<Vec<Point>> {
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    // An empty result is fine: the operation just sees an empty region.
+    let _ = FillTessellator::new().tessellate_path(
+        path,
+        options,
+        &mut BuffersBuilder::new(&mut buffers, PositionConstructor),
+    );
+
+    buffers
+        .indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            triangle
+                .iter()
+                .map(|&i| buffers.vertices[i as usize])
+                .collect()
+        })
+        .filter_map(counter_clockwise)
+        .collect()
+}
+
+/// Orients a triangle counter-clockwise, or returns `None` if it is degenerate.
+fn counter_clockwise(mut triangle: Vec<Point>) -> Option<Vec<Point>> {
+    let area = area(&triangle);
+    if area < 0.0 {
+        triangle.reverse();
+    }
+
+    if area.abs() > AREA_EPSILON {
+        Some(triangle)
+    } else {
+        None
+    }
+}
+
+fn build(pieces: impl IntoIterator<Item = Vec<Point>>) -> Path {
+    let mut builder = Path::builder();
+    for piece in pieces {
+        builder.add_polygon(Polygon {
+            points: &piece,
+            closed: true,
+        });
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_tessellation::{
+        math::{point, Rect},
+        path::{path::Builder, PathEvent, Winding},
+    };
+
+    use super::*;
+
+    fn rectangle(builder: &mut Builder, min: Point, max: Point) {
+        builder.add_rectangle(&Rect::from_points([min, max]), Winding::Positive);
+    }
+
+    /// Returns the area covered by the non-overlapping pieces of a path.
+    fn total_area(path: &Path) -> f32 {
+        let mut points = Vec::new();
+        let mut total = 0.0;
+        for event in path {
+            match event {
+                PathEvent::Begin { at } => points = vec![at],
+                PathEvent::Line { to, .. } => points.push(to),
+                PathEvent::End { .. } => total += area(&points),
+                _ => unreachable!("boolean operations only produce polygons"),
+            }
+        }
+
+        total
+    }
+
+    /// Returns the corners of the box bounding the points of a path.
+    fn bounds(path: &Path) -> (Point, Point) {
+        path.iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .fold(
+                (point(f32::MAX, f32::MAX), point(f32::MIN, f32::MIN)),
+                |(min, max), p| (min.min(p), max.max(p)),
+            )
+    }
+
+    #[test]
+    fn union_covers_both_rectangles() {
+        let mut a = Path::builder();
+        rectangle(&mut a, point(0.0, 0.0), point(20.0, 10.0));
+        let mut b = Path::builder();
+        rectangle(&mut b, point(15.0, 0.0), point(35.0, 10.0));
+
+        let union = union(&a.build(), &b.build(), &FillOptions::default());
+        assert!((total_area(&union) - 350.0).abs() < 1e-3);
+        let (min, max) = bounds(&union);
+        assert!((min - point(0.0, 0.0)).length() < 1e-4);
+        assert!((max - point(35.0, 10.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn disjoint_paths_have_an_empty_intersection() {
+        let mut a = Path::builder();
+        rectangle(&mut a, point(0.0, 0.0), point(10.0, 10.0));
+        let mut b = Path::builder();
+        rectangle(&mut b, point(20.0, 0.0), point(30.0, 10.0));
+
+        let intersection = intersection(&a.build(), &b.build(), &FillOptions::default());
+        assert_eq!(intersection.iter().count(), 0);
+    }
+
+    #[test]
+    fn a_path_minus_itself_is_empty() {
+        let mut a = Path::builder();
+        rectangle(&mut a, point(0.0, 0.0), point(10.0, 10.0));
+        let a = a.build();
+
+        let difference = difference(&a, &a, &FillOptions::default());
+        assert_eq!(difference.iter().count(), 0);
+    }
+
+    #[test]
+    fn operands_are_filled_with_the_given_options() {
+        // Two nested squares wound the same way: the inner one is a hole only
+        // with the even-odd fill rule.
+        let mut nested = Path::builder();
+        rectangle(&mut nested, point(0.0, 0.0), point(10.0, 10.0));
+        rectangle(&mut nested, point(2.0, 2.0), point(8.0, 8.0));
+        let nested = nested.build();
+        let empty = Path::new();
+
+        let even_odd = union(&nested, &empty, &FillOptions::even_odd());
+        let non_zero = union(&nested, &empty, &FillOptions::non_zero());
+        assert!((total_area(&even_odd) - 64.0).abs() < 1e-3);
+        assert!((total_area(&non_zero) - 100.0).abs() < 1e-3);
+    }
+}
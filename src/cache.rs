@@ -0,0 +1,317 @@
+//! Sharing of meshes between identical shapes.
+//!
+//! Many games spawn lots of shapes with the same geometry and draw mode, like
+//! bullets or tiles. The [`ShapeMeshCache`] resource allows those shapes to
+//! share a single `Mesh` asset instead of being tessellated one by one.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use bevy::{
+    asset::{Handle, HandleId},
+    ecs::entity::Entity,
+    render::{color::Color, mesh::Mesh},
+    utils::HashMap,
+};
+use lyon_tessellation::{
+    math::Point,
+    path::{Path as LyonPath, PathEvent},
+    FillOptions, FillRule, Orientation, StrokeOptions,
+};
+
+use crate::{
+    draw::{DrawMode, FillMode, Gradient, StrokeGradient, StrokeMode},
+    entity::Path,
+    plugin::MeshConfig,
+};
+
+/// A resource that stores the meshes generated for each combination of path
+/// and draw mode, so that identical shapes share the same `Mesh` asset.
+///
+/// While the cache is enabled, identical shapes end up with equal
+/// `Mesh2dHandle`s and differ only by their `Transform`, including when they
+/// are spawned in the same frame. In that case, only one of them is
+/// tessellated.
+///
+/// The cache is disabled by default. It counts the entities using each of its
+/// meshes, and drops a mesh once they have all been despawned or meshed again
+/// with another one, so changing shapes don't pile up stale meshes.
+#[derive(Default)]
+pub struct ShapeMeshCache {
+    /// Whether the cache is used when meshing shapes.
+    pub enabled: bool,
+    meshes: HashMap<ShapeKey, Handle<Mesh>>,
+    /// The key of each cached mesh, and the number of entities using it.
+    shared: HashMap<HandleId, (ShapeKey, usize)>,
+    /// The cached mesh used by each entity.
+    users: HashMap<Entity, HandleId>,
+}
+
+impl ShapeMeshCache {
+    /// Returns a new, enabled cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of meshes stored in the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Returns `true` if the cache contains no meshes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+
+    /// Removes all the meshes from the cache, and forgets which shapes share
+    /// them.
+    ///
+    /// The meshes are freed as soon as no shape uses them anymore. Since they
+    /// are no longer known to be shared, a shape still using one of them that
+    /// is meshed again outside of the cache, for example after adding a
+    /// [`TargetMesh`](crate::entity::TargetMesh), may overwrite it in place.
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+        self.shared.clear();
+        self.users.clear();
+    }
+
+    /// Returns `true` if the given mesh has been handed out by the cache, and
+    /// may therefore be used by more than one shape.
+    #[must_use]
+    pub fn is_shared(&self, handle: &Handle<Mesh>) -> bool {
+        self.shared.contains_key(&handle.id)
+    }
+
+    /// Records that `entity` now uses the mesh `id`, releasing the mesh it used
+    /// before.
+    pub(crate) fn track(&mut self, entity: Entity, id: HandleId) {
+        if self.users.get(&entity) == Some(&id) {
+            return;
+        }
+        self.release(entity);
+        if let Some((_, users)) = self.shared.get_mut(&id) {
+            *users += 1;
+            self.users.insert(entity, id);
+        }
+    }
+
+    /// Records that `entity` no longer uses a mesh, dropping the mesh it used
+    /// if it was the last entity to do so.
+    pub(crate) fn release(&mut self, entity: Entity) {
+        let id = match self.users.remove(&entity) {
+            Some(id) => id,
+            None => return,
+        };
+        if let Some((key, users)) = self.shared.get_mut(&id) {
+            *users -= 1;
+            if *users == 0 {
+                self.meshes.remove(key);
+                self.shared.remove(&id);
+            }
+        }
+    }
+
+    /// Returns the cached mesh for the given key, if any.
+    pub(crate) fn get(&self, key: &ShapeKey) -> Option<Handle<Mesh>> {
+        self.meshes.get(key).cloned()
+    }
+
+    /// Stores a mesh in the cache and returns its handle.
+    pub(crate) fn insert(&mut self, key: ShapeKey, handle: Handle<Mesh>) -> Handle<Mesh> {
+        self.shared.insert(handle.id, (key.clone(), 0));
+        self.meshes.insert(key, handle.clone());
+        handle
+    }
+
+    /// Computes the cache key for a shape.
+    ///
+    /// The key covers everything that ends up in the mesh: the path, the draw
+    /// mode (including colors, since they are stored per vertex), the depth
+    /// and the mesh configuration.
+    pub(crate) fn key(path: &Path, mode: &DrawMode, z: f32, config: &MeshConfig) -> ShapeKey {
+        let mut writer = KeyWriter::default();
+        hash_path(&path.0, &mut writer);
+        hash_draw_mode(mode, &mut writer);
+        z.to_bits().hash(&mut writer);
+        config.hash(&mut writer);
+
+        ShapeKey(writer.0)
+    }
+}
+
+/// The key of a mesh in the [`ShapeMeshCache`].
+///
+/// It holds the bytes of everything that is hashed to describe the shape,
+/// rather than their hash, so that shapes whose hashes collide never share a
+/// mesh.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ShapeKey(Vec<u8>);
+
+/// A hasher that records the bytes written to it, to build a [`ShapeKey`].
+#[derive(Default)]
+struct KeyWriter(Vec<u8>);
+
+impl Hasher for KeyWriter {
+    fn finish(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+fn hash_point(point: Point, hasher: &mut impl Hasher) {
+    point.x.to_bits().hash(hasher);
+    point.y.to_bits().hash(hasher);
+}
+
+fn hash_color(color: Color, hasher: &mut impl Hasher) {
+    color.as_linear_rgba_u32().hash(hasher);
+}
+
+fn hash_path(path: &LyonPath, hasher: &mut impl Hasher) {
+    for event in path.iter() {
+        match event {
+            PathEvent::Begin { at } => {
+                0_u8.hash(hasher);
+                hash_point(at, hasher);
+            }
+            PathEvent::Line { to, .. } => {
+                1_u8.hash(hasher);
+                hash_point(to, hasher);
+            }
+            PathEvent::Quadratic { ctrl, to, .. } => {
+                2_u8.hash(hasher);
+                hash_point(ctrl, hasher);
+                hash_point(to, hasher);
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => {
+                3_u8.hash(hasher);
+                hash_point(ctrl1, hasher);
+                hash_point(ctrl2, hasher);
+                hash_point(to, hasher);
+            }
+            PathEvent::End { close, .. } => {
+                4_u8.hash(hasher);
+                close.hash(hasher);
+            }
+        }
+    }
+}
+
+fn hash_draw_mode(mode: &DrawMode, hasher: &mut impl Hasher) {
+    match mode {
+        DrawMode::Fill(fill_mode) => {
+            0_u8.hash(hasher);
+            hash_fill_mode(fill_mode, hasher);
+        }
+        DrawMode::Stroke(stroke_mode) => {
+            1_u8.hash(hasher);
+            hash_stroke_mode(stroke_mode, hasher);
+        }
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => {
+            2_u8.hash(hasher);
+            hash_fill_mode(fill_mode, hasher);
+            hash_stroke_mode(outline_mode, hasher);
+        }
+        DrawMode::Wireframe(fill_mode) => {
+            3_u8.hash(hasher);
+            hash_fill_mode(fill_mode, hasher);
+        }
+    }
+}
+
+fn hash_fill_mode(mode: &FillMode, hasher: &mut impl Hasher) {
+    hash_fill_options(&mode.options, hasher);
+    mode.antialiasing.map(f32::to_bits).hash(hasher);
+    mode.closing.hash(hasher);
+    mode.delaunay_refinement.hash(hasher);
+    match mode.gradient {
+        None => {
+            0_u8.hash(hasher);
+            hash_color(mode.color, hasher);
+        }
+        Some(Gradient::Linear(gradient)) => {
+            1_u8.hash(hasher);
+            gradient.start.to_array().map(f32::to_bits).hash(hasher);
+            gradient.end.to_array().map(f32::to_bits).hash(hasher);
+            hash_color(gradient.start_color, hasher);
+            hash_color(gradient.end_color, hasher);
+        }
+        Some(Gradient::Radial(gradient)) => {
+            2_u8.hash(hasher);
+            gradient.center.to_array().map(f32::to_bits).hash(hasher);
+            gradient.radius.to_bits().hash(hasher);
+            hash_color(gradient.inner_color, hasher);
+            hash_color(gradient.outer_color, hasher);
+        }
+        Some(Gradient::Custom(color_at)) => {
+            3_u8.hash(hasher);
+            (color_at as usize).hash(hasher);
+        }
+    }
+}
+
+fn hash_stroke_mode(mode: &StrokeMode, hasher: &mut impl Hasher) {
+    hash_stroke_options(&mode.options, hasher);
+    hash_color(mode.color, hasher);
+    match mode.dash {
+        None => 0_u8.hash(hasher),
+        Some(dash) => {
+            1_u8.hash(hasher);
+            [dash.dash, dash.gap, dash.offset]
+                .map(f32::to_bits)
+                .hash(hasher);
+        }
+    }
+    match mode.gradient {
+        None => 0_u8.hash(hasher),
+        Some(StrokeGradient::Linear {
+            start_color,
+            end_color,
+        }) => {
+            1_u8.hash(hasher);
+            hash_color(start_color, hasher);
+            hash_color(end_color, hasher);
+        }
+        Some(StrokeGradient::Custom(color_at)) => {
+            2_u8.hash(hasher);
+            (color_at as usize).hash(hasher);
+        }
+    }
+    mode.alignment.hash(hasher);
+    mode.behind_fill.hash(hasher);
+}
+
+fn hash_fill_options(options: &FillOptions, hasher: &mut impl Hasher) {
+    options.tolerance.to_bits().hash(hasher);
+    matches!(options.fill_rule, FillRule::EvenOdd).hash(hasher);
+    matches!(options.sweep_orientation, Orientation::Vertical).hash(hasher);
+    options.handle_intersections.hash(hasher);
+}
+
+fn hash_stroke_options(options: &StrokeOptions, hasher: &mut impl Hasher) {
+    (options.start_cap as u8).hash(hasher);
+    (options.end_cap as u8).hash(hasher);
+    (options.line_join as u8).hash(hasher);
+    options.line_width.to_bits().hash(hasher);
+    options.miter_limit.to_bits().hash(hasher);
+    options.tolerance.to_bits().hash(hasher);
+}
@@ -0,0 +1,117 @@
+//! Conversion of shapes to physics colliders.
+//!
+//! This module doesn't depend on any physics engine. [`Path::to_collider_points`]
+//! returns the outline of a shape as rings of points, and a
+//! [`ColliderBackend`] turns them into the collider of a given engine, for
+//! example a polyline or a convex decomposition.
+
+use bevy::math::Vec2;
+use lyon_tessellation::FillOptions;
+
+use crate::{convert::ToVec2, entity::Path, utils::flatten};
+
+/// The points of a sub-path of a shape, flattened to line segments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColliderRing {
+    /// The points of the sub-path, in the order it goes through them. The
+    /// first point of a closed ring is not repeated at its end.
+    pub points: Vec<Vec2>,
+    /// Whether the last point is connected back to the first one.
+    pub closed: bool,
+}
+
+/// Builds the collider of a physics engine from the rings of a shape.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// /// A backend building a list of segments, like a polyline collider.
+/// struct Segments;
+///
+/// impl ColliderBackend for Segments {
+///     type Collider = Vec<(Vec2, Vec2)>;
+///
+///     fn build_collider(rings: &[ColliderRing]) -> Self::Collider {
+///         let mut segments = Vec::new();
+///         for ring in rings {
+///             segments.extend(ring.points.windows(2).map(|pair| (pair[0], pair[1])));
+///             if let (true, Some(&first), Some(&last)) =
+///                 (ring.closed, ring.points.first(), ring.points.last())
+///             {
+///                 segments.push((last, first));
+///             }
+///         }
+///         segments
+///     }
+/// }
+///
+/// let square = ShapePath::build_as(&shapes::Rectangle::default());
+/// assert_eq!(square.to_collider::<Segments>().len(), 4);
+/// ```
+pub trait ColliderBackend {
+    /// The collider type of the physics engine.
+    type Collider;
+
+    /// Builds a collider from the rings returned by
+    /// [`Path::to_collider_points`].
+    fn build_collider(rings: &[ColliderRing]) -> Self::Collider;
+}
+
+impl Path {
+    /// Returns a ring of points for each sub-path of the shape, with curves
+    /// approximated with the default tessellation tolerance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let points = vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0)];
+    /// let triangle = ShapePath::build_as(&shapes::Polygon {
+    ///     points: points.clone(),
+    ///     closed: true,
+    /// });
+    ///
+    /// assert_eq!(
+    ///     triangle.to_collider_points(),
+    ///     vec![ColliderRing {
+    ///         points,
+    ///         closed: true
+    ///     }],
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_collider_points(&self) -> Vec<ColliderRing> {
+        flatten(&self.0, FillOptions::DEFAULT_TOLERANCE)
+            .into_iter()
+            .map(|sub_path| {
+                let mut points = sub_path
+                    .points
+                    .iter()
+                    .map(|point| point.to_vec2())
+                    .collect::<Vec<_>>();
+                // Sub-paths going back to their start before closing would
+                // otherwise give a zero length segment.
+                if sub_path.closed && points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+
+                ColliderRing {
+                    points,
+                    closed: sub_path.closed,
+                }
+            })
+            .collect()
+    }
+
+    /// Builds the collider of the shape with the given backend, from the
+    /// rings returned by [`to_collider_points`](Self::to_collider_points).
+    #[must_use]
+    pub fn to_collider<B: ColliderBackend>(&self) -> B::Collider {
+        B::build_collider(&self.to_collider_points())
+    }
+}
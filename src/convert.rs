@@ -0,0 +1,95 @@
+//! Conversions between Bevy and lyon vector types.
+//!
+//! Geometries are built with the points and vectors of lyon, while the rest of
+//! a Bevy app uses `Vec2`. The orphan rules prevent implementing `From`
+//! between them in this crate, so these traits and functions do the
+//! conversions instead. They are part of the [`prelude`](crate::prelude).
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_prototype_lyon::prelude::*;
+//! #
+//! let triangle = ShapePath::build_as(&shapes::Polygon {
+//!     points: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+//!     closed: true,
+//! });
+//!
+//! // The lyon path of the shape stores its vertices as `Point`s.
+//! let vertices = triangle
+//!     .0
+//!     .iter()
+//!     .map(|event| event.to().to_vec2())
+//!     .collect::<Vec<_>>();
+//! assert_eq!(vertices[..3], [Vec2::ZERO, Vec2::X, Vec2::Y]);
+//! assert_eq!(triangle.0.iter().nth(1).map(|event| event.to()), Some(Vec2::X.to_point()));
+//! ```
+
+use bevy::math::Vec2;
+use lyon_tessellation::math::{Point, Vector};
+
+/// Converts a value to a lyon `Point`.
+pub trait ToPoint {
+    /// Returns the value as a lyon `Point`.
+    fn to_point(self) -> Point;
+}
+
+/// Converts a value to a lyon `Vector`.
+pub trait ToVector {
+    /// Returns the value as a lyon `Vector`.
+    fn to_vector(self) -> Vector;
+}
+
+/// Converts a value to a Bevy `Vec2`.
+pub trait ToVec2 {
+    /// Returns the value as a Bevy `Vec2`.
+    fn to_vec2(self) -> Vec2;
+}
+
+impl ToPoint for Vec2 {
+    fn to_point(self) -> Point {
+        Point::new(self.x, self.y)
+    }
+}
+
+impl ToVector for Vec2 {
+    fn to_vector(self) -> Vector {
+        Vector::new(self.x, self.y)
+    }
+}
+
+impl ToVec2 for Point {
+    fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl ToVec2 for Vector {
+    fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+/// Returns `v` as a lyon `Point`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::convert::{point, vec2};
+/// #
+/// let p = point(Vec2::new(1.0, 2.0));
+/// assert_eq!((p.x, p.y), (1.0, 2.0));
+/// assert_eq!(vec2(p), Vec2::new(1.0, 2.0));
+/// ```
+#[must_use]
+pub fn point(v: Vec2) -> Point {
+    v.to_point()
+}
+
+/// Returns the lyon point `p` as a Bevy `Vec2`.
+#[must_use]
+pub fn vec2(p: Point) -> Vec2 {
+    p.to_vec2()
+}
@@ -1,10 +1,17 @@
 //! Types for defining shape color and options.
 
-use bevy::{ecs::component::Component, render::color::Color};
-use lyon_tessellation::{FillOptions, StrokeOptions};
+use bevy::{ecs::component::Component, math::Vec2, render::color::Color};
+use lyon_tessellation::{
+    math::Point,
+    path::{traits::PathBuilder, Path, Polygon},
+    FillOptions, FillRule, LineCap, LineJoin, Orientation, StrokeOptions,
+};
+
+use crate::utils::flatten;
 
 /// Determines how a shape will be drawn.
 #[derive(Debug, Clone, Copy, PartialEq, Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawMode {
     /// The shape will be filled using the provided [`FillMode`].
     Fill(FillMode),
@@ -17,15 +24,71 @@ pub enum DrawMode {
         /// Properties about the outline.
         outline_mode: StrokeMode,
     },
+    /// The triangles generated by filling the shape with the provided
+    /// [`FillMode`] are drawn as lines, to inspect the tessellation.
+    Wireframe(FillMode),
+}
+
+/// The tessellation options of a [`FillMode`] or [`StrokeMode`] that are set
+/// by the mode itself, rather than by the
+/// [`TessellationSettings`](crate::plugin::TessellationSettings).
+///
+/// The builder methods setting these options mark them here. Options changed
+/// directly on the `options` of a mode are replaced by the settings unless
+/// they are marked too.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionOverrides {
+    /// Whether the mode sets its tolerance.
+    pub tolerance: bool,
+    /// Whether the mode sets its fill rule. Only used by fills.
+    pub fill_rule: bool,
+    /// Whether the mode sets whether intersections are handled. Only used by
+    /// fills.
+    pub handle_intersections: bool,
+}
+
+impl OptionOverrides {
+    /// No option is overridden, they all come from the settings.
+    pub const NONE: Self = Self {
+        tolerance: false,
+        fill_rule: false,
+        handle_intersections: false,
+    };
 }
 
 /// Defines the fill options for the lyon tessellator and color of the generated
 /// vertices.
+///
+/// If a `gradient` is set, it is used to color the vertices in place of
+/// `color`. If `antialiasing` is set, the edges of the fill fade out over that
+/// width, see [`with_antialiasing`](Self::with_antialiasing). `closing` sets
+/// how open sub-paths are filled, see [`with_closing`](Self::with_closing). If
+/// a `palette_index` is set, the color is looked up in the [`Palette`], see
+/// [`indexed`](Self::indexed). `delaunay_refinement` improves the shape of the
+/// triangles, see [`with_delaunay_refinement`](Self::with_delaunay_refinement).
+/// `overrides` marks the `options` that are not replaced by the
+/// [`TessellationSettings`](crate::plugin::TessellationSettings).
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FillMode {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::fill_options"))]
     pub options: FillOptions,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
     pub color: Color,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gradient: Option<Gradient>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialiasing: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub closing: FillClosing,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub palette_index: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub delaunay_refinement: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overrides: OptionOverrides,
 }
 
 impl FillMode {
@@ -35,17 +98,424 @@ impl FillMode {
         Self {
             options: FillOptions::default(),
             color,
+            gradient: None,
+            antialiasing: None,
+            closing: FillClosing::CloseStraight,
+            palette_index: None,
+            delaunay_refinement: false,
+            overrides: OptionOverrides::NONE,
+        }
+    }
+
+    /// Convenience constructor requiring only a [`LinearGradient`].
+    #[must_use]
+    pub fn linear_gradient(gradient: LinearGradient) -> Self {
+        Self {
+            options: FillOptions::default(),
+            color: gradient.end_color,
+            gradient: Some(Gradient::Linear(gradient)),
+            antialiasing: None,
+            closing: FillClosing::CloseStraight,
+            palette_index: None,
+            delaunay_refinement: false,
+            overrides: OptionOverrides::NONE,
+        }
+    }
+
+    /// Convenience constructor requiring only a [`RadialGradient`].
+    #[must_use]
+    pub fn radial_gradient(gradient: RadialGradient) -> Self {
+        Self {
+            options: FillOptions::default(),
+            color: gradient.outer_color,
+            gradient: Some(Gradient::Radial(gradient)),
+            antialiasing: None,
+            closing: FillClosing::CloseStraight,
+            palette_index: None,
+            delaunay_refinement: false,
+            overrides: OptionOverrides::NONE,
+        }
+    }
+
+    /// Convenience constructor requiring only a function that returns the
+    /// color of each vertex from its position.
+    ///
+    /// The function is called on the vertices generated by the tessellator,
+    /// so it also colors the vertices that aren't part of the path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// // Left half red, right half blue.
+    /// let mode = FillMode::per_vertex(|position| {
+    ///     if position.x < 0.0 {
+    ///         Color::RED
+    ///     } else {
+    ///         Color::BLUE
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn per_vertex(color_at: fn(Vec2) -> Color) -> Self {
+        Self {
+            options: FillOptions::default(),
+            color: Color::WHITE,
+            gradient: Some(Gradient::Custom(color_at)),
+            antialiasing: None,
+            closing: FillClosing::CloseStraight,
+            palette_index: None,
+            delaunay_refinement: false,
+            overrides: OptionOverrides::NONE,
+        }
+    }
+
+    /// Convenience constructor requiring only the index of a color of the
+    /// [`Palette`] resource.
+    ///
+    /// The color is looked up when the shape is tessellated, and shapes are
+    /// tessellated again when the palette changes, so that the colors of a
+    /// whole scene can be swapped by editing the palette. Indices missing from
+    /// the palette fill the shape in white.
+    #[must_use]
+    pub fn indexed(index: u8) -> Self {
+        Self {
+            palette_index: Some(index),
+            ..Self::color(Color::WHITE)
+        }
+    }
+
+    /// Sets the tessellation tolerance, that is the maximum distance allowed
+    /// between a curve and the line segments approximating it.
+    ///
+    /// Smaller values make curves smoother, at the cost of generating more
+    /// vertices and triangles. Since the tolerance is measured in the local
+    /// space of the shape, scaled up shapes may need a smaller one.
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.options.tolerance = tolerance;
+        self.overrides.tolerance = true;
+        self
+    }
+
+    /// Adds a translucent ring of vertices around the fill, fading from the
+    /// fill color to transparent over `width`, to smooth its edges without
+    /// relying on MSAA.
+    ///
+    /// The width is measured in the local space of the shape, so a width of
+    /// `1.0` makes a one pixel wide fade for shapes that are not scaled. The
+    /// fade starts at the edge of the fill and extends outwards.
+    #[must_use]
+    pub const fn with_antialiasing(mut self, width: f32) -> Self {
+        self.antialiasing = Some(width);
+        self
+    }
+
+    /// Flips the edges of the triangles of the fill until they are Delaunay,
+    /// as done by [`refine_delaunay`](crate::vertex::refine_delaunay).
+    ///
+    /// The fill of concave shapes may have sliver triangles, which make
+    /// per-vertex colors like gradients interpolate unevenly. Refining keeps
+    /// the vertices and the outline of the fill, at the cost of some meshing
+    /// time.
+    #[must_use]
+    pub const fn with_delaunay_refinement(mut self) -> Self {
+        self.delaunay_refinement = true;
+        self
+    }
+
+    /// Sets the rule deciding which parts of the path are inside the shape.
+    ///
+    /// With [`FillRule::EvenOdd`], the default, regions enclosed an even
+    /// number of times are left empty, so the center of a self-intersecting
+    /// star or a sub-path drawn inside another one becomes a hole regardless
+    /// of the direction of the sub-paths. With [`FillRule::NonZero`], every
+    /// enclosed region is filled, unless the sub-paths around it wind in
+    /// opposite directions.
+    #[must_use]
+    pub const fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.options.fill_rule = fill_rule;
+        self.overrides.fill_rule = true;
+        self
+    }
+
+    /// Sets the direction in which the tessellator sweeps the path.
+    ///
+    /// The default, [`Orientation::Vertical`], works for any shape. Changing it
+    /// only affects how the fill is split into triangles.
+    #[must_use]
+    pub const fn with_sweep_orientation(mut self, orientation: Orientation) -> Self {
+        self.options.sweep_orientation = orientation;
+        self
+    }
+
+    /// Sets whether the tessellator looks for edges crossing each other, which
+    /// is the default.
+    ///
+    /// Paths known not to self-intersect, nor to have overlapping sub-paths,
+    /// can skip it to be tessellated faster. Other paths, like messy imported
+    /// geometry, then fail to tessellate or produce a wrong fill.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::plugin::tessellate_fill;
+    /// #
+    /// let bowtie = ShapePath::build_as(&shapes::Polygon {
+    ///     points: vec![
+    ///         Vec2::ZERO,
+    ///         Vec2::new(100.0, 100.0),
+    ///         Vec2::new(100.0, 0.0),
+    ///         Vec2::new(0.0, 100.0),
+    ///     ],
+    ///     closed: true,
+    /// });
+    /// let handled = tessellate_fill(&bowtie, &FillMode::color(Color::RED));
+    /// let ignored = tessellate_fill(
+    ///     &bowtie,
+    ///     &FillMode::color(Color::RED).with_intersections(false),
+    /// );
+    ///
+    /// // The crossing point of the two edges is only added when intersections
+    /// // are handled.
+    /// assert_ne!(handled.count_vertices(), ignored.count_vertices());
+    /// ```
+    #[must_use]
+    pub const fn with_intersections(mut self, handle_intersections: bool) -> Self {
+        self.options.handle_intersections = handle_intersections;
+        self.overrides.handle_intersections = true;
+        self
+    }
+
+    /// Sets how the open sub-paths of the path are filled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::plugin::tessellate_fill;
+    /// #
+    /// let corner = ShapePath::build_as(&shapes::Polygon {
+    ///     points: vec![Vec2::new(0.0, 100.0), Vec2::ZERO, Vec2::new(100.0, 0.0)],
+    ///     closed: false,
+    /// });
+    ///
+    /// let mode = FillMode::color(Color::RED).with_closing(FillClosing::CloseStraight);
+    /// let mesh = tessellate_fill(&corner, &mode);
+    /// assert_eq!(mesh.indices().unwrap().len(), 3);
+    ///
+    /// let mode = FillMode::color(Color::RED).with_closing(FillClosing::CloseNone);
+    /// let mesh = tessellate_fill(&corner, &mode);
+    /// assert_eq!(mesh.indices().unwrap().len(), 0);
+    /// ```
+    #[must_use]
+    pub const fn with_closing(mut self, closing: FillClosing) -> Self {
+        self.closing = closing;
+        self
+    }
+}
+
+/// How the open sub-paths of a path are filled.
+///
+/// Closed sub-paths are always filled as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillClosing {
+    /// Open sub-paths are closed by a straight line from their last point to
+    /// their first one, which is what lyon does.
+    CloseStraight,
+    /// Open sub-paths are not filled, and a warning is logged.
+    CloseNone,
+    /// Open sub-paths are replaced by their convex hull.
+    CloseConvexHull,
+}
+
+impl Default for FillClosing {
+    fn default() -> Self {
+        Self::CloseStraight
+    }
+}
+
+/// A resource holding the colors of the fills made with [`FillMode::indexed`].
+///
+/// # Example
+///
+/// ```
+/// # use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+/// # use bevy_prototype_lyon::{plugin::tessellate_fill, prelude::*, vertex::pack_color};
+/// #
+/// let square = ShapePath::build_as(&shapes::Rectangle::default());
+/// let mode = FillMode::indexed(1);
+/// let mut palette = Palette(vec![Color::BLACK, Color::RED]);
+/// let colors = |palette: &Palette| {
+///     match tessellate_fill(&square, &palette.resolve(mode)).attribute(Mesh::ATTRIBUTE_COLOR) {
+///         Some(VertexAttributeValues::Uint32(colors)) => colors.clone(),
+///         _ => unreachable!(),
+///     }
+/// };
+///
+/// assert!(colors(&palette).iter().all(|&c| c == pack_color(Color::RED)));
+/// palette.0[1] = Color::BLUE;
+/// assert!(colors(&palette).iter().all(|&c| c == pack_color(Color::BLUE)));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Palette(pub Vec<Color>);
+
+impl Palette {
+    /// Returns `mode` with the color at its `palette_index`, if it has one
+    /// and the palette holds it.
+    #[must_use]
+    pub fn resolve(&self, mode: FillMode) -> FillMode {
+        match mode.palette_index.and_then(|i| self.0.get(usize::from(i))) {
+            Some(&color) => FillMode {
+                color,
+                gradient: None,
+                ..mode
+            },
+            None => mode,
+        }
+    }
+}
+
+/// A color ramp used to fill a shape.
+///
+/// Gradients are sampled at each vertex of the mesh and then interpolated
+/// across triangles, so their accuracy depends on how the shape is
+/// tessellated. For example, the fill of a circle has no vertex at its center.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gradient {
+    Linear(LinearGradient),
+    Radial(RadialGradient),
+    /// Colors each vertex with the color returned by the function for its
+    /// position.
+    ///
+    /// Functions can't be serialized: serializing a mode using this variant
+    /// returns an error, and deserializing never produces it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(Vec2) -> Color),
+}
+
+impl Gradient {
+    /// Returns the color of the gradient at the given position.
+    #[must_use]
+    pub fn color_at(&self, position: Vec2) -> Color {
+        match self {
+            Self::Linear(gradient) => gradient.color_at(position),
+            Self::Radial(gradient) => gradient.color_at(position),
+            Self::Custom(color_at) => color_at(position),
         }
     }
 }
 
+/// A gradient going from `start_color` at `start` to `end_color` at `end`.
+///
+/// Points beyond the two ends of the gradient axis get the color of the
+/// nearest end.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearGradient {
+    pub start: Vec2,
+    pub end: Vec2,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+    pub start_color: Color,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+    pub end_color: Color,
+}
+
+impl LinearGradient {
+    /// Returns the color of the gradient at the given position, obtained by
+    /// projecting the position onto the gradient axis.
+    #[must_use]
+    pub fn color_at(&self, position: Vec2) -> Color {
+        let axis = self.end - self.start;
+        let length_squared = axis.length_squared();
+        if length_squared == 0.0 {
+            return self.end_color;
+        }
+
+        let t = ((position - self.start).dot(axis) / length_squared).clamp(0.0, 1.0);
+        lerp_linear(self.start_color, self.end_color, t)
+    }
+}
+
+/// A gradient going from `inner_color` at `center` to `outer_color` at
+/// `radius` distance from it.
+///
+/// Points farther than `radius` from the center get `outer_color`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadialGradient {
+    pub center: Vec2,
+    pub radius: f32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+    pub inner_color: Color,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+    pub outer_color: Color,
+}
+
+impl RadialGradient {
+    /// Returns the color of the gradient at the given position, based on its
+    /// distance from the center.
+    ///
+    /// If `radius` is zero, `outer_color` is returned everywhere.
+    #[must_use]
+    pub fn color_at(&self, position: Vec2) -> Color {
+        if self.radius <= 0.0 {
+            return self.outer_color;
+        }
+
+        let t = (position.distance(self.center) / self.radius).clamp(0.0, 1.0);
+        lerp_linear(self.inner_color, self.outer_color, t)
+    }
+}
+
+/// Interpolates two colors in linear space.
+fn lerp_linear(from: Color, to: Color, t: f32) -> Color {
+    let from = from.as_linear_rgba_f32();
+    let to = to.as_linear_rgba_f32();
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| (to[i] - from[i]).mul_add(t, from[i]));
+
+    Color::rgba_linear(r, g, b, a)
+}
+
 /// Defines the stroke options for the lyon tessellator and color of the
 /// generated vertices.
+///
+/// If a `dash` pattern is set, the path is split into dashes before being
+/// stroked. If a `gradient` is set, it is used to color the vertices in place
+/// of `color`. The `alignment` places the stroke relative to the path, and
+/// `behind_fill` draws it under the fill of [`DrawMode::Outlined`] shapes.
+/// `overrides` marks the `options` that are not replaced by the
+/// [`TessellationSettings`](crate::plugin::TessellationSettings).
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StrokeMode {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::stroke_options")
+    )]
     pub options: StrokeOptions,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
     pub color: Color,
+    pub dash: Option<DashPattern>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gradient: Option<StrokeGradient>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alignment: StrokeAlignment,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub behind_fill: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub overrides: OptionOverrides,
 }
 
 impl StrokeMode {
@@ -55,6 +525,11 @@ impl StrokeMode {
         Self {
             options: StrokeOptions::default().with_line_width(line_width),
             color,
+            dash: None,
+            gradient: None,
+            alignment: StrokeAlignment::Center,
+            behind_fill: false,
+            overrides: OptionOverrides::NONE,
         }
     }
 
@@ -64,6 +539,479 @@ impl StrokeMode {
         Self {
             options: StrokeOptions::default(),
             color,
+            dash: None,
+            gradient: None,
+            alignment: StrokeAlignment::Center,
+            behind_fill: false,
+            overrides: OptionOverrides::NONE,
         }
     }
+
+    /// Sets the tessellation tolerance. See [`FillMode::with_tolerance`].
+    ///
+    /// The tolerance of the stroke is independent of the one of the fill of
+    /// [`DrawMode::Outlined`] shapes.
+    #[must_use]
+    pub const fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.options.tolerance = tolerance;
+        self.overrides.tolerance = true;
+        self
+    }
+
+    /// Sets the shape of both ends of open sub-paths.
+    #[must_use]
+    pub const fn with_line_cap(mut self, cap: LineCap) -> Self {
+        self.options.start_cap = cap;
+        self.options.end_cap = cap;
+        self
+    }
+
+    /// Sets the shape of the start of open sub-paths, leaving their end as
+    /// is. Combined with [`with_end_cap`](Self::with_end_cap), this gives
+    /// arrows a flat tail and a round tip, for instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::plugin::tessellate_stroke;
+    /// #
+    /// let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+    /// let mode = StrokeMode::new(Color::BLACK, 10.0)
+    ///     .with_start_cap(LineCap::Butt)
+    ///     .with_end_cap(LineCap::Square);
+    /// let mesh = tessellate_stroke(&line, &mode);
+    ///
+    /// let xs = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+    ///     Some(VertexAttributeValues::Float32x3(positions)) => {
+    ///         positions.iter().map(|p| p[0]).collect::<Vec<_>>()
+    ///     }
+    ///     _ => unreachable!(),
+    /// };
+    /// // The butt start stops at the first point, the square end extends past
+    /// // the last one by half the line width.
+    /// assert_eq!(xs.iter().copied().fold(f32::INFINITY, f32::min), 0.0);
+    /// assert_eq!(xs.iter().copied().fold(f32::NEG_INFINITY, f32::max), 105.0);
+    /// ```
+    #[must_use]
+    pub const fn with_start_cap(mut self, cap: LineCap) -> Self {
+        self.options.start_cap = cap;
+        self
+    }
+
+    /// Sets the shape of the end of open sub-paths, leaving their start as
+    /// is. See [`with_start_cap`](Self::with_start_cap).
+    #[must_use]
+    pub const fn with_end_cap(mut self, cap: LineCap) -> Self {
+        self.options.end_cap = cap;
+        self
+    }
+
+    /// Sets the shape of the joins between two segments.
+    #[must_use]
+    pub const fn with_line_join(mut self, join: LineJoin) -> Self {
+        self.options.line_join = join;
+        self
+    }
+
+    /// Sets the limit ratio between the length of a miter join and the line
+    /// width. Sharper joins fall back to a bevel, so that they don't spike out
+    /// of thick strokes.
+    #[must_use]
+    pub const fn with_miter_limit(mut self, limit: f32) -> Self {
+        self.options.miter_limit = limit;
+        self
+    }
+
+    /// Makes the stroke dashed, following the given pattern.
+    #[must_use]
+    pub const fn with_dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+
+    /// Colors the stroke with a gradient along the length of the path.
+    #[must_use]
+    pub const fn with_gradient(mut self, gradient: StrokeGradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    /// Sets where the stroke is placed relative to the path.
+    #[must_use]
+    pub const fn with_alignment(mut self, alignment: StrokeAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Draws the outline of a [`DrawMode::Outlined`] shape under its fill
+    /// instead of over it. Combined with [`StrokeAlignment::Center`], this
+    /// hides the inner half of the outline.
+    #[must_use]
+    pub const fn behind_fill(mut self) -> Self {
+        self.behind_fill = true;
+        self
+    }
+}
+
+/// A color ramp along the length of a stroke.
+///
+/// Each vertex is colored from its arc length parameter, going from `0.0` at
+/// the start of the path to `1.0` at its end. The sub-paths are walked one
+/// after the other, so the ramp spans the whole path, and the gaps between
+/// dashes are not counted. Closed sub-paths are stroked as open ones starting
+/// and ending at their first point, where the two ends of the ramp meet, so
+/// that corner is not joined.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::export::export_shape_obj;
+/// #
+/// let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+/// let heat_map = StrokeGradient::Linear {
+///     start_color: Color::GREEN,
+///     end_color: Color::RED,
+/// };
+/// let mode = DrawMode::Stroke(StrokeMode::new(Color::BLACK, 4.0).with_gradient(heat_map));
+/// let mut obj = Vec::new();
+/// export_shape_obj(&line, &mode, &mut obj).unwrap();
+///
+/// let obj = String::from_utf8(obj).unwrap();
+/// for vertex in obj.lines().filter(|line| line.starts_with("v ")) {
+///     let values: Vec<f32> = vertex[2..]
+///         .split(' ')
+///         .map(|value| value.parse().unwrap())
+///         .collect();
+///     match values[0] {
+///         x if x == 0.0 => assert_eq!(values[3..], [0.0, 1.0, 0.0]),
+///         x if x == 100.0 => assert_eq!(values[3..], [1.0, 0.0, 0.0]),
+///         _ => {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeGradient {
+    /// Goes from `start_color` at the start of the path to `end_color` at its
+    /// end.
+    #[allow(missing_docs)]
+    Linear {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+        start_color: Color,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::color"))]
+        end_color: Color,
+    },
+    /// Colors each vertex with the color returned by the function for its arc
+    /// length parameter.
+    ///
+    /// Functions can't be serialized: serializing a mode using this variant
+    /// returns an error, and deserializing never produces it.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(f32) -> Color),
+}
+
+impl StrokeGradient {
+    /// Returns the color of the gradient at the given arc length parameter,
+    /// which is clamped to the `[0, 1]` range.
+    #[must_use]
+    pub fn color_at(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear {
+                start_color,
+                end_color,
+            } => lerp_linear(*start_color, *end_color, t),
+            Self::Custom(color_at) => color_at(t),
+        }
+    }
+}
+
+/// Where a stroke is placed relative to the path.
+///
+/// Only closed sub-paths have an inside and an outside, which are determined
+/// with the even-odd fill rule. Open sub-paths are always stroked centered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeAlignment {
+    /// The stroke is centered on the path.
+    Center,
+    /// The stroke lies inside of the path. Parts of the shape thinner than
+    /// the line width get covered by overlapping strokes.
+    Inside,
+    /// The stroke lies outside of the path, so the shape grows by the line
+    /// width on every side.
+    Outside,
+}
+
+impl Default for StrokeAlignment {
+    fn default() -> Self {
+        Self::Center
+    }
+}
+
+/// A repeating pattern of dashes and gaps along a stroke.
+///
+/// The pattern restarts at the beginning of each sub-path. Around closed
+/// sub-paths it wraps continuously, so a dash crossing the start of the
+/// sub-path is drawn as a single dash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DashPattern {
+    /// The length of each dash.
+    pub dash: f32,
+    /// The length of the gap between two dashes. A zero gap results in a solid
+    /// stroke.
+    pub gap: f32,
+    /// How far into the pattern the stroke starts.
+    pub offset: f32,
+}
+
+impl DashPattern {
+    /// Creates a pattern of dashes and gaps, starting with a dash.
+    #[must_use]
+    pub const fn new(dash: f32, gap: f32) -> Self {
+        Self {
+            dash,
+            gap,
+            offset: 0.0,
+        }
+    }
+
+    /// Splits each sub-path of `path` into open sub-paths, one for each dash.
+    /// Curves are approximated with line segments within `tolerance`.
+    #[must_use]
+    pub fn apply(&self, path: &Path, tolerance: f32) -> Path {
+        let mut builder = Path::builder();
+        if self.gap <= 0.0 {
+            builder.concatenate(&[path.as_slice()]);
+            return builder.build();
+        }
+        if self.dash <= 0.0 {
+            return builder.build();
+        }
+
+        let period = self.dash + self.gap;
+        let mut add_dash = |points: &mut Vec<Point>| {
+            if points.len() > 1 {
+                builder.add_polygon(Polygon {
+                    points: points.as_slice(),
+                    closed: false,
+                });
+            }
+            points.clear();
+        };
+
+        for sub_path in flatten(path, tolerance) {
+            let mut phase = self.offset.rem_euclid(period);
+            let mut dash_points = Vec::new();
+            // The first dash of a closed sub-path is held back in case the
+            // last one runs into it across the seam.
+            let holds_first_dash = sub_path.closed && phase < self.dash;
+            let mut first_dash: Option<Vec<Point>> = None;
+
+            for (from, to) in sub_path.segments() {
+                let length = (to - from).length();
+                let mut travelled = 0.0;
+
+                while travelled < length {
+                    let drawing = phase < self.dash;
+                    let phase_end = if drawing { self.dash } else { period };
+                    let to_phase_end = phase_end - phase;
+                    let remaining = length - travelled;
+                    let start = from.lerp(to, travelled / length);
+                    if to_phase_end <= remaining {
+                        travelled += to_phase_end;
+                        phase = phase_end;
+                    } else {
+                        travelled = length;
+                        phase += remaining;
+                    }
+                    let end = from.lerp(to, travelled / length);
+
+                    if drawing {
+                        if dash_points.is_empty() {
+                            dash_points.push(start);
+                        }
+                        dash_points.push(end);
+                        if phase >= self.dash {
+                            if holds_first_dash && first_dash.is_none() {
+                                first_dash = Some(std::mem::take(&mut dash_points));
+                            } else {
+                                add_dash(&mut dash_points);
+                            }
+                        }
+                    }
+                    if phase >= period {
+                        phase = 0.0;
+                    }
+                }
+            }
+
+            if let Some(mut first_dash) = first_dash {
+                if dash_points.is_empty() {
+                    add_dash(&mut first_dash);
+                } else {
+                    dash_points.extend(first_dash.into_iter().skip(1));
+                }
+            }
+            add_dash(&mut dash_points);
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_tessellation::{
+        path::PathEvent, BuffersBuilder, FillTessellator, FillVertex, VertexBuffers,
+    };
+
+    use super::*;
+
+    fn sub_path_count(path: &Path) -> usize {
+        path.iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }))
+            .count()
+    }
+
+    fn line(length: f32) -> Path {
+        let mut builder = Path::builder();
+        builder.begin(Point::new(0.0, 0.0));
+        builder.line_to(Point::new(length, 0.0));
+        builder.end(false);
+        builder.build()
+    }
+
+    #[test]
+    fn dashes_split_a_straight_line() {
+        let line = line(10.0);
+
+        // Dashes start at 0, 3, 6 and 9, the last one being cut short.
+        assert_eq!(
+            sub_path_count(&DashPattern::new(2.0, 1.0).apply(&line, 0.1)),
+            4
+        );
+        let offset = DashPattern {
+            offset: 1.0,
+            ..DashPattern::new(2.0, 1.0)
+        };
+        assert_eq!(sub_path_count(&offset.apply(&line, 0.1)), 4);
+        assert_eq!(
+            sub_path_count(&DashPattern::new(2.0, 0.0).apply(&line, 0.1)),
+            1
+        );
+        assert_eq!(
+            sub_path_count(&DashPattern::new(0.0, 1.0).apply(&line, 0.1)),
+            0
+        );
+    }
+
+    #[test]
+    fn dashes_wrap_around_closed_sub_paths() {
+        let mut builder = Path::builder();
+        builder.add_polygon(Polygon {
+            points: &[
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+            ],
+            closed: true,
+        });
+        let square = builder.build();
+
+        // The pattern starts one unit into the first dash, so the dash drawn
+        // from 14 to 17 along the perimeter crosses the seam and absorbs the
+        // one drawn from 0 to 1.
+        let dashes = DashPattern {
+            offset: 2.0,
+            ..DashPattern::new(3.0, 1.0)
+        }
+        .apply(&square, 0.1);
+        assert_eq!(sub_path_count(&dashes), 4);
+
+        let seam_dash: Vec<Point> = dashes
+            .iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at),
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .skip_while(|point| *point != Point::new(0.0, 2.0))
+            .take(3)
+            .collect();
+        assert_eq!(
+            seam_dash,
+            [
+                Point::new(0.0, 2.0),
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn stroke_builders_set_the_options() {
+        let mode = StrokeMode::new(Color::BLACK, 4.0)
+            .with_line_cap(LineCap::Round)
+            .with_line_join(LineJoin::Bevel)
+            .with_miter_limit(2.5);
+
+        assert_eq!(mode.options.start_cap, LineCap::Round);
+        assert_eq!(mode.options.end_cap, LineCap::Round);
+        assert_eq!(mode.options.line_join, LineJoin::Bevel);
+        assert!((mode.options.miter_limit - 2.5).abs() < f32::EPSILON);
+        assert!((mode.options.line_width - 4.0).abs() < f32::EPSILON);
+
+        let arrow = mode
+            .with_start_cap(LineCap::Butt)
+            .with_end_cap(LineCap::Square);
+        assert_eq!(arrow.options.start_cap, LineCap::Butt);
+        assert_eq!(arrow.options.end_cap, LineCap::Square);
+        assert_eq!(arrow.options.line_join, LineJoin::Bevel);
+    }
+
+    fn triangle_count(path: &Path, mode: &FillMode) -> usize {
+        let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                path,
+                &mode.options,
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| vertex.position()),
+            )
+            .unwrap();
+        buffers.indices.len() / 3
+    }
+
+    #[test]
+    fn fill_rule_decides_whether_nested_squares_overlap() {
+        // Both squares wind the same way, so they only overlap with non-zero.
+        let square = |size: f32| {
+            [
+                Point::new(-size, -size),
+                Point::new(size, -size),
+                Point::new(size, size),
+                Point::new(-size, size),
+            ]
+        };
+        let mut builder = Path::builder();
+        for points in [square(10.0), square(5.0)] {
+            builder.add_polygon(Polygon {
+                points: &points,
+                closed: true,
+            });
+        }
+        let path = builder.build();
+
+        let mode = FillMode::color(Color::RED);
+        let even_odd = triangle_count(&path, &mode.with_fill_rule(FillRule::EvenOdd));
+        let non_zero = triangle_count(&path, &mode.with_fill_rule(FillRule::NonZero));
+        assert!(even_odd > 0 && non_zero > 0);
+        assert_ne!(even_odd, non_zero);
+    }
 }
@@ -1,23 +1,31 @@
 //! Custom Bevy ECS bundle for shapes.
 
 use bevy::{
-    ecs::{bundle::Bundle, component::Component},
+    asset::Handle,
+    ecs::{bundle::Bundle, component::Component, entity::Entity},
+    math::Vec2,
     render::{
         color::Color,
+        mesh::Mesh,
         view::{ComputedVisibility, Visibility},
     },
-    sprite::Mesh2dHandle,
+    sprite::{Mesh2dHandle, Rect, SpecializedMaterial2d, TextureAtlas},
     transform::components::{GlobalTransform, Transform},
 };
-use lyon_tessellation::{self as tess, FillOptions};
+use lyon_tessellation as tess;
 
 use crate::{
     draw::{DrawMode, FillMode},
-    prelude::Geometry,
+    geometry::{Geometry, GeometryBuilder},
+    path::ShapePath,
     render::Shape,
 };
 
 /// A Bevy `Bundle` to represent a shape.
+///
+/// Each shape gets its own mesh, unless the
+/// [`ShapeMeshCache`](crate::cache::ShapeMeshCache) is enabled, in which case
+/// identical shapes share one, even when they are spawned in the same frame.
 #[allow(missing_docs)]
 #[derive(Bundle)]
 pub struct ShapeBundle {
@@ -31,14 +39,37 @@ pub struct ShapeBundle {
     pub computed_visibility: ComputedVisibility,
 }
 
+impl ShapeBundle {
+    /// Returns a [`ShapeBundle`] drawing `shape` with the given draw mode and
+    /// transform. This is the same as [`GeometryBuilder::build_as`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::entity::ShapeBundle;
+    /// #
+    /// fn my_system(mut commands: Commands) {
+    ///     commands.spawn_bundle(ShapeBundle::new(
+    ///         &shapes::Circle::default(),
+    ///         DrawMode::Fill(FillMode::color(Color::CYAN)),
+    ///         Transform::from_xyz(100.0, 50.0, 0.0),
+    ///     ));
+    /// }
+    /// # my_system.system();
+    /// ```
+    #[must_use]
+    pub fn new(shape: &impl Geometry, mode: DrawMode, transform: Transform) -> Self {
+        GeometryBuilder::build_as(shape, mode, transform)
+    }
+}
+
 impl Default for ShapeBundle {
     fn default() -> Self {
         Self {
             path: Path(tess::path::Path::new()),
-            mode: DrawMode::Fill(FillMode {
-                options: FillOptions::default(),
-                color: Color::WHITE,
-            }),
+            mode: DrawMode::Fill(FillMode::color(Color::WHITE)),
             shape: Shape::default(),
             mesh2d: Mesh2dHandle::default(),
             transform: Transform::default(),
@@ -49,10 +80,451 @@ impl Default for ShapeBundle {
     }
 }
 
+/// A Bevy `Bundle` to represent a shape drawn with a custom `Material2d`
+/// instead of the shape pipeline.
+///
+/// The shape is meshed from its [`Path`] and [`DrawMode`] as usual, but it is
+/// drawn by the `Material2dPlugin` of `M`, which must be added to the app.
+/// Materials can read the colors of the draw mode from `Mesh::ATTRIBUTE_COLOR`,
+/// written in the [`MeshColorFormat`](crate::plugin::MeshColorFormat) of the
+/// [`MeshConfig`](crate::plugin::MeshConfig). Since the 2d mesh pipeline of
+/// Bevy requires normals and UVs, the config must include them.
+#[allow(missing_docs)]
+#[derive(Bundle)]
+pub struct MaterialShapeBundle<M: SpecializedMaterial2d> {
+    pub path: Path,
+    pub mode: DrawMode,
+    pub material: Handle<M>,
+    pub mesh2d: Mesh2dHandle,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+impl<M: SpecializedMaterial2d> MaterialShapeBundle<M> {
+    /// Returns a [`MaterialShapeBundle`] drawing `shape` with the given draw
+    /// mode, material and transform.
+    #[must_use]
+    pub fn new(
+        shape: &impl Geometry,
+        mode: DrawMode,
+        material: Handle<M>,
+        transform: Transform,
+    ) -> Self {
+        Self {
+            path: ShapePath::build_as(shape),
+            mode,
+            material,
+            transform,
+            ..Self::default()
+        }
+    }
+}
+
+impl<M: SpecializedMaterial2d> Default for MaterialShapeBundle<M> {
+    fn default() -> Self {
+        let ShapeBundle {
+            path,
+            mode,
+            mesh2d,
+            transform,
+            global_transform,
+            visibility,
+            computed_visibility,
+            ..
+        } = ShapeBundle::default();
+
+        Self {
+            path,
+            mode,
+            material: Handle::default(),
+            mesh2d,
+            transform,
+            global_transform,
+            visibility,
+            computed_visibility,
+        }
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Component)]
 pub struct Path(pub tess::path::Path);
 
+/// An optional component that sets the Z coordinate of every vertex of the
+/// shape's mesh.
+///
+/// This allows layering shapes inside the same mesh without touching the
+/// `Transform`. Shapes without this component are meshed at a depth of `0.0`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+pub struct ShapeDepth(pub f32);
+
+/// An optional component that keeps the stroke of the shape as many pixels
+/// wide as its value on screen, whatever the zoom of the camera.
+///
+/// The line width of the [`DrawMode`] is updated to follow the scale of the
+/// `OrthographicProjection` of the cameras, re-tessellating the shape when it
+/// changes. If there are several cameras, the stroke is sized for the one with
+/// the largest scale, so that it is never thinner than requested. The
+/// `Transform` of the shape is not taken into account.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct ScreenSpaceStroke(pub f32);
+
+/// A marker component that scales the tessellation tolerance of the shape with
+/// the zoom of the camera, so that curves stay smooth when zoomed in and cheap
+/// when zoomed out.
+///
+/// The tolerances of the [`DrawMode`] are multiplied by the scale of the
+/// `OrthographicProjection` of the cameras, rounded to a power of two. The
+/// shape is only tessellated again when the scale moves three quarters of the
+/// way to the next power of two, so that zooming back and forth around a
+/// threshold doesn't re-tessellate it every frame. If there are several
+/// cameras, the one with the smallest scale is used, so that the shape is
+/// smooth in all of them. The [`DrawMode`] of the shape is left untouched.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct AdaptiveTolerance;
+
+/// An optional component that moves the dashes of a dashed stroke along the
+/// path over time, for marching ants effects.
+///
+/// The offset of the [`DashPattern`](crate::draw::DashPattern) of the
+/// [`DrawMode`] advances by `speed` units per second, a positive speed moving
+/// the dashes towards the end of the path. The shape is only re-tessellated
+/// while the speed is not zero and the stroke is dashed.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct DashAnimation {
+    /// The distance travelled by the dashes each second.
+    pub speed: f32,
+}
+
+/// An optional component that only strokes the first `fraction` of the length
+/// of the path, to animate it being drawn.
+///
+/// A `fraction` of `0.0` strokes nothing and `1.0` strokes the whole path.
+/// Closed sub-paths that are only partly revealed are stroked as open ones.
+/// Fills are not affected. Revealed shapes bypass the
+/// [`ShapeMeshCache`](crate::cache::ShapeMeshCache), since each fraction would
+/// otherwise leave its own mesh in it.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct ShapeReveal {
+    /// The revealed part of the path, in the `[0, 1]` range.
+    pub fraction: f32,
+}
+
+impl Default for ShapeReveal {
+    fn default() -> Self {
+        Self { fraction: 1.0 }
+    }
+}
+
+/// A marker component that moves the tessellation of a shape to the
+/// `AsyncComputeTaskPool`, so that heavy paths don't stall the schedule.
+///
+/// The mesh of the shape is updated once the task completes, usually a frame
+/// or two after the shape changed. If the shape changes again before that, the
+/// pending task is cancelled and replaced.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct AsyncTessellation;
+
+/// A marker component for shapes drawn with a material that supplies their
+/// color, like a `ColorMaterial` in a [`MaterialShapeBundle`].
+///
+/// The vertices of the shape are all white instead of taking the colors of its
+/// [`DrawMode`], so its mesh only depends on its geometry. With the
+/// [`ShapeMeshCache`](crate::cache::ShapeMeshCache) enabled, identical shapes
+/// then share a single mesh whatever their colors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct MaterialColor;
+
+/// A component that makes [`ShapePlugin`](crate::plugin::ShapePlugin) write
+/// the mesh of a shape into the given mesh asset, instead of one of its own.
+///
+/// The asset is overwritten each time the shape is meshed, and created if it
+/// doesn't exist yet, so a handle already wired into a custom pipeline keeps
+/// showing the shape. The `Mesh2dHandle` of the shape is set to it too.
+/// Targeted shapes bypass the [`ShapeMeshCache`](crate::cache::ShapeMeshCache).
+/// Once this component is removed, the shape is meshed again into a mesh of its
+/// own, leaving the asset as it was.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// fn my_system(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+///     let target = meshes.add(Mesh::from(shape::Quad::default()));
+///     commands
+///         .spawn_bundle(GeometryBuilder::build_as(
+///             &shapes::Circle::default(),
+///             DrawMode::Fill(FillMode::color(Color::RED)),
+///             Transform::default(),
+///         ))
+///         .insert(TargetMesh(target));
+/// }
+/// # my_system.system();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct TargetMesh(pub Handle<Mesh>);
+
+/// A component that draws the fill and the outline of a
+/// [`DrawMode::Outlined`] shape as two child entities, each with its own mesh.
+///
+/// The children are [`ShapeBundle`]s kept in sync with the [`Path`] and the
+/// [`DrawMode`] of the shape, which is no longer meshed itself. Their `Shape`
+/// component can be replaced by a material, like in a [`MaterialShapeBundle`],
+/// to draw the fill and the outline with different materials. The one drawn
+/// on top is slightly in front of the other. Shapes with another draw mode
+/// are drawn entirely by the fill child, without an outline child.
+///
+/// The children are spawned by [`ShapePlugin`](crate::plugin::ShapePlugin),
+/// so they are only drawn from the frame after the component is added.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// fn my_system(mut commands: Commands) {
+///     commands
+///         .spawn_bundle(GeometryBuilder::build_as(
+///             &shapes::Circle::default(),
+///             DrawMode::Outlined {
+///                 fill_mode: FillMode::color(Color::rgba(1.0, 0.0, 0.0, 0.2)),
+///                 outline_mode: StrokeMode::new(Color::BLACK, 2.0),
+///             },
+///             Transform::default(),
+///         ))
+///         .insert(SplitOutline::default());
+/// }
+/// # my_system.system();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub struct SplitOutline {
+    pub(crate) fill: Option<Entity>,
+    pub(crate) outline: Option<Entity>,
+}
+
+impl SplitOutline {
+    /// Returns the entity drawing the fill, once it has been spawned.
+    #[must_use]
+    pub const fn fill(&self) -> Option<Entity> {
+        self.fill
+    }
+
+    /// Returns the entity drawing the outline, once it has been spawned. It
+    /// only exists while the shape is [`DrawMode::Outlined`].
+    #[must_use]
+    pub const fn outline(&self) -> Option<Entity> {
+        self.outline
+    }
+}
+
+/// A component that tiles a texture across a shape, by scaling its UVs.
+///
+/// UVs normally span the `[0, 1]` range over the bounding box of the shape.
+/// With this component they span `[0, repeat.x]` and `[0, repeat.y]`, so a
+/// material sampling `fract(uv)` draws its texture `repeat` times along each
+/// axis. [`MeshConfig::include_uvs`](crate::plugin::MeshConfig::include_uvs)
+/// must be enabled. Repeat counts don't need to be integers, the last tiles
+/// are then cut by the edge of the bounding box.
+///
+/// Each tile is stretched to `bounding box size / repeat`, so to keep the
+/// aspect ratio of a non-square texture, or atlas region, the repeat counts
+/// should be proportional to the bounding box divided by the texture size.
+///
+/// To tile a region of a `TextureAtlas`, pass its
+/// [`atlas_region`](Self::atlas_region) to the material, which then samples
+/// `region.min + fract(uv) * (region.max - region.min)`. Tiled shapes bypass
+/// the [`ShapeMeshCache`](crate::cache::ShapeMeshCache).
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct TextureTiling {
+    /// The number of times the texture is repeated along each axis.
+    pub repeat: Vec2,
+}
+
+impl TextureTiling {
+    /// Returns the region at `index` of `atlas`, normalized to the `[0, 1]`
+    /// texture coordinates of the atlas, or `None` if there is no such
+    /// region.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::{prelude::*, sprite::Rect};
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let mut atlas = TextureAtlas::new_empty(Handle::default(), Vec2::new(256.0, 128.0));
+    /// atlas.add_texture(Rect {
+    ///     min: Vec2::new(64.0, 0.0),
+    ///     max: Vec2::new(192.0, 64.0),
+    /// });
+    ///
+    /// let region = TextureTiling::atlas_region(&atlas, 0).unwrap();
+    /// assert_eq!(region.min, Vec2::new(0.25, 0.0));
+    /// assert_eq!(region.max, Vec2::new(0.75, 0.5));
+    /// assert!(TextureTiling::atlas_region(&atlas, 1).is_none());
+    /// ```
+    #[must_use]
+    pub fn atlas_region(atlas: &TextureAtlas, index: usize) -> Option<Rect> {
+        let rect = atlas.textures.get(index)?;
+
+        Some(Rect {
+            min: rect.min / atlas.size,
+            max: rect.max / atlas.size,
+        })
+    }
+}
+
+impl Default for TextureTiling {
+    fn default() -> Self {
+        Self { repeat: Vec2::ONE }
+    }
+}
+
+/// A component that merges the shapes of many entities into a single mesh,
+/// drawn with a single draw call.
+///
+/// Each member entity only needs a [`Path`] and a [`DrawMode`]. Members must
+/// not have a `Mesh2dHandle`, otherwise they are also drawn on their own. Since
+/// colors are stored per vertex, members may use different draw modes.
+///
+/// The tradeoff is that the whole batch is re-tessellated whenever any of its
+/// members changes, including its `Transform`, and that later members are
+/// drawn on top of earlier ones. The paths of the members are placed in the
+/// local space of the batch entity, moved by the `Transform` of the members
+/// that have one. The [`ShapeDepth`], [`MaterialColor`] and
+/// [`AdaptiveTolerance`] of the members are applied as for other shapes.
+/// [`DrawMode::Wireframe`] members are drawn filled, since the batch mesh
+/// contains only triangles.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// fn my_system(mut commands: Commands) {
+///     let members = (0..100)
+///         .map(|i| {
+///             let tile = shapes::Rectangle {
+///                 extents: Vec2::splat(8.0),
+///                 origin: RectangleOrigin::CustomCenter(Vec2::new(i as f32 * 10.0, 0.0)),
+///             };
+///             commands
+///                 .spawn()
+///                 .insert_bundle((
+///                     ShapePath::build_as(&tile),
+///                     DrawMode::Fill(FillMode::color(Color::GREEN)),
+///                 ))
+///                 .id()
+///         })
+///         .collect();
+///
+///     commands.spawn_bundle(BatchedShapesBundle {
+///         batch: BatchedShapes(members),
+///         ..BatchedShapesBundle::default()
+///     });
+/// }
+/// # my_system.system();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component)]
+pub struct BatchedShapes(pub Vec<Entity>);
+
+/// A Bevy `Bundle` to represent a batch of shapes. See [`BatchedShapes`].
+#[allow(missing_docs)]
+#[derive(Bundle, Default)]
+pub struct BatchedShapesBundle {
+    pub batch: BatchedShapes,
+    pub shape: Shape,
+    pub mesh2d: Mesh2dHandle,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// A component that draws the mesh of a shape once for each of its instances,
+/// with a single instanced draw call.
+///
+/// This is much cheaper than spawning an entity for each copy of a shape, like
+/// for particles, since the instances have no per-entity overhead. The shape
+/// is meshed from its [`Path`] and [`DrawMode`] as usual, each instance being
+/// placed relative to the `Transform` of the entity. Instances are drawn in
+/// order, without depth sorting between them.
+///
+/// The entity must not have a [`Shape`] component, otherwise its mesh is also
+/// drawn on its own. Use [`InstancedShapeBundle`] to spawn it.
+#[derive(Debug, Default, Clone, PartialEq, Component)]
+pub struct ShapeInstances(pub Vec<ShapeInstance>);
+
+/// A copy of a shape drawn by [`ShapeInstances`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeInstance {
+    /// The position of the instance.
+    pub position: Vec2,
+    /// The counter-clockwise rotation of the instance, in radians.
+    pub rotation: f32,
+    /// The uniform scale of the instance.
+    pub scale: f32,
+    /// The color multiplied with the vertex colors of the mesh.
+    pub color: Color,
+}
+
+impl Default for ShapeInstance {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            rotation: 0.0,
+            scale: 1.0,
+            color: Color::WHITE,
+        }
+    }
+}
+
+/// A Bevy `Bundle` to represent a shape drawn once for each of its
+/// [`ShapeInstances`].
+#[allow(missing_docs)]
+#[derive(Bundle)]
+pub struct InstancedShapeBundle {
+    pub path: Path,
+    pub mode: DrawMode,
+    pub instances: ShapeInstances,
+    pub mesh2d: Mesh2dHandle,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+impl Default for InstancedShapeBundle {
+    fn default() -> Self {
+        let ShapeBundle {
+            path,
+            mode,
+            mesh2d,
+            transform,
+            global_transform,
+            visibility,
+            computed_visibility,
+            ..
+        } = ShapeBundle::default();
+
+        Self {
+            path,
+            mode,
+            instances: ShapeInstances::default(),
+            mesh2d,
+            transform,
+            global_transform,
+            visibility,
+            computed_visibility,
+        }
+    }
+}
+
 impl Geometry for Path {
     fn add_geometry(&self, b: &mut tess::path::path::Builder) {
         b.concatenate(&[self.0.as_slice()]);
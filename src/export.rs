@@ -0,0 +1,147 @@
+//! Exporting of tessellated shapes to mesh files.
+
+use std::io::{self, Write};
+
+use bevy::log::error;
+use lyon_tessellation::{FillTessellator, StrokeTessellator};
+
+use crate::{draw::DrawMode, entity::Path, plugin::tessellate, vertex::unpack_color};
+
+/// Tessellates a shape the same way [`ShapePlugin`](crate::plugin::ShapePlugin)
+/// does, and writes the result to `writer` in the Wavefront OBJ format.
+///
+/// Each vertex is written with its linear RGB color after its position
+/// (`v x y z r g b`), an extension understood by most mesh tools. The alpha of
+/// the colors is not written. Faces are written as triangles. Paths with NaN or
+/// infinite coordinates are not tessellated, and write nothing.
+///
+/// # Errors
+///
+/// Returns any error returned by `writer`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::export::export_shape_obj;
+/// #
+/// let triangle = ShapePath::build_as(&RegularPolygon {
+///     sides: 3,
+///     ..RegularPolygon::default()
+/// });
+/// let mut obj = Vec::new();
+/// export_shape_obj(
+///     &triangle,
+///     &DrawMode::Fill(FillMode::color(Color::RED)),
+///     &mut obj,
+/// )
+/// .unwrap();
+///
+/// let obj = String::from_utf8(obj).unwrap();
+/// assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), 3);
+/// assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), 1);
+///
+/// let nan = lyon_tessellation::math::Transform::scale(f32::NAN, 1.0);
+/// let mut obj = Vec::new();
+/// export_shape_obj(
+///     &Path(triangle.0.transformed(&nan)),
+///     &DrawMode::Fill(FillMode::color(Color::RED)),
+///     &mut obj,
+/// )
+/// .unwrap();
+/// assert!(obj.is_empty());
+/// ```
+pub fn export_shape_obj(path: &Path, mode: &DrawMode, mut writer: impl Write) -> io::Result<()> {
+    let (buffers, error) = tessellate(
+        &mut FillTessellator::new(),
+        &mut StrokeTessellator::new(),
+        &path.0,
+        mode,
+        1.0,
+    );
+    if let Some(e) = error {
+        error!("Tessellation error: {:?}", e);
+    }
+
+    for vertex in &buffers.vertices {
+        let [r, g, b, _] = unpack_color(vertex.color);
+        writeln!(
+            writer,
+            "v {} {} 0 {} {} {}",
+            vertex.position[0], vertex.position[1], r, g, b
+        )?;
+    }
+    // OBJ indices start at 1.
+    for triangle in buffers.indices.chunks_exact(3) {
+        writeln!(
+            writer,
+            "f {} {} {}",
+            triangle[0] + 1,
+            triangle[1] + 1,
+            triangle[2] + 1
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::Vec2, render::color::Color};
+
+    use super::*;
+    use crate::{
+        draw::FillMode,
+        path::ShapePath,
+        shapes::{Rectangle, RectangleOrigin},
+    };
+
+    #[test]
+    fn exported_rectangle_reads_back() {
+        let rectangle = ShapePath::build_as(&Rectangle {
+            extents: Vec2::new(20.0, 10.0),
+            origin: RectangleOrigin::BottomLeft,
+        });
+        let mut obj = Vec::new();
+        export_shape_obj(
+            &rectangle,
+            &DrawMode::Fill(FillMode::color(Color::RED)),
+            &mut obj,
+        )
+        .unwrap();
+
+        let obj = String::from_utf8(obj).unwrap();
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for line in obj.lines() {
+            let mut values = line.split(' ');
+            match values.next() {
+                Some("v") => vertices.push(
+                    values
+                        .map(|value| value.parse::<f32>().unwrap())
+                        .collect::<Vec<_>>(),
+                ),
+                Some("f") => faces.push(
+                    values
+                        .map(|index| index.parse::<usize>().unwrap())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => panic!("unexpected line {:?}", line),
+            }
+        }
+
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(faces.len(), 2);
+        for vertex in &vertices {
+            assert_eq!(vertex.len(), 6);
+            assert!([0.0, 20.0].contains(&vertex[0]));
+            assert!([0.0, 10.0].contains(&vertex[1]));
+            assert_eq!(vertex[2..], [0.0, 1.0, 0.0, 0.0]);
+        }
+        for face in &faces {
+            assert_eq!(face.len(), 3);
+            assert!(face.iter().all(|&index| (1..=4).contains(&index)));
+        }
+    }
+}
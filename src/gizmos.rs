@@ -0,0 +1,144 @@
+//! Immediate mode drawing of shapes, for debugging.
+
+use bevy::{
+    math::Vec2, render::color::Color, sprite::Rect, transform::components::GlobalTransform,
+};
+use lyon_tessellation::path::Path;
+
+use crate::{
+    draw::{DrawMode, StrokeMode},
+    entity,
+    geometry::Geometry,
+    path::ShapePath,
+    shapes::{Circle, Line, Rectangle, RectangleOrigin},
+};
+
+/// A resource that draws shapes for a single frame, without spawning an entity
+/// for each of them.
+///
+/// The shapes queued during a frame are tessellated together by
+/// [`ShapePlugin`](crate::plugin::ShapePlugin) into the mesh of a single
+/// entity, and the queue is cleared, so they must be drawn again every frame
+/// to stay on screen. Shapes are placed in world space, and drawn filled
+/// instead of as [`DrawMode::Wireframe`]. Since they are tessellated from
+/// scratch every frame, gizmos are meant for a few debug shapes.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// fn debug_system(mut gizmos: ResMut<ShapeGizmos>, query: Query<&Transform, With<Path>>) {
+///     for transform in query.iter() {
+///         gizmos.circle(transform.translation.truncate(), 20.0, Color::RED);
+///     }
+/// }
+/// # debug_system.system();
+/// ```
+pub struct ShapeGizmos {
+    /// The width of the lines drawn by [`line`](Self::line),
+    /// [`circle`](Self::circle) and [`rect`](Self::rect).
+    pub line_width: f32,
+    /// The Z coordinate of the gizmos, in world space.
+    pub depth: f32,
+    shapes: Vec<(Path, DrawMode)>,
+}
+
+impl Default for ShapeGizmos {
+    fn default() -> Self {
+        Self {
+            line_width: 2.0,
+            depth: 999.0,
+            shapes: Vec::new(),
+        }
+    }
+}
+
+impl ShapeGizmos {
+    /// Draws `shape` with the given draw mode.
+    pub fn shape(&mut self, shape: &impl Geometry, mode: DrawMode) {
+        self.shapes.push((ShapePath::build_as(shape).0, mode));
+    }
+
+    /// Draws a line from `start` to `end`.
+    pub fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.shape(&Line(start, end), self.stroke(color));
+    }
+
+    /// Draws the outline of a circle.
+    pub fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let circle = Circle {
+            radius,
+            center,
+            ..Circle::default()
+        };
+        self.shape(&circle, self.stroke(color));
+    }
+
+    /// Draws the outline of a rectangle.
+    pub fn rect(&mut self, center: Vec2, size: Vec2, color: Color) {
+        let rectangle = Rectangle {
+            extents: size,
+            origin: RectangleOrigin::CustomCenter(center),
+        };
+        self.shape(&rectangle, self.stroke(color));
+    }
+
+    /// Removes the shapes drawn since the last call.
+    pub(crate) fn take(&mut self) -> Vec<(Path, DrawMode)> {
+        std::mem::take(&mut self.shapes)
+    }
+
+    fn stroke(&self, color: Color) -> DrawMode {
+        DrawMode::Stroke(StrokeMode::new(color, self.line_width))
+    }
+}
+
+/// Returns the axis-aligned bounding box of a shape in world space, as drawn
+/// by [`ShapePluginBuilder::with_bounding_boxes`](crate::plugin::ShapePluginBuilder::with_bounding_boxes).
+///
+/// The corners of [`Path::bounding_box`](entity::Path::bounding_box) are moved
+/// by `transform`, so for a rotated shape this is the box enclosing the
+/// rotated local box.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::{gizmos::world_bounding_box, prelude::*};
+/// #
+/// let rectangle = ShapePath::build_as(&shapes::Rectangle {
+///     extents: Vec2::new(20.0, 10.0),
+///     origin: RectangleOrigin::Center,
+/// });
+///
+/// let moved = GlobalTransform::from_xyz(100.0, 0.0, 0.0);
+/// let bbox = world_bounding_box(&rectangle, &moved);
+/// assert_eq!((bbox.min, bbox.max), (Vec2::new(90.0, -5.0), Vec2::new(110.0, 5.0)));
+///
+/// // A quarter turn swaps the width and the height of the box.
+/// let quarter_turn = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+/// let turned = GlobalTransform::from_rotation(quarter_turn);
+/// let bbox = world_bounding_box(&rectangle, &turned);
+/// assert!((bbox.max - Vec2::new(5.0, 10.0)).length() < 1e-4);
+/// assert!((bbox.min + Vec2::new(5.0, 10.0)).length() < 1e-4);
+/// ```
+#[must_use]
+pub fn world_bounding_box(path: &entity::Path, transform: &GlobalTransform) -> Rect {
+    let local = path.bounding_box();
+    let corners = [
+        local.min,
+        Vec2::new(local.max.x, local.min.y),
+        local.max,
+        Vec2::new(local.min.x, local.max.y),
+    ]
+    .map(|corner| transform.mul_vec3(corner.extend(0.0)).truncate());
+
+    let (min, max) = corners[1..]
+        .iter()
+        .fold((corners[0], corners[0]), |(min, max), &corner| {
+            (min.min(corner), max.max(corner))
+        });
+    Rect { min, max }
+}
@@ -22,16 +22,27 @@
 #![allow(clippy::needless_pass_by_value)] // False positives with `SystemParam`s.
 #![allow(clippy::forget_non_drop)]
 
+pub mod cache;
+#[cfg(feature = "collider")]
+pub mod collider;
+pub mod convert;
 pub mod draw;
 pub mod entity;
+pub mod export;
 pub mod geometry;
+pub mod gizmos;
 pub mod path;
+pub mod picking;
 pub mod plugin;
 pub mod render;
 pub mod shapes;
+pub mod vertex;
 
+mod antialiasing;
+mod boolean;
+#[cfg(feature = "serde")]
+mod serialization;
 mod utils;
-mod vertex;
 
 /// Import this module as `use bevy_prototype_lyon::prelude::*` to get
 /// convenient imports.
@@ -41,11 +52,29 @@ pub mod prelude {
     };
 
     pub use crate::{
-        draw::{DrawMode, FillMode, StrokeMode},
-        entity::Path,
+        cache::ShapeMeshCache,
+        convert::{ToPoint, ToVec2, ToVector},
+        draw::{
+            DashPattern, DrawMode, FillClosing, FillMode, Gradient, LinearGradient,
+            OptionOverrides, Palette, RadialGradient, StrokeAlignment, StrokeGradient, StrokeMode,
+        },
+        entity::{
+            AdaptiveTolerance, AsyncTessellation, BatchedShapes, BatchedShapesBundle,
+            DashAnimation, InstancedShapeBundle, MaterialColor, MaterialShapeBundle, Path,
+            ScreenSpaceStroke, ShapeDepth, ShapeInstance, ShapeInstances, ShapeReveal,
+            SplitOutline, TargetMesh, TextureTiling,
+        },
         geometry::{Geometry, GeometryBuilder},
+        gizmos::ShapeGizmos,
         path::{PathBuilder, ShapePath},
-        plugin::ShapePlugin,
-        shapes::{self, RectangleOrigin, RegularPolygon, RegularPolygonFeature},
+        picking::ShapeHit,
+        plugin::{
+            MeshColorFormat, MeshConfig, MeshIndexFormat, MeshWinding, ShapePlugin,
+            ShapePluginBuilder, ShapeTessellationError, TessellationSettings,
+        },
+        shapes::{self, RectangleOrigin, RegularPolygon, RegularPolygonFeature, ShapeOrigin},
     };
+
+    #[cfg(feature = "collider")]
+    pub use crate::collider::{ColliderBackend, ColliderRing};
 }
@@ -1,15 +1,33 @@
 //! Types outputting lyon `Path`s.
 
-use bevy::math::Vec2;
+use bevy::{
+    math::Vec2,
+    render::{
+        color::Color,
+        mesh::{Mesh, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
+    sprite::Rect,
+    utils::HashMap,
+};
 use lyon_tessellation::{
-    geom::Angle,
-    path::{builder::WithSvg, path::Builder, EndpointId},
+    self as tess,
+    geom::{Angle, LineSegment},
+    math::Point,
+    path::{
+        builder::WithSvg, path::Builder, traits::PathBuilder as _, EndpointId, PathEvent, Polygon,
+    },
+    FillOptions, FillRule, LineJoin,
 };
 
 use crate::{
+    boolean,
+    convert::{ToPoint, ToVec2, ToVector},
+    draw::StrokeMode,
     entity::Path,
     geometry::Geometry,
-    utils::{ToPoint, ToVector},
+    plugin::tessellate_stroke,
+    utils::{flatten, is_inside, ring_points, simplify_polyline, winding_number, FlatSubPath},
 };
 
 /// A builder for `Path`s based on shapes implementing [`Geometry`].
@@ -58,6 +76,37 @@ impl ShapePath {
     }
 
     /// Builds the `Path` and returns it.
+    ///
+    /// Each shape added to the builder keeps its own sub-paths, with their
+    /// direction, so holes made by the fill rule are kept when shapes are
+    /// combined. Since a [`Path`] is also a [`Geometry`], built paths can be
+    /// added too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use tess::path::PathEvent;
+    /// #
+    /// let body = shapes::Circle {
+    ///     radius: 40.0,
+    ///     ..shapes::Circle::default()
+    /// };
+    /// let head = ShapePath::build_as(&shapes::Circle {
+    ///     radius: 25.0,
+    ///     center: Vec2::new(0.0, 60.0),
+    ///     ..shapes::Circle::default()
+    /// });
+    /// let snowman = ShapePath::new().add(&body).add(&head).build();
+    ///
+    /// let sub_paths = snowman
+    ///     .0
+    ///     .iter()
+    ///     .filter(|event| matches!(event, PathEvent::Begin { .. }))
+    ///     .count();
+    /// assert_eq!(sub_paths, 2);
+    /// ```
     #[must_use]
     pub fn build(self) -> Path {
         Path(self.0.build())
@@ -99,51 +148,92 @@ impl Default for ShapePath {
 }
 
 /// A SVG-like path builder.
-pub struct PathBuilder(WithSvg<Builder>);
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let mut path_builder = PathBuilder::new();
+/// path_builder.move_to(Vec2::ZERO);
+/// path_builder.cubic_bezier_to(
+///     Vec2::new(50.0, 100.0),
+///     Vec2::new(50.0, -100.0),
+///     Vec2::new(100.0, 0.0),
+/// );
+/// let s_curve = path_builder.build();
+/// assert!(s_curve.0.iter().next().is_some());
+/// ```
+pub struct PathBuilder {
+    builder: WithSvg<Builder>,
+    auto_close: bool,
+    sub_path_open: bool,
+}
 
 impl PathBuilder {
     /// Returns a new, empty `PathBuilder`.
     #[must_use]
     pub fn new() -> Self {
-        Self(Builder::new().with_svg())
+        Self {
+            builder: Builder::new().with_svg(),
+            auto_close: false,
+            sub_path_open: false,
+        }
+    }
+
+    /// Makes the builder close every sub-path that has been left open, when a
+    /// new one is started or when the path is built.
+    #[must_use]
+    pub fn with_auto_close(mut self) -> Self {
+        self.auto_close = true;
+        self
     }
 
     /// Returns a finalized [`Path`].
     #[must_use]
-    pub fn build(self) -> Path {
-        Path(self.0.build())
+    pub fn build(mut self) -> Path {
+        self.auto_close_sub_path();
+        Path(self.builder.build())
     }
 
     /// Moves the current point to the given position.
     pub fn move_to(&mut self, to: Vec2) -> EndpointId {
-        self.0.move_to(to.to_point())
+        self.auto_close_sub_path();
+        self.builder.move_to(to.to_point())
     }
 
     /// Adds to the path a line from the current position to the given one.
     pub fn line_to(&mut self, to: Vec2) -> EndpointId {
-        self.0.line_to(to.to_point())
+        self.sub_path_open = true;
+        self.builder.line_to(to.to_point())
     }
 
     /// Closes the shape, adding to the path a line from the current position to
     /// the starting location.
     pub fn close(&mut self) {
-        self.0.close();
+        self.sub_path_open = false;
+        self.builder.close();
     }
 
     /// Adds a quadratic bezier to the path.
     pub fn quadratic_bezier_to(&mut self, ctrl: Vec2, to: Vec2) -> EndpointId {
-        self.0.quadratic_bezier_to(ctrl.to_point(), to.to_point())
+        self.sub_path_open = true;
+        self.builder
+            .quadratic_bezier_to(ctrl.to_point(), to.to_point())
     }
 
     /// Adds a cubic bezier to the path.
     pub fn cubic_bezier_to(&mut self, ctrl1: Vec2, ctrl2: Vec2, to: Vec2) -> EndpointId {
-        self.0
+        self.sub_path_open = true;
+        self.builder
             .cubic_bezier_to(ctrl1.to_point(), ctrl2.to_point(), to.to_point())
     }
 
     /// Adds an arc to the path.
     pub fn arc(&mut self, center: Vec2, radii: Vec2, sweep_angle: f32, x_rotation: f32) {
-        self.0.arc(
+        self.sub_path_open = true;
+        self.builder.arc(
             center.to_point(),
             radii.to_vector(),
             Angle::radians(sweep_angle),
@@ -154,9 +244,23 @@ impl PathBuilder {
     /// Returns the path's current position.
     #[must_use]
     pub fn current_position(&self) -> Vec2 {
-        let p = self.0.current_position();
+        let p = self.builder.current_position();
         Vec2::new(p.x, p.y)
     }
+
+    /// Returns `true` if the current sub-path has some segments and has not
+    /// been closed.
+    #[must_use]
+    pub const fn has_open_sub_path(&self) -> bool {
+        self.sub_path_open
+    }
+
+    fn auto_close_sub_path(&mut self) {
+        if self.auto_close && self.sub_path_open {
+            self.close();
+        }
+        self.sub_path_open = false;
+    }
 }
 
 impl Default for PathBuilder {
@@ -164,3 +268,1085 @@ impl Default for PathBuilder {
         Self::new()
     }
 }
+
+/// Boolean operations between the filled regions of paths.
+///
+/// The paths are filled with `FillOptions::default()`, which uses the
+/// even-odd fill rule, or with the options given to the `_with_options`
+/// variants. The result is made of non-overlapping convex polygons, so it is
+/// meant to be filled: stroking it would also outline the internal edges
+/// between the polygons.
+impl Path {
+    /// Returns a path covering the region of either `self` or `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let rectangle = |x| {
+    ///     ShapePath::build_as(&shapes::Rectangle {
+    ///         extents: Vec2::new(20.0, 10.0),
+    ///         origin: RectangleOrigin::CustomCenter(Vec2::new(x, 0.0)),
+    ///     })
+    /// };
+    ///
+    /// let union = rectangle(0.0).union(&rectangle(15.0));
+    /// let xs = union.0.iter().map(|event| event.to().x);
+    /// assert!((xs.clone().fold(f32::MAX, f32::min) + 10.0).abs() < 1e-4);
+    /// assert!((xs.fold(f32::MIN, f32::max) - 25.0).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.union_with_options(other, &FillOptions::default())
+    }
+
+    /// Returns a path covering the region of either `self` or `other`, both
+    /// filled with `options`.
+    #[must_use]
+    pub fn union_with_options(&self, other: &Self, options: &FillOptions) -> Self {
+        Self(boolean::union(&self.0, &other.0, options))
+    }
+
+    /// Returns a path covering the region shared by `self` and `other`.
+    ///
+    /// Disjoint paths produce an empty path.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.intersection_with_options(other, &FillOptions::default())
+    }
+
+    /// Returns a path covering the region shared by `self` and `other`, both
+    /// filled with `options`.
+    #[must_use]
+    pub fn intersection_with_options(&self, other: &Self, options: &FillOptions) -> Self {
+        Self(boolean::intersection(&self.0, &other.0, options))
+    }
+
+    /// Returns a path covering the region of `self` that is not covered by
+    /// `other`.
+    ///
+    /// Subtracting a path from itself produces an empty path.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.difference_with_options(other, &FillOptions::default())
+    }
+
+    /// Returns a path covering the region of `self` that is not covered by
+    /// `other`, both filled with `options`.
+    #[must_use]
+    pub fn difference_with_options(&self, other: &Self, options: &FillOptions) -> Self {
+        Self(boolean::difference(&self.0, &other.0, options))
+    }
+
+    /// Returns a path covering the region of the stroke of `self`, including
+    /// its caps, joins and dashes, so that it can be filled with any
+    /// [`FillMode`](crate::draw::FillMode).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use tess::path::PathEvent;
+    /// #
+    /// let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+    /// let outline = line.stroke_outline(&StrokeMode::new(Color::BLACK, 10.0));
+    ///
+    /// // Sum the signed areas of the polygons of the outline.
+    /// let area: f32 = outline
+    ///     .0
+    ///     .iter()
+    ///     .map(|event| match event {
+    ///         PathEvent::Line { from, to }
+    ///         | PathEvent::End {
+    ///             last: from,
+    ///             first: to,
+    ///             ..
+    ///         } => from.to_vector().cross(to.to_vector()) / 2.0,
+    ///         _ => 0.0,
+    ///     })
+    ///     .sum();
+    /// assert!((area - 100.0 * 10.0).abs() < 1e-2);
+    /// ```
+    #[must_use]
+    pub fn stroke_outline(&self, mode: &StrokeMode) -> Self {
+        let (positions, vertices) =
+            welded_triangles(&tessellate_stroke(self, mode)).unwrap_or_default();
+        let triangles = vertices
+            .chunks_exact(3)
+            .map(|triangle| triangle.iter().map(|&i| positions[i].to_point()).collect());
+
+        Self(boolean::merge_triangles(triangles))
+    }
+
+    /// Returns a path covering the region of `self` grown by `distance` in
+    /// every direction, or shrunk if `distance` is negative.
+    ///
+    /// The region swept by a stroke of width `2 * distance` along the path is
+    /// added to, or removed from, the path, with mitered corners. Parts of the
+    /// shape thinner than twice a negative `distance` therefore disappear
+    /// instead of folding over themselves, and concave corners never
+    /// self-intersect. Unlike a stroke, this covers the inside of the path
+    /// too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let square = ShapePath::build_as(&shapes::Rectangle {
+    ///     extents: Vec2::splat(100.0),
+    ///     origin: RectangleOrigin::Center,
+    /// });
+    ///
+    /// let bounds = square.offset(10.0).bounding_box();
+    /// assert!(bounds.min.abs_diff_eq(Vec2::splat(-60.0), 1e-3));
+    /// assert!(bounds.max.abs_diff_eq(Vec2::splat(60.0), 1e-3));
+    ///
+    /// let bounds = square.offset(-10.0).bounding_box();
+    /// assert!(bounds.min.abs_diff_eq(Vec2::splat(-40.0), 1e-3));
+    /// assert!(bounds.max.abs_diff_eq(Vec2::splat(40.0), 1e-3));
+    ///
+    /// assert_eq!(square.offset(-60.0).0.iter().count(), 0);
+    /// ```
+    #[must_use]
+    pub fn offset(&self, distance: f32) -> Self {
+        if distance == 0.0 {
+            return Self(self.0.clone());
+        }
+
+        let band = self.stroke_outline(
+            &StrokeMode::new(Color::BLACK, 2.0 * distance.abs()).with_line_join(LineJoin::Miter),
+        );
+        if distance > 0.0 {
+            self.union(&band)
+        } else {
+            self.difference(&band)
+        }
+    }
+
+    /// Returns the part of the path inside `rect`.
+    ///
+    /// A path lying entirely inside `rect` is returned unchanged, and one
+    /// lying entirely outside of it produces an empty path. To clip against an
+    /// arbitrary region, use [`intersection`](Self::intersection).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::{prelude::*, sprite::Rect};
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let square = ShapePath::build_as(&shapes::Rectangle {
+    ///     extents: Vec2::splat(100.0),
+    ///     origin: RectangleOrigin::Center,
+    /// });
+    /// let clipped = square.clip_rect(Rect {
+    ///     min: Vec2::ZERO,
+    ///     max: Vec2::splat(200.0),
+    /// });
+    ///
+    /// let bounds = clipped.bounding_box();
+    /// assert!(bounds.min.abs_diff_eq(Vec2::ZERO, 1e-4));
+    /// assert!(bounds.max.abs_diff_eq(Vec2::splat(50.0), 1e-4));
+    /// ```
+    #[must_use]
+    pub fn clip_rect(&self, rect: Rect) -> Self {
+        let bounds = self.bounding_box();
+        if bounds.min.cmpge(rect.min).all() && bounds.max.cmple(rect.max).all() {
+            return Self(self.0.clone());
+        }
+        if bounds.min.cmpgt(rect.max).any() || bounds.max.cmplt(rect.min).any() {
+            return Self(tess::path::Path::new());
+        }
+
+        let mut builder = Builder::new();
+        builder.add_polygon(Polygon {
+            points: &[
+                rect.min.to_point(),
+                Vec2::new(rect.max.x, rect.min.y).to_point(),
+                rect.max.to_point(),
+                Vec2::new(rect.min.x, rect.max.y).to_point(),
+            ],
+            closed: true,
+        });
+
+        self.intersection(&Self(builder.build()))
+    }
+
+    /// Returns the path with the points that lie within `epsilon` of the
+    /// others removed, using the Ramer-Douglas-Peucker algorithm. This cuts
+    /// down the triangle count of imported paths with many redundant points.
+    ///
+    /// Curves are first flattened with the default tessellation tolerance, so
+    /// the result is only made of lines. Each sub-path is simplified on its
+    /// own and keeps being open or closed. Open sub-paths keep their ends.
+    /// Closed sub-paths that would collapse to fewer than three points are
+    /// kept as they are, so that no region of the shape disappears.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let line = ShapePath::build_as(&shapes::Polyline {
+    ///     points: (0..=100).map(|i| Vec2::new(i as f32, 0.0)).collect(),
+    ///     closed: false,
+    /// });
+    /// let simplified = line.simplify(0.01);
+    ///
+    /// assert_eq!(simplified.vertex_count(), 2);
+    /// assert!((simplified.length() - 100.0).abs() < 1e-4);
+    ///
+    /// let circle = ShapePath::build_as(&shapes::Circle {
+    ///     radius: 100.0,
+    ///     ..shapes::Circle::default()
+    /// });
+    /// let coarse = circle.simplify(5.0);
+    /// assert!(coarse.vertex_count() >= 3);
+    /// assert!(coarse.vertex_count() < circle.simplify(0.0).vertex_count());
+    /// ```
+    #[must_use]
+    pub fn simplify(&self, epsilon: f32) -> Self {
+        let mut builder = Builder::new();
+        for sub_path in flatten(&self.0, FillOptions::DEFAULT_TOLERANCE) {
+            let points = if sub_path.closed {
+                simplify_ring(&ring_points(&sub_path), epsilon)
+            } else {
+                simplify_polyline(&sub_path.points, epsilon)
+            };
+            builder.add_polygon(Polygon {
+                points: &points,
+                closed: sub_path.closed,
+            });
+        }
+
+        Self(builder.build())
+    }
+}
+
+/// Simplifies a closed ring by splitting it into two polylines, between its
+/// first point and the point farthest from it.
+fn simplify_ring(ring: &[Point], epsilon: f32) -> Vec<Point> {
+    if ring.len() < 3 {
+        return ring.to_vec();
+    }
+
+    let farthest = ring
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            let (a, b) = ((**a - ring[0]).length(), (**b - ring[0]).length());
+            a.total_cmp(&b)
+        })
+        .map_or(0, |(i, _)| i);
+    if farthest == 0 {
+        return ring.to_vec();
+    }
+
+    let mut second_half = ring[farthest..].to_vec();
+    second_half.push(ring[0]);
+    let mut points = simplify_polyline(&ring[..=farthest], epsilon);
+    let second_half = simplify_polyline(&second_half, epsilon);
+    // Both halves repeat their shared ends.
+    points.extend_from_slice(&second_half[1..second_half.len() - 1]);
+
+    if points.len() < 3 {
+        ring.to_vec()
+    } else {
+        points
+    }
+}
+
+/// Geometric queries.
+impl Path {
+    /// Returns the axis-aligned bounding box of the path.
+    ///
+    /// The box encloses all the endpoints and control points of the path.
+    /// Since curves always lie inside their control polygon, the box is exact
+    /// for paths made only of lines and may be slightly bigger than needed for
+    /// curves. An empty path returns a zero-sized box at the origin.
+    #[must_use]
+    pub fn bounding_box(&self) -> Rect {
+        let mut points = self.0.iter().flat_map(|event| match event {
+            PathEvent::Begin { at } => vec![at],
+            PathEvent::Line { to, .. } => vec![to],
+            PathEvent::Quadratic { ctrl, to, .. } => vec![ctrl, to],
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => vec![ctrl1, ctrl2, to],
+            PathEvent::End { .. } => Vec::new(),
+        });
+
+        let first = match points.next() {
+            Some(first) => first.to_vec2(),
+            None => {
+                return Rect {
+                    min: Vec2::ZERO,
+                    max: Vec2::ZERO,
+                }
+            }
+        };
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (min.min(p.to_vec2()), max.max(p.to_vec2()))
+        });
+
+        Rect { min, max }
+    }
+
+    /// Returns the center and the radius of a circle enclosing the path, for
+    /// fast culling.
+    ///
+    /// This is not the smallest enclosing circle: the center is the center of
+    /// the bounding box of the path, flattened with the default tessellation
+    /// tolerance, and the radius is the distance to its farthest point, plus
+    /// the tolerance to cover the curves between the points. Its radius is at
+    /// most `sqrt(2)` times the one of the smallest circle, plus the tolerance.
+    /// An empty path returns a zero-sized circle at the origin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let circle = ShapePath::build_as(&shapes::Circle {
+    ///     radius: 50.0,
+    ///     center: Vec2::new(10.0, -20.0),
+    ///     ..shapes::Circle::default()
+    /// });
+    /// let (center, radius) = circle.bounding_circle();
+    ///
+    /// assert!(center.abs_diff_eq(Vec2::new(10.0, -20.0), 0.1));
+    /// assert!(radius >= 50.0 && radius - 50.0 < 0.5);
+    /// ```
+    #[must_use]
+    pub fn bounding_circle(&self) -> (Vec2, f32) {
+        let tolerance = FillOptions::DEFAULT_TOLERANCE;
+        let sub_paths = flatten(&self.0, tolerance);
+        let mut points = sub_paths
+            .iter()
+            .flat_map(|sub_path| &sub_path.points)
+            .map(|point| point.to_vec2());
+        let first = match points.next() {
+            Some(first) => first,
+            None => return (Vec2::ZERO, 0.0),
+        };
+        let (min, max) = points
+            .clone()
+            .fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        let center = (min + max) / 2.0;
+        let radius = points.fold(first.distance(center), |radius, p| {
+            radius.max(p.distance(center))
+        });
+
+        (center, radius + tolerance)
+    }
+
+    /// Returns `true` if `point` lies inside the filled region of the path,
+    /// according to the given fill rule.
+    ///
+    /// Paths are filled as if each sub-path was closed, and curves are
+    /// approximated with the default tessellation tolerance. Points lying on
+    /// an edge are considered inside.
+    #[must_use]
+    pub fn contains_point(&self, point: Vec2, fill_rule: FillRule) -> bool {
+        const EDGE_EPSILON: f32 = 1e-4;
+
+        let p = point.to_point();
+        let sub_paths = flatten(&self.0, FillOptions::DEFAULT_TOLERANCE);
+        let on_edge = sub_paths.iter().any(|sub_path| {
+            let points = &sub_path.points;
+            points.iter().enumerate().any(|(i, &from)| {
+                let to = points[(i + 1) % points.len()];
+                LineSegment { from, to }.distance_to_point(p) <= EDGE_EPSILON
+            })
+        });
+
+        on_edge || is_inside(winding_number(&sub_paths, p), fill_rule)
+    }
+
+    /// Returns the total length of the path, including the closing segments
+    /// of closed sub-paths. Curves are approximated with the default
+    /// tessellation tolerance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let square = ShapePath::build_as(&shapes::Polyline {
+    ///     points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y],
+    ///     closed: true,
+    /// });
+    /// assert!((square.length() - 4.0).abs() < 1e-5);
+    ///
+    /// // Halfway along the top side, going left.
+    /// let (position, tangent) = square.point_at(0.625).unwrap();
+    /// assert!(position.abs_diff_eq(Vec2::new(0.5, 1.0), 1e-5));
+    /// assert!(tangent.abs_diff_eq(Vec2::new(-1.0, 0.0), 1e-5));
+    /// ```
+    #[must_use]
+    pub fn length(&self) -> f32 {
+        flatten(&self.0, FillOptions::DEFAULT_TOLERANCE)
+            .iter()
+            .map(FlatSubPath::length)
+            .sum()
+    }
+
+    /// Returns the position and the unit tangent of the path at `t` of its
+    /// [`length`](Self::length), `t` being clamped to the `[0, 1]` range.
+    ///
+    /// Sub-paths are walked one after the other, skipping the gaps between
+    /// them. Returns `None` if the path has no length.
+    #[must_use]
+    pub fn point_at(&self, t: f32) -> Option<(Vec2, Vec2)> {
+        let (segments, _) = self.flat_segments();
+        let length = segments
+            .iter()
+            .map(|(from, to)| (*to - *from).length())
+            .sum::<f32>();
+        if length <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = t.clamp(0.0, 1.0) * length;
+        for (i, &(from, to)) in segments.iter().enumerate() {
+            let segment_length = (to - from).length();
+            if remaining <= segment_length || i == segments.len() - 1 {
+                let position = from.lerp(to, (remaining / segment_length).min(1.0));
+                let tangent = (to - from) / segment_length;
+                return Some((position.to_vec2(), Vec2::new(tangent.x, tangent.y)));
+            }
+            remaining -= segment_length;
+        }
+
+        None
+    }
+
+    /// Returns `n` points evenly spaced along the [`length`](Self::length) of
+    /// the path, for instance to build a physics collider.
+    ///
+    /// Sub-paths are walked one after the other, skipping the gaps between
+    /// them. If they are all closed, the points are `length / n` apart and the
+    /// last one stops short of the start of the path. Otherwise the first and
+    /// last points are the ends of the path, `length / (n - 1)` apart. A
+    /// single point is the start of the path, and a path with no length gives
+    /// no points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let square = ShapePath::build_as(&shapes::Polyline {
+    ///     points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y],
+    ///     closed: true,
+    /// });
+    /// let corners = square.resample(4);
+    /// assert_eq!(corners.len(), 4);
+    /// for (corner, expected) in corners.iter().zip([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]) {
+    ///     assert!(corner.abs_diff_eq(expected, 1e-5));
+    /// }
+    ///
+    /// let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(10.0, 0.0)));
+    /// let points = line.resample(3);
+    /// assert!(points[1].abs_diff_eq(Vec2::new(5.0, 0.0), 1e-5));
+    /// assert!(points[2].abs_diff_eq(Vec2::new(10.0, 0.0), 1e-5));
+    /// assert!(line.resample(0).is_empty());
+    /// ```
+    #[must_use]
+    pub fn resample(&self, n: usize) -> Vec<Vec2> {
+        let (segments, closed) = self.flat_segments();
+        let lengths = segments
+            .iter()
+            .map(|(from, to)| (*to - *from).length())
+            .collect::<Vec<_>>();
+        let length = lengths.iter().sum::<f32>();
+        if n == 0 || length <= 0.0 {
+            return Vec::new();
+        }
+
+        let divisions = if closed { n } else { n - 1 };
+        let spacing = length / divisions.max(1) as f32;
+        let mut samples = Vec::with_capacity(n);
+        let (mut segment, mut start) = (0, 0.0);
+        for i in 0..n {
+            let distance = i as f32 * spacing;
+            while segment + 1 < segments.len() && distance > start + lengths[segment] {
+                start += lengths[segment];
+                segment += 1;
+            }
+            let (from, to) = segments[segment];
+            let t = ((distance - start) / lengths[segment]).clamp(0.0, 1.0);
+            samples.push(from.lerp(to, t).to_vec2());
+        }
+
+        samples
+    }
+
+    /// Returns the non-degenerate segments of the flattened path, in order,
+    /// and whether all of its sub-paths are closed.
+    fn flat_segments(&self) -> (Vec<(Point, Point)>, bool) {
+        let sub_paths = flatten(&self.0, FillOptions::DEFAULT_TOLERANCE);
+        let segments = sub_paths
+            .iter()
+            .flat_map(FlatSubPath::segments)
+            .filter(|(from, to)| from != to)
+            .collect();
+
+        (segments, sub_paths.iter().all(|sub_path| sub_path.closed))
+    }
+}
+
+/// Conversion from meshes.
+impl Path {
+    /// Returns the outline of a `TriangleList` mesh, as closed sub-paths made
+    /// of the edges that belong to a single triangle.
+    ///
+    /// Vertices are matched by their x and y positions, so meshes without
+    /// shared vertices are outlined too. A mesh with holes gives a sub-path for
+    /// each of its outer and inner boundaries. Boundaries that don't form loops,
+    /// as can happen with non-manifold meshes, are kept as open sub-paths.
+    /// Meshes with another topology or without 2D or 3D positions give an empty
+    /// path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::plugin::tessellate_fill;
+    /// #
+    /// let quad = tessellate_fill(
+    ///     &ShapePath::build_as(&shapes::Rectangle::default()),
+    ///     &FillMode::color(Color::RED),
+    /// );
+    /// let outline = Path::from_mesh_outline(&quad);
+    ///
+    /// assert_eq!(outline.vertex_count(), 4);
+    /// assert!(outline.contains_point(Vec2::ZERO, FillRule::NonZero));
+    /// ```
+    #[must_use]
+    pub fn from_mesh_outline(mesh: &Mesh) -> Self {
+        let (positions, vertices) = match welded_triangles(mesh) {
+            Some(triangles) => triangles,
+            None => return Self(tess::path::Path::new()),
+        };
+
+        let edges = boundary_edges(&vertices);
+        let mut next = HashMap::<usize, Vec<usize>>::default();
+        for &(from, to) in &edges {
+            next.entry(from).or_default().push(to);
+        }
+
+        let mut builder = Builder::new();
+        for &(start, second) in &edges {
+            // Skip the edges already walked.
+            let outgoing = next.entry(start).or_default();
+            if let Some(i) = outgoing.iter().position(|&to| to == second) {
+                outgoing.swap_remove(i);
+            } else {
+                continue;
+            }
+
+            let mut points = vec![positions[start].to_point()];
+            let mut current = second;
+            let mut closed = true;
+            while current != start {
+                points.push(positions[current].to_point());
+                match next.get_mut(&current).and_then(Vec::pop) {
+                    Some(to) => current = to,
+                    None => {
+                        closed = false;
+                        break;
+                    }
+                }
+            }
+            builder.add_polygon(Polygon {
+                points: &points,
+                closed,
+            });
+        }
+
+        Self(builder.build())
+    }
+}
+
+/// Returns the 2D vertex positions of a `TriangleList` mesh, along with its
+/// triangles. The vertices of the triangles that share a position are replaced
+/// by the first of them.
+fn welded_triangles(mesh: &Mesh) -> Option<(Vec<Vec2>, Vec<usize>)> {
+    let positions: Vec<Vec2> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+        VertexAttributeValues::Float32x3(positions) => {
+            positions.iter().map(|p| Vec2::new(p[0], p[1])).collect()
+        }
+        VertexAttributeValues::Float32x2(positions) => {
+            positions.iter().map(|&p| Vec2::from(p)).collect()
+        }
+        _ => return None,
+    };
+    let indices = mesh.indices().map_or_else(
+        || (0..positions.len()).collect(),
+        |indices| indices.iter().collect::<Vec<_>>(),
+    );
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList
+        || indices.iter().any(|&i| i >= positions.len())
+    {
+        return None;
+    }
+
+    let mut welded = HashMap::default();
+    let vertices = indices
+        .iter()
+        .map(|&i| {
+            let position = positions[i];
+            *welded
+                .entry([position.x.to_bits(), position.y.to_bits()])
+                .or_insert(i)
+        })
+        .collect();
+
+    Some((positions, vertices))
+}
+
+/// Returns the edges of a triangle list that belong to a single triangle,
+/// oriented as in that triangle.
+fn boundary_edges(triangles: &[usize]) -> Vec<(usize, usize)> {
+    let edges = || {
+        triangles
+            .chunks_exact(3)
+            .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+            .filter(|(from, to)| from != to)
+    };
+
+    let mut counts = HashMap::<(usize, usize), usize>::default();
+    for (from, to) in edges() {
+        *counts.entry((from.min(to), from.max(to))).or_default() += 1;
+    }
+
+    edges()
+        .filter(|&(from, to)| counts[&(from.min(to), from.max(to))] == 1)
+        .collect()
+}
+
+/// Interpolation between paths.
+impl Path {
+    /// Returns a path interpolated between `self` at `t = 0` and `other` at
+    /// `t = 1`. `t` is clamped to the `[0, 1]` range.
+    ///
+    /// Curves are approximated with line segments, then corresponding
+    /// sub-paths are resampled along their arc length so that both have a
+    /// point wherever one of them has a vertex. This makes the outermost
+    /// values of `t` reproduce the shape of the two paths.
+    ///
+    /// A sub-path without a counterpart in the other path shrinks to its
+    /// centroid. The resulting sub-paths are closed only if both of their
+    /// sources are closed.
+    #[must_use]
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let from = flatten(&self.0, FillOptions::DEFAULT_TOLERANCE);
+        let to = flatten(&other.0, FillOptions::DEFAULT_TOLERANCE);
+
+        let mut builder = Builder::new();
+        for i in 0..from.len().max(to.len()) {
+            let (a, b) = match (from.get(i), to.get(i)) {
+                (Some(a), Some(b)) => (a, b),
+                (Some(sub_path), None) | (None, Some(sub_path)) => (sub_path, sub_path),
+                (None, None) => unreachable!(),
+            };
+            let closed = a.closed && b.closed;
+
+            let mut parameters = a.point_parameters();
+            parameters.extend(b.point_parameters());
+            if !closed {
+                parameters.push(1.0);
+            }
+            parameters.sort_by(f32::total_cmp);
+            parameters.dedup();
+
+            let sample = |sub_path: Option<&FlatSubPath>| {
+                sub_path.map_or_else(
+                    || vec![a.centroid(); parameters.len()],
+                    |sub_path| sub_path.sample(&parameters),
+                )
+            };
+            let (a_points, b_points) = (sample(from.get(i)), sample(to.get(i)));
+
+            let points = a_points
+                .iter()
+                .zip(&b_points)
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect::<Vec<_>>();
+            builder.add_polygon(Polygon {
+                points: &points,
+                closed,
+            });
+        }
+
+        Self(builder.build())
+    }
+}
+
+/// Editing of the vertices of a path.
+///
+/// Vertices are the endpoints of the segments of the path, counted in order
+/// across all of its sub-paths: the first point of each sub-path is a vertex,
+/// followed by the end of each of its segments. Control points are not
+/// vertices. Each edit rebuilds the path, and returns `true` if the path
+/// changed, which out of bounds edits and moving a vertex onto itself don't.
+///
+/// Mutably dereferencing a `Mut<Path>` always marks the shape to be re-meshed.
+/// To only re-mesh it when an edit changes the path, edit it through
+/// `bypass_change_detection` and call `set_changed` when the edit returns
+/// `true`:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// fn drag_first_vertex(mut query: Query<&mut Path>, cursor: Vec2) {
+///     for mut path in query.iter_mut() {
+///         if path.bypass_change_detection().set_vertex(0, cursor) {
+///             path.set_changed();
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let mut path = ShapePath::build_as(&shapes::Rectangle {
+///     extents: Vec2::splat(2.0),
+///     origin: RectangleOrigin::Center,
+/// });
+/// assert_eq!(path.vertex_count(), 4);
+///
+/// assert!(path.set_vertex(0, Vec2::new(-2.0, -2.0)));
+/// assert!(path.insert_vertex(1, Vec2::new(0.0, -3.0)));
+/// assert_eq!(path.vertex_count(), 5);
+/// assert!(path.remove_vertex(1));
+/// assert_eq!(path.vertex_count(), 4);
+///
+/// // Out of bounds and no-op edits leave the path untouched.
+/// assert!(!path.remove_vertex(4));
+/// assert!(!path.set_vertex(0, Vec2::new(-2.0, -2.0)));
+/// ```
+impl Path {
+    /// Returns the number of vertices of the path.
+    #[must_use]
+    pub fn vertex_count(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|event| !matches!(event, PathEvent::End { .. }))
+            .count()
+    }
+
+    /// Moves the vertex at `index` to `position`.
+    pub fn set_vertex(&mut self, index: usize, position: Vec2) -> bool {
+        self.edit(index, false, |sub_path, local| {
+            if local == 0 {
+                sub_path.start = position.to_point();
+            } else {
+                sub_path.segments[local - 1].to = position.to_point();
+            }
+        })
+    }
+
+    /// Inserts a vertex at `position` before the vertex at `index`, connected
+    /// to its neighbours with straight lines. An `index` equal to
+    /// [`vertex_count`](Self::vertex_count) appends the vertex to the last
+    /// sub-path.
+    pub fn insert_vertex(&mut self, index: usize, position: Vec2) -> bool {
+        self.edit(index, true, |sub_path, local| {
+            let line = EditableSegment {
+                ctrl: SegmentControls::Line,
+                to: position.to_point(),
+            };
+            if local == 0 {
+                let old_start = std::mem::replace(&mut sub_path.start, line.to);
+                sub_path.segments.insert(
+                    0,
+                    EditableSegment {
+                        ctrl: SegmentControls::Line,
+                        to: old_start,
+                    },
+                );
+            } else {
+                sub_path.segments.insert(local - 1, line);
+            }
+        })
+    }
+
+    /// Removes the vertex at `index`, along with the segment ending at it. The
+    /// following segment then starts from the previous vertex.
+    ///
+    /// Removing the only vertex of a sub-path removes the whole sub-path.
+    pub fn remove_vertex(&mut self, index: usize) -> bool {
+        self.edit(index, false, |sub_path, local| {
+            if local > 0 {
+                sub_path.segments.remove(local - 1);
+            } else if !sub_path.segments.is_empty() {
+                sub_path.start = sub_path.segments.remove(0).to;
+            } else {
+                sub_path.removed = true;
+            }
+        })
+    }
+
+    /// Applies `edit` to the sub-path holding the vertex at `index`, then
+    /// rebuilds the path and returns whether it changed. If `allow_end` is
+    /// `true`, `index` may also be one past the last vertex.
+    fn edit(
+        &mut self,
+        index: usize,
+        allow_end: bool,
+        edit: impl FnOnce(&mut EditableSubPath, usize),
+    ) -> bool {
+        let mut sub_paths = EditableSubPath::from_path(&self.0);
+        let mut first = 0;
+        let mut target = None;
+        for (i, sub_path) in sub_paths.iter().enumerate() {
+            let count = sub_path.segments.len() + 1;
+            if index < first + count {
+                target = Some((i, index - first));
+                break;
+            }
+            first += count;
+        }
+        if target.is_none() && allow_end && index == first {
+            target = sub_paths
+                .len()
+                .checked_sub(1)
+                .map(|last| (last, sub_paths[last].segments.len() + 1));
+        }
+
+        let (sub_path, local) = match target {
+            Some(target) => target,
+            None => return false,
+        };
+        edit(&mut sub_paths[sub_path], local);
+
+        let mut builder = Builder::new();
+        for sub_path in sub_paths.iter().filter(|sub_path| !sub_path.removed) {
+            sub_path.build(&mut builder);
+        }
+        let edited = builder.build();
+        let changed = !edited.iter().eq(self.0.iter());
+        self.0 = edited;
+
+        changed
+    }
+}
+
+/// The control points of a segment.
+#[derive(Clone, Copy)]
+enum SegmentControls {
+    Line,
+    Quadratic(Point),
+    Cubic(Point, Point),
+}
+
+/// A segment of an [`EditableSubPath`], ending at `to`.
+#[derive(Clone, Copy)]
+struct EditableSegment {
+    ctrl: SegmentControls,
+    to: Point,
+}
+
+/// A sub-path stored as a list of segments, so that its vertices can be
+/// edited.
+struct EditableSubPath {
+    start: Point,
+    segments: Vec<EditableSegment>,
+    closed: bool,
+    removed: bool,
+}
+
+impl EditableSubPath {
+    fn from_path(path: &tess::path::Path) -> Vec<Self> {
+        let mut sub_paths = Vec::new();
+        for event in path.iter() {
+            let (ctrl, to) = match event {
+                PathEvent::Begin { at } => {
+                    sub_paths.push(Self {
+                        start: at,
+                        segments: Vec::new(),
+                        closed: false,
+                        removed: false,
+                    });
+                    continue;
+                }
+                PathEvent::End { close, .. } => {
+                    if let Some(sub_path) = sub_paths.last_mut() {
+                        sub_path.closed = close;
+                    }
+                    continue;
+                }
+                PathEvent::Line { to, .. } => (SegmentControls::Line, to),
+                PathEvent::Quadratic { ctrl, to, .. } => (SegmentControls::Quadratic(ctrl), to),
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => (SegmentControls::Cubic(ctrl1, ctrl2), to),
+            };
+            if let Some(sub_path) = sub_paths.last_mut() {
+                sub_path.segments.push(EditableSegment { ctrl, to });
+            }
+        }
+
+        sub_paths
+    }
+
+    fn build(&self, builder: &mut Builder) {
+        builder.begin(self.start);
+        for segment in &self.segments {
+            match segment.ctrl {
+                SegmentControls::Line => {
+                    builder.line_to(segment.to);
+                }
+                SegmentControls::Quadratic(ctrl) => {
+                    builder.quadratic_bezier_to(ctrl, segment.to);
+                }
+                SegmentControls::Cubic(ctrl1, ctrl2) => {
+                    builder.cubic_bezier_to(ctrl1, ctrl2, segment.to);
+                }
+            }
+        }
+        builder.end(self.closed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes;
+
+    #[test]
+    fn bounding_box_of_a_rectangle_is_exact() {
+        let rectangle = ShapePath::build_as(&shapes::Rectangle {
+            extents: Vec2::new(20.0, 10.0),
+            origin: shapes::RectangleOrigin::BottomLeft,
+        });
+
+        let bounds = rectangle.bounding_box();
+        assert_eq!(bounds.min, Vec2::ZERO);
+        assert_eq!(bounds.max, Vec2::new(20.0, 10.0));
+
+        let empty = Path(tess::path::Path::new()).bounding_box();
+        assert_eq!((empty.min, empty.max), (Vec2::ZERO, Vec2::ZERO));
+    }
+
+    #[test]
+    fn bounding_box_of_a_circle_is_within_tolerance() {
+        let circle = ShapePath::build_as(&shapes::Circle {
+            radius: 50.0,
+            center: Vec2::new(10.0, -20.0),
+            ..shapes::Circle::default()
+        });
+
+        // The control points of the curves may lie a bit outside the circle.
+        let bounds = circle.bounding_box();
+        let (min, max) = (Vec2::new(-40.0, -70.0), Vec2::new(60.0, 30.0));
+        assert!(bounds.min.cmple(min).all() && bounds.min.abs_diff_eq(min, 1.0));
+        assert!(bounds.max.cmpge(max).all() && bounds.max.abs_diff_eq(max, 1.0));
+    }
+
+    #[test]
+    fn contains_points_of_a_square() {
+        let square = ShapePath::build_as(&shapes::Rectangle {
+            extents: Vec2::splat(10.0),
+            origin: shapes::RectangleOrigin::BottomLeft,
+        });
+
+        for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            assert!(square.contains_point(Vec2::new(5.0, 5.0), fill_rule));
+            assert!(!square.contains_point(Vec2::new(15.0, 5.0), fill_rule));
+            assert!(!square.contains_point(Vec2::new(-0.1, 5.0), fill_rule));
+            // Points on an edge or a corner are inside.
+            assert!(square.contains_point(Vec2::new(10.0, 5.0), fill_rule));
+            assert!(square.contains_point(Vec2::new(5.0, 0.0), fill_rule));
+            assert!(square.contains_point(Vec2::ZERO, fill_rule));
+        }
+    }
+
+    #[test]
+    fn holes_of_a_ring_are_excluded() {
+        let square = |half: f32| {
+            vec![
+                Vec2::new(-half, -half),
+                Vec2::new(half, -half),
+                Vec2::new(half, half),
+                Vec2::new(-half, half),
+            ]
+        };
+        let ring = ShapePath::build_as(&shapes::PolygonWithHoles {
+            outer: square(10.0),
+            holes: vec![square(5.0)],
+        });
+
+        for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+            assert!(!ring.contains_point(Vec2::ZERO, fill_rule));
+            assert!(ring.contains_point(Vec2::new(7.5, 0.0), fill_rule));
+            assert!(!ring.contains_point(Vec2::new(12.0, 0.0), fill_rule));
+        }
+    }
+
+    fn points(path: &Path) -> Vec<Vec2> {
+        path.0
+            .iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(at.to_vec2()),
+                PathEvent::Line { to, .. } => Some(to.to_vec2()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lerp_reproduces_its_endpoints() {
+        let triangle = ShapePath::build_as(&shapes::Polygon {
+            points: vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(5.0, 10.0)],
+            closed: true,
+        });
+        let square = ShapePath::build_as(&shapes::Rectangle {
+            extents: Vec2::splat(10.0),
+            origin: shapes::RectangleOrigin::BottomLeft,
+        });
+
+        for (t, expected) in [(0.0, &triangle), (1.0, &square)] {
+            let lerped = triangle.lerp(&square, t);
+            let lerped_points = points(&lerped);
+            // Every vertex is kept, and the added ones lie on the edges.
+            for vertex in points(expected) {
+                assert!(lerped_points.iter().any(|p| p.abs_diff_eq(vertex, 1e-4)));
+            }
+            for point in &lerped_points {
+                assert!(expected.contains_point(*point, FillRule::NonZero));
+            }
+            let (bounds, expected_bounds) = (lerped.bounding_box(), expected.bounding_box());
+            assert!(bounds.min.abs_diff_eq(expected_bounds.min, 1e-4));
+            assert!(bounds.max.abs_diff_eq(expected_bounds.max, 1e-4));
+        }
+
+        // `t` is clamped.
+        assert_eq!(
+            points(&triangle.lerp(&square, -1.0)),
+            points(&triangle.lerp(&square, 0.0))
+        );
+        assert_eq!(
+            points(&triangle.lerp(&square, 2.0)),
+            points(&triangle.lerp(&square, 1.0))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Hit testing of shapes, for example to pick them with the mouse.
+
+use bevy::{math::Vec2, transform::components::GlobalTransform};
+use lyon_tessellation::FillRule;
+
+use crate::entity::Path;
+
+/// Tests whether points in world space hit shapes.
+pub struct ShapeHit;
+
+impl ShapeHit {
+    /// Returns `true` if the `world` position lies inside `path`, drawn with
+    /// the given `transform`.
+    ///
+    /// The position is brought back into the space of the path by the inverse
+    /// of the transform, so rotated, scaled and mirrored shapes are handled.
+    /// It is then tested with [`Path::contains_point`], using
+    /// [`FillRule::EvenOdd`] like the default fill mode. Shapes with a zero
+    /// scale are never hit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// let square = ShapePath::build_as(&shapes::Rectangle {
+    ///     extents: Vec2::splat(100.0),
+    ///     origin: RectangleOrigin::Center,
+    /// });
+    /// let transform = GlobalTransform::from(
+    ///     Transform::from_xyz(200.0, 0.0, 0.0)
+    ///         .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+    /// );
+    ///
+    /// // Only inside because the corner of the square points there.
+    /// assert!(ShapeHit::pick(Vec2::new(260.0, 0.0), &transform, &square));
+    /// assert!(!ShapeHit::pick(Vec2::new(200.0, 75.0), &transform, &square));
+    /// ```
+    #[must_use]
+    pub fn pick(world: Vec2, transform: &GlobalTransform, path: &Path) -> bool {
+        let matrix = transform.compute_matrix();
+        if matrix.determinant() == 0.0 {
+            return false;
+        }
+
+        let local = matrix
+            .inverse()
+            .transform_point3(world.extend(transform.translation.z));
+        path.contains_point(local.truncate(), FillRule::EvenOdd)
+    }
+}
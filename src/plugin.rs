@@ -5,34 +5,77 @@
 //!
 //! ## How it works
 //! The user spawns a [`ShapeBundle`](crate::entity::ShapeBundle) from a
-//! system in the `UPDATE` stage.
+//! system in the `UPDATE` stage, or in any stage before [`Stage::Shape`].
 //!
 //! Then, in [`Stage::Shape`] stage, there is a system
 //! that creates a mesh for each entity that has been spawned as a
 //! `ShapeBundle`.
 
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
 use bevy::{
-    app::{App, Plugin},
-    asset::Assets,
+    app::{App, CoreStage, Plugin},
+    asset::{Assets, Handle},
+    core::Time,
     ecs::{
-        query::{Changed, Or},
-        schedule::{StageLabel, SystemStage},
-        system::{Query, ResMut},
+        change_detection::DetectChanges,
+        component::Component,
+        entity::Entity,
+        event::EventWriter,
+        query::{ChangeTrackers, Changed, Or, With, Without},
+        schedule::{ParallelSystemDescriptorCoercion, StageLabel, SystemLabel, SystemStage},
+        system::{Commands, Local, Query, RemovedComponents, Res, ResMut},
+        world::Mut,
     },
-    log::error,
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    log::{error, warn},
+    math::{Vec2, Vec3},
     render::{
-        mesh::{Indices, Mesh},
-        render_resource::PrimitiveTopology,
+        camera::{Camera, OrthographicProjection},
+        color::Color,
+        mesh::{Indices, Mesh, MeshVertexAttribute, VertexAttributeValues},
+        render_resource::{PrimitiveTopology, VertexFormat},
+        view::{ComputedVisibility, Visibility},
     },
     sprite::Mesh2dHandle,
+    tasks::{AsyncComputeTaskPool, ComputeTaskPool, Task},
+    transform::components::{GlobalTransform, Transform},
+    utils::{HashMap, HashSet},
+    window::{Window, Windows},
+};
+use futures_lite::future;
+use lyon_tessellation::{
+    self as tess,
+    path::{traits::PathBuilder, Polygon},
+    BuffersBuilder, FillOptions, FillRule, FillTessellator, LineCap, StrokeTessellator,
+    TessellationError,
 };
-use lyon_tessellation::{self as tess, BuffersBuilder, FillTessellator, StrokeTessellator};
 
 use crate::{
-    draw::{DrawMode, FillMode, StrokeMode},
-    entity::Path,
-    render::RenderShapePlugin,
-    vertex::{VertexBuffers, VertexConstructor},
+    antialiasing,
+    cache::{ShapeKey, ShapeMeshCache},
+    draw::{DrawMode, FillClosing, FillMode, Palette, StrokeAlignment, StrokeGradient, StrokeMode},
+    entity::{
+        AdaptiveTolerance, AsyncTessellation, BatchedShapes, DashAnimation, MaterialColor, Path,
+        ScreenSpaceStroke, ShapeBundle, ShapeDepth, ShapeReveal, SplitOutline, TargetMesh,
+        TextureTiling,
+    },
+    gizmos::{world_bounding_box, ShapeGizmos},
+    render::{RenderShapePlugin, Shape},
+    utils::{convex_hull, flatten, has_open_sub_path, is_finite, offset, truncate, FlatSubPath},
+    vertex::{
+        canonicalize, premultiply_alpha, refine_delaunay_from, set_winding, unpack_color, weld,
+        GradientVertexConstructor, StrokeGradientVertexConstructor, TriangleCounter, VertexBuffers,
+        VertexConstructor,
+    },
 };
 
 /// Stages for this plugin.
@@ -43,109 +86,3489 @@ pub enum Stage {
     Shape,
 }
 
+/// Labels of the systems of this plugin.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, SystemLabel)]
+enum ShapeSystem {
+    /// Installs the meshes of the completed tessellation tasks. It runs before
+    /// new tasks are spawned, so that a completed stale task can't remove its
+    /// replacement.
+    PollTessellationTasks,
+    /// Marks the hidden shapes that became visible to be meshed. It runs before
+    /// the shapes are meshed.
+    WakeDeferredShapes,
+    /// Marks the shapes that lost their [`TargetMesh`] to be meshed into a
+    /// mesh of their own. It runs before the shapes are meshed.
+    ReleaseTargetMeshes,
+    /// Marks the shapes throttled by the meshing budget to be meshed. It runs
+    /// before the shapes are meshed.
+    WakeThrottledShapes,
+    /// Updates the line width of screen space strokes. It runs before the
+    /// shapes are meshed.
+    ScaleScreenSpaceStrokes,
+    /// Advances the dash offsets of animated strokes. It runs before the
+    /// shapes are meshed.
+    AnimateDashes,
+    /// Updates the children of [`SplitOutline`] shapes. It runs before the
+    /// shapes are meshed.
+    SplitOutlines,
+    /// Tracks the scale factor of the windows for
+    /// [`MeshConfig::scale_strokes_by_dpi`]. It runs before the shapes are
+    /// meshed.
+    UpdateStrokeScale,
+    /// Marks the shapes filled from the [`Palette`] to be meshed again when it
+    /// changes. It runs before the shapes are meshed.
+    UpdatePalette,
+    /// Tracks the zoom of the cameras for [`AdaptiveTolerance`] shapes. It
+    /// runs before the shapes are meshed.
+    UpdateZoomLevel,
+    /// Marks all the shapes to be meshed again when the
+    /// [`TessellationSettings`] change. It runs before the shapes are meshed.
+    UpdateTessellationSettings,
+    /// Meshes the changed shapes and batches, from their paths or from the
+    /// [`ShapeMeshCache`].
+    MeshShapes,
+    /// Merges the shapes drawn through [`ShapeGizmos`] into a mesh. The
+    /// bounding boxes of the shapes are drawn before it.
+    DrawShapeGizmos,
+}
+
+/// Controls how [`ShapePlugin`] generates meshes, and which attributes are
+/// written into them.
+///
+/// Insert this resource before adding the plugin to override the defaults.
+/// Meshes can also be built outside of the plugin with a given config, with
+/// [`tessellate_with_config`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshConfig {
+    /// Whether to write `Mesh::ATTRIBUTE_UV_0`. UVs are computed by
+    /// normalizing each vertex position against the bounding box of the
+    /// shape, so that a texture covers the whole shape.
+    pub include_uvs: bool,
+    /// Whether to write `Mesh::ATTRIBUTE_NORMAL`, for shaders that require it
+    /// (for example lit materials). Since shapes are planar, every normal is
+    /// `[0.0, 0.0, 1.0]`.
+    pub include_normals: bool,
+    /// The type of the indices of the meshes.
+    pub index_format: MeshIndexFormat,
+    /// Whether to skip the tessellation of shapes whose `Visibility` is
+    /// hidden. Their mesh is updated once they are shown again.
+    pub defer_hidden: bool,
+    /// Whether to output a `TriangleStrip` mesh instead of a `TriangleList`
+    /// when the triangles of a shape form a single strip, like the stroke of a
+    /// straight thick line does. This shrinks the index buffer. Other shapes
+    /// keep using lists.
+    pub triangle_strips: bool,
+    /// The format of `Mesh::ATTRIBUTE_COLOR`.
+    pub color_format: MeshColorFormat,
+    /// The maximum number of vertices of the mesh of a shape, if any. Shapes
+    /// producing more are tessellated again with a coarser tolerance until
+    /// they fit, which may take a few attempts. If a coarser tolerance no
+    /// longer helps, like for polygons with too many points, a warning is
+    /// logged and the coarsest mesh is kept.
+    pub max_vertices: Option<usize>,
+    /// The direction in which the triangles of the meshes are wound.
+    pub winding: MeshWinding,
+    /// The maximum number of shapes tessellated each frame, if any. Shapes
+    /// taking their mesh from the [`ShapeMeshCache`] don't count. This spreads
+    /// the meshing of many shapes changed at once, like when a level is
+    /// spawned, over several frames, at the cost of shapes showing up late.
+    /// The shapes left over are meshed first on the next frames, in the order
+    /// they were left over, so that none of them waits forever. Which shapes
+    /// are left over among the ones changed in the same frame is unspecified.
+    pub max_meshes_per_frame: Option<usize>,
+    /// Whether to multiply the line width of strokes by the scale factor of
+    /// the window, so that lines look as thick on high-DPI displays. Shapes
+    /// are stroked again when the scale factor changes, for example when the
+    /// window is moved to another monitor. With several windows, the largest
+    /// scale factor is used. The [`DrawMode`] of the shapes is left untouched.
+    pub scale_strokes_by_dpi: bool,
+    /// Whether to merge the vertices of a mesh that have the same color and
+    /// lie within a ten thousandth of a unit of each other, as done by
+    /// [`weld`](crate::vertex::weld). This shrinks the vertex buffer of shapes
+    /// whose tessellation emits coincident vertices, at the cost of some
+    /// meshing time.
+    pub weld_vertices: bool,
+    /// Whether to sort the vertices of each mesh into a canonical order, as
+    /// done by [`canonicalize`](crate::vertex::canonicalize), so that the
+    /// buffers of a mesh only depend on its geometry and not on the order in
+    /// which lyon emits vertices.
+    pub canonical_order: bool,
+    /// Whether to multiply the RGB channels of the vertex colors by their
+    /// alpha, as done by [`premultiply_alpha`](crate::vertex::premultiply_alpha).
+    /// Enable it when drawing shapes with a material blending with
+    /// premultiplied alpha, or additively, so that translucent shapes don't
+    /// come out too bright. The pipeline of [`Shape`]s then blends with
+    /// premultiplied alpha too. By default colors are kept with straight alpha.
+    pub premultiply_alpha: bool,
+}
+
+/// Selects the type of the indices written into shape meshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshIndexFormat {
+    /// Uses `u16` indices when all the vertices of the mesh can be indexed by
+    /// them, halving the size of the index buffer, and `u32` indices
+    /// otherwise.
+    Auto,
+    /// Always uses `u32` indices.
+    U32,
+}
+
+impl Default for MeshIndexFormat {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// Selects the format of the colors written into shape meshes.
+///
+/// The shape pipeline reads the format from the vertex layout of each mesh, so
+/// this only matters to custom shaders and materials reading the meshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshColorFormat {
+    /// Packs each color in a `Uint32`, as done by
+    /// [`pack_color`](crate::vertex::pack_color).
+    Packed,
+    /// Writes each color as a `Float32x4`, holding the linear RGBA channels
+    /// unpacked by [`unpack_color`](crate::vertex::unpack_color).
+    Float32x4,
+}
+
+impl Default for MeshColorFormat {
+    fn default() -> Self {
+        Self::Packed
+    }
+}
+
+/// Selects the direction in which the triangles of shape meshes are wound,
+/// with the y axis pointing up.
+///
+/// The shape pipeline draws both faces of the triangles, so this only matters
+/// to custom pipelines and materials that cull back faces, or to meshes used
+/// in 3D.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshWinding {
+    /// Counter-clockwise triangles, which Bevy treats as front faces.
+    Ccw,
+    /// Clockwise triangles.
+    Cw,
+}
+
+impl Default for MeshWinding {
+    fn default() -> Self {
+        Self::Ccw
+    }
+}
+
+/// A resource holding the tessellation options used by all the shapes that
+/// don't set their own.
+///
+/// Each setting replaces the matching option of the [`FillMode`]s and
+/// [`StrokeMode`]s that don't override it, see
+/// [`OptionOverrides`](crate::draw::OptionOverrides). The defaults of this
+/// resource are the ones of lyon. Shapes are tessellated again when it
+/// changes.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// App::new()
+///     .insert_resource(TessellationSettings {
+///         tolerance: 0.5,
+///         ..TessellationSettings::default()
+///     })
+///     .add_plugin(ShapePlugin);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TessellationSettings {
+    /// The tessellation tolerance of fills and strokes, see
+    /// [`FillMode::with_tolerance`].
+    pub tolerance: f32,
+    /// The fill rule of fills, see [`FillMode::with_fill_rule`].
+    pub fill_rule: FillRule,
+    /// Whether fills handle self-intersecting paths, see
+    /// [`FillMode::with_intersections`].
+    pub handle_intersections: bool,
+}
+
+impl Default for TessellationSettings {
+    fn default() -> Self {
+        Self {
+            tolerance: FillOptions::DEFAULT_TOLERANCE,
+            fill_rule: FillOptions::DEFAULT_FILL_RULE,
+            handle_intersections: true,
+        }
+    }
+}
+
+/// An event sent by [`ShapePlugin`] when the tessellation of a shape fails.
+///
+/// The mesh of the shape is still updated, with whatever was tessellated
+/// before the error, which is often nothing.
+#[derive(Debug)]
+pub struct ShapeTessellationError {
+    /// The entity of the shape. For a [`BatchedShapes`] member, this is the
+    /// member rather than the batch.
+    pub entity: Entity,
+    /// The error returned by the tessellator.
+    pub kind: TessellationError,
+}
+
 /// A plugin that provides resources and a system to draw shapes in Bevy with
 /// less boilerplate.
+///
+/// [`Stage::Shape`] runs right after `CoreStage::Update`. Use a
+/// [`ShapePluginBuilder`] to place it elsewhere in the schedule.
+#[derive(Debug, Default, Clone, Copy)]
 pub struct ShapePlugin;
 
 impl Plugin for ShapePlugin {
     fn build(&self, app: &mut App) {
+        ShapePluginBuilder::default().build(app);
+    }
+}
+
+/// A configurable [`ShapePlugin`].
+///
+/// By default, it builds the same plugin as [`ShapePlugin`]. Use
+/// [`after_stage`](Self::after_stage) or [`before_stage`](Self::before_stage)
+/// to place [`Stage::Shape`] elsewhere in the schedule.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+/// struct PostProcessing;
+///
+/// App::new()
+///     .add_stage_after(CoreStage::Update, PostProcessing, SystemStage::parallel())
+///     // Mesh the shapes before they get post-processed.
+///     .add_plugin(ShapePluginBuilder::default().before_stage(PostProcessing));
+/// ```
+pub struct ShapePluginBuilder {
+    add_stage: Box<dyn Fn(&mut App) + Send + Sync>,
+    bounding_boxes: bool,
+}
+
+impl ShapePluginBuilder {
+    /// Runs [`Stage::Shape`] right after the `target` stage.
+    #[must_use]
+    pub fn after_stage(mut self, target: impl StageLabel + Clone) -> Self {
+        self.add_stage = Box::new(move |app| {
+            app.add_stage_after(target.clone(), Stage::Shape, SystemStage::parallel());
+        });
+        self
+    }
+
+    /// Runs [`Stage::Shape`] right before the `target` stage.
+    #[must_use]
+    pub fn before_stage(mut self, target: impl StageLabel + Clone) -> Self {
+        self.add_stage = Box::new(move |app| {
+            app.add_stage_before(target.clone(), Stage::Shape, SystemStage::parallel());
+        });
+        self
+    }
+
+    /// Draws the bounding box of every shape through [`ShapeGizmos`], for
+    /// layout debugging. The boxes are computed by
+    /// [`world_bounding_box`](crate::gizmos::world_bounding_box) every frame,
+    /// so they follow the shapes as they change or move.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// App::new().add_plugin(ShapePluginBuilder::default().with_bounding_boxes());
+    /// ```
+    #[must_use]
+    pub const fn with_bounding_boxes(mut self) -> Self {
+        self.bounding_boxes = true;
+        self
+    }
+}
+
+impl Default for ShapePluginBuilder {
+    fn default() -> Self {
+        Self {
+            add_stage: Box::new(|app| {
+                app.add_stage_after(CoreStage::Update, Stage::Shape, SystemStage::parallel());
+            }),
+            bounding_boxes: false,
+        }
+    }
+}
+
+impl Plugin for ShapePluginBuilder {
+    fn build(&self, app: &mut App) {
+        (self.add_stage)(app);
+
         let fill_tess = FillTessellator::new();
         let stroke_tess = StrokeTessellator::new();
         app.insert_resource(fill_tess)
             .insert_resource(stroke_tess)
-            .add_stage_after(
-                bevy::app::CoreStage::Update,
+            .init_resource::<MeshConfig>()
+            .init_resource::<ShapeMeshCache>()
+            .init_resource::<DeferredShapes>()
+            .init_resource::<ThrottledShapes>()
+            .init_resource::<StrokeScale>()
+            .init_resource::<Palette>()
+            .init_resource::<TessellationSettings>()
+            .init_resource::<ZoomLevel>()
+            .init_resource::<ShapeGizmos>()
+            .add_event::<ShapeTessellationError>()
+            .add_system_to_stage(
+                Stage::Shape,
+                wake_deferred_shapes_system.label(ShapeSystem::WakeDeferredShapes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                wake_throttled_shapes_system.label(ShapeSystem::WakeThrottledShapes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                release_target_meshes_system.label(ShapeSystem::ReleaseTargetMeshes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                scale_screen_space_strokes_system.label(ShapeSystem::ScaleScreenSpaceStrokes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                animate_dashes_system.label(ShapeSystem::AnimateDashes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                update_stroke_scale_system.label(ShapeSystem::UpdateStrokeScale),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                update_palette_system.label(ShapeSystem::UpdatePalette),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                update_tessellation_settings_system.label(ShapeSystem::UpdateTessellationSettings),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                update_zoom_level_system.label(ShapeSystem::UpdateZoomLevel),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                split_outlines_system
+                    .label(ShapeSystem::SplitOutlines)
+                    .after(ShapeSystem::ScaleScreenSpaceStrokes)
+                    .after(ShapeSystem::AnimateDashes),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                mesh_shapes_system
+                    .label(ShapeSystem::MeshShapes)
+                    .after(ShapeSystem::WakeDeferredShapes)
+                    .after(ShapeSystem::ReleaseTargetMeshes)
+                    .after(ShapeSystem::WakeThrottledShapes)
+                    .after(ShapeSystem::ScaleScreenSpaceStrokes)
+                    .after(ShapeSystem::AnimateDashes)
+                    .after(ShapeSystem::UpdateStrokeScale)
+                    .after(ShapeSystem::UpdatePalette)
+                    .after(ShapeSystem::UpdateZoomLevel)
+                    .after(ShapeSystem::UpdateTessellationSettings)
+                    .after(ShapeSystem::SplitOutlines),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                poll_tessellation_tasks_system.label(ShapeSystem::PollTessellationTasks),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                spawn_tessellation_tasks_system
+                    .label(ShapeSystem::MeshShapes)
+                    .after(ShapeSystem::PollTessellationTasks)
+                    .after(ShapeSystem::ReleaseTargetMeshes)
+                    .after(ShapeSystem::ScaleScreenSpaceStrokes)
+                    .after(ShapeSystem::AnimateDashes)
+                    .after(ShapeSystem::UpdateStrokeScale)
+                    .after(ShapeSystem::UpdatePalette)
+                    .after(ShapeSystem::UpdateZoomLevel)
+                    .after(ShapeSystem::UpdateTessellationSettings)
+                    .after(ShapeSystem::SplitOutlines),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                mesh_batches_system
+                    .label(ShapeSystem::MeshShapes)
+                    .after(ShapeSystem::UpdateStrokeScale)
+                    .after(ShapeSystem::UpdateZoomLevel)
+                    .after(ShapeSystem::UpdatePalette)
+                    .after(ShapeSystem::UpdateTessellationSettings),
+            )
+            .add_system_to_stage(
+                Stage::Shape,
+                draw_shape_gizmos_system.label(ShapeSystem::DrawShapeGizmos),
+            )
+            .add_system_to_stage(
                 Stage::Shape,
-                SystemStage::parallel(),
+                prune_mesh_cache_system
+                    .after(ShapeSystem::MeshShapes)
+                    .after(ShapeSystem::PollTessellationTasks),
             )
-            .add_system_to_stage(Stage::Shape, mesh_shapes_system)
             .add_plugin(RenderShapePlugin);
+
+        if self.bounding_boxes {
+            app.add_system_to_stage(
+                Stage::Shape,
+                draw_bounding_boxes_system.before(ShapeSystem::DrawShapeGizmos),
+            );
+        }
     }
 }
 
+/// The number of shapes meshed by each task of [`mesh_shapes_system`].
+const MESHING_BATCH_SIZE: usize = 32;
+
+thread_local! {
+    /// The tessellators used by the worker threads of [`mesh_shapes_system`].
+    static TESSELLATORS: RefCell<(FillTessellator, StrokeTessellator)> =
+        RefCell::new((FillTessellator::new(), StrokeTessellator::new()));
+}
+
+/// Tessellates a shape with the [`TESSELLATORS`] of the current thread.
+fn tessellate_on_thread(
+    path: &tess::path::Path,
+    mode: &DrawMode,
+    reveal: f32,
+    max_vertices: Option<usize>,
+) -> (VertexBuffers, Option<TessellationError>) {
+    TESSELLATORS.with(|tessellators| {
+        let (fill_tess, stroke_tess) = &mut *tessellators.borrow_mut();
+        tessellate_within(fill_tess, stroke_tess, path, mode, reveal, max_vertices)
+    })
+}
+
+/// A mesh generated in parallel, waiting to be stored in the mesh assets.
+struct MeshedShape {
+    entity: Entity,
+    mesh: Mesh,
+    is_empty: bool,
+    /// Whether the shape is expected to produce no geometry, like a stroke
+    /// that is not revealed yet.
+    is_empty_by_design: bool,
+    is_finite: bool,
+    /// The line width of the stroke of the shape, if it is zero or negative.
+    invalid_line_width: Option<f32>,
+    error: Option<TessellationError>,
+    cache_key: Option<ShapeKey>,
+    target: Option<Handle<Mesh>>,
+}
+
 /// Queries all the [`ShapeBundle`]s to mesh them when they are added
 /// or re-mesh them when they are changed.
-#[allow(clippy::type_complexity)]
+///
+/// Shapes are tessellated in parallel on the `ComputeTaskPool`, each worker
+/// thread using its own tessellators. The resulting meshes are then stored
+/// serially, since that needs exclusive access to the mesh assets.
+#[allow(
+    clippy::too_many_arguments,
+    clippy::too_many_lines,
+    clippy::type_complexity
+)]
 fn mesh_shapes_system(
     mut meshes: ResMut<Assets<Mesh>>,
-    mut fill_tess: ResMut<FillTessellator>,
-    mut stroke_tess: ResMut<StrokeTessellator>,
-    mut query: Query<(&DrawMode, &Path, &mut Mesh2dHandle), Or<(Changed<Path>, Changed<DrawMode>)>>,
+    config: Res<MeshConfig>,
+    settings: Res<TessellationSettings>,
+    stroke_scale: Res<StrokeScale>,
+    zoom: Res<ZoomLevel>,
+    palette: Res<Palette>,
+    mut cache: ResMut<ShapeMeshCache>,
+    pool: Res<ComputeTaskPool>,
+    mut empty_shapes: Local<HashSet<Entity>>,
+    mut unstroked_shapes: Local<HashSet<Entity>>,
+    mut deferred: ResMut<DeferredShapes>,
+    mut throttled: ResMut<ThrottledShapes>,
+    mut errors: EventWriter<ShapeTessellationError>,
+    mut query: Query<
+        (
+            Entity,
+            &DrawMode,
+            &Path,
+            (
+                Option<&ShapeDepth>,
+                Option<&Visibility>,
+                Option<&ShapeReveal>,
+                Option<&MaterialColor>,
+                Option<&TextureTiling>,
+                Option<&TargetMesh>,
+                Option<&AdaptiveTolerance>,
+            ),
+            &mut Mesh2dHandle,
+        ),
+        (
+            Without<AsyncTessellation>,
+            Without<SplitOutline>,
+            Or<(
+                Changed<Path>,
+                Changed<DrawMode>,
+                Changed<ShapeDepth>,
+                Changed<ShapeReveal>,
+                Changed<MaterialColor>,
+                Changed<TextureTiling>,
+                Changed<TargetMesh>,
+                Changed<AdaptiveTolerance>,
+            )>,
+        ),
+    >,
 ) {
-    for (tess_mode, path, mut mesh) in query.iter_mut() {
-        let mut buffers = VertexBuffers::new();
+    let meshed = Mutex::new(Vec::new());
+    // The first shape of each cache key tessellates it, its duplicates taking
+    // the cached mesh once stored.
+    let claimed = Mutex::new(HashMap::<ShapeKey, Entity>::default());
+    let duplicates = Mutex::new(Vec::new());
+    let hidden = Mutex::new(Vec::new());
+    let over_budget = Mutex::new(Vec::new());
+    let admitted = AtomicUsize::new(0);
+    query.par_for_each_mut(
+        &pool,
+        MESHING_BATCH_SIZE,
+        |(
+            entity,
+            tess_mode,
+            path,
+            (depth, visibility, reveal, material_color, tiling, target, adaptive),
+            mut mesh,
+        )| {
+            if config.defer_hidden && visibility.map_or(false, |v| !v.is_visible) {
+                lock(&hidden).push(entity);
+                return;
+            }
 
-        match tess_mode {
-            DrawMode::Fill(mode) => {
-                fill(&mut fill_tess, &path.0, mode, &mut buffers);
+            let z = depth.map_or(0.0, |depth| depth.0);
+            let reveal = reveal.map_or(1.0, |reveal| reveal.fraction);
+            let tess_mode = &default_options(*tess_mode, &settings);
+            let tess_mode = &palette_colors(*tess_mode, &palette);
+            let tess_mode = &vertex_colors(*tess_mode, material_color.is_some());
+            let tess_mode = &scale_strokes(*tess_mode, stroke_scale.0);
+            let tess_mode = &zoom_tolerance(*tess_mode, adaptive.is_some(), zoom.level);
+            let cache_key =
+                (cache.enabled && reveal >= 1.0 && tiling.is_none() && target.is_none())
+                    .then(|| ShapeMeshCache::key(path, tess_mode, z, &config));
+            if let Some(handle) = cache_key.as_ref().and_then(|key| cache.get(key)) {
+                mesh.0 = handle;
+                return;
             }
-            DrawMode::Stroke(mode) => {
-                stroke(&mut stroke_tess, &path.0, mode, &mut buffers);
+            // Only the shapes that are tessellated count against the budget.
+            if let Some(key) = &cache_key {
+                let mut claimed = lock(&claimed);
+                if claimed.contains_key(key) {
+                    lock(&duplicates).push((entity, key.clone()));
+                    return;
+                }
+                if !throttled.admit(entity, config.max_meshes_per_frame, &admitted) {
+                    lock(&over_budget).push(entity);
+                    return;
+                }
+                claimed.insert(key.clone(), entity);
+            } else if !throttled.admit(entity, config.max_meshes_per_frame, &admitted) {
+                lock(&over_budget).push(entity);
+                return;
             }
-            DrawMode::Outlined {
-                fill_mode,
-                outline_mode,
-            } => {
-                fill(&mut fill_tess, &path.0, fill_mode, &mut buffers);
-                stroke(&mut stroke_tess, &path.0, outline_mode, &mut buffers);
+
+            let (buffers, error) =
+                tessellate_on_thread(&path.0, tess_mode, reveal, config.max_vertices);
+            let shape = MeshedShape {
+                entity,
+                mesh: tile_uvs(
+                    build_mesh(&buffers, &config, z, topology(tess_mode)),
+                    tiling.map(|tiling| tiling.repeat),
+                ),
+                is_empty: buffers.vertices.is_empty(),
+                is_empty_by_design: reveal <= 0.0 && matches!(tess_mode, DrawMode::Stroke(_)),
+                is_finite: is_finite(&path.0),
+                invalid_line_width: invalid_line_width(tess_mode),
+                error,
+                cache_key,
+                target: target.map(|target| target.0.clone()),
+            };
+            lock(&meshed).push(shape);
+        },
+    );
+    deferred.0.extend(into_inner(hidden));
+    // The parallel iteration pushes the shapes in any order, so they are
+    // sorted to be woken deterministically.
+    let mut over_budget = into_inner(over_budget);
+    over_budget.sort_unstable();
+    for entity in over_budget {
+        throttled.push(entity);
+    }
+
+    for shape in into_inner(meshed) {
+        warn_if_empty(&mut empty_shapes, &shape);
+        warn_if_unstroked(&mut unstroked_shapes, &shape);
+        send_error(&mut errors, shape.entity, shape.error);
+        if let Ok((.., mut mesh)) = query.get_mut(shape.entity) {
+            store_mesh(
+                &mut meshes,
+                &mut cache,
+                &mut mesh,
+                shape.mesh,
+                shape.cache_key,
+                shape.target,
+            );
+        }
+    }
+    for (entity, key) in into_inner(duplicates) {
+        if let (Ok((.., mut mesh)), Some(handle)) = (query.get_mut(entity), cache.get(&key)) {
+            mesh.0 = handle;
+        }
+    }
+}
+
+/// Locks a mutex, ignoring poisoning.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Consumes a mutex, returning its data and ignoring poisoning.
+fn into_inner<T>(mutex: Mutex<T>) -> T {
+    mutex.into_inner().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Shapes that were left unmeshed by [`mesh_shapes_system`] because they were
+/// hidden.
+#[derive(Default)]
+struct DeferredShapes(HashSet<Entity>);
+
+/// Triggers the meshing of the deferred shapes that have been shown again.
+fn wake_deferred_shapes_system(
+    mut deferred: ResMut<DeferredShapes>,
+    removed: RemovedComponents<Path>,
+    mut query: Query<(Entity, &Visibility, &mut Path), Changed<Visibility>>,
+) {
+    if deferred.0.is_empty() {
+        return;
+    }
+
+    for entity in removed.iter() {
+        deferred.0.remove(&entity);
+    }
+    for (entity, visibility, mut path) in query.iter_mut() {
+        if visibility.is_visible && deferred.0.remove(&entity) {
+            path.set_changed();
+        }
+    }
+}
+
+/// Detaches the shapes that lost their [`TargetMesh`] from it, and marks them
+/// to be meshed again, so that they don't keep overwriting the asset.
+fn release_target_meshes_system(
+    removed: RemovedComponents<TargetMesh>,
+    mut query: Query<(&mut Mesh2dHandle, &mut Path), Without<TargetMesh>>,
+) {
+    for entity in removed.iter() {
+        if let Ok((mut mesh, mut path)) = query.get_mut(entity) {
+            mesh.0 = Handle::default();
+            path.set_changed();
+        }
+    }
+}
+
+/// Shapes that were left unmeshed by [`mesh_shapes_system`] because the
+/// [`MeshConfig::max_meshes_per_frame`] budget was spent.
+#[derive(Default)]
+struct ThrottledShapes {
+    /// The throttled shapes, in the order they were throttled.
+    queue: VecDeque<Entity>,
+    queued: HashSet<Entity>,
+    /// The throttled shapes marked to be meshed this frame.
+    woken: HashSet<Entity>,
+}
+
+impl ThrottledShapes {
+    /// Queues a shape, unless it is already queued.
+    fn push(&mut self, entity: Entity) {
+        if self.queued.insert(entity) {
+            self.queue.push_back(entity);
+        }
+    }
+
+    /// Returns whether a changed shape fits in the budget of the frame,
+    /// `admitted` counting the other changed shapes. The woken shapes are
+    /// always admitted, the others sharing what is left of the budget.
+    fn admit(&self, entity: Entity, budget: Option<usize>, admitted: &AtomicUsize) -> bool {
+        budget.map_or(true, |budget| {
+            self.woken.contains(&entity)
+                || admitted.fetch_add(1, Ordering::Relaxed) + self.woken.len() < budget
+        })
+    }
+}
+
+/// Marks the shapes that have been throttled for the longest to be meshed, up
+/// to the budget of the frame.
+fn wake_throttled_shapes_system(
+    config: Res<MeshConfig>,
+    mut throttled: ResMut<ThrottledShapes>,
+    mut query: Query<&mut Path>,
+) {
+    throttled.woken.clear();
+    let budget = config.max_meshes_per_frame.unwrap_or(usize::MAX);
+    while throttled.woken.len() < budget {
+        let entity = match throttled.queue.pop_front() {
+            Some(entity) => entity,
+            None => break,
+        };
+        throttled.queued.remove(&entity);
+        // Despawned shapes are skipped.
+        if let Ok(mut path) = query.get_mut(entity) {
+            path.set_changed();
+            throttled.woken.insert(entity);
+        }
+    }
+}
+
+/// Sets the line width of each [`ScreenSpaceStroke`] shape from the scale of
+/// the cameras. Draw modes are only touched when the width changes, to avoid
+/// needless re-tessellations.
+fn scale_screen_space_strokes_system(
+    cameras: Query<&OrthographicProjection, With<Camera>>,
+    mut shapes: Query<(&ScreenSpaceStroke, &mut DrawMode)>,
+) {
+    let scale = match cameras
+        .iter()
+        .map(|projection| projection.scale)
+        .reduce(f32::max)
+    {
+        Some(scale) => scale,
+        None => return,
+    };
+
+    for (stroke, mut mode) in shapes.iter_mut() {
+        let width = stroke.0 * scale;
+        let current = match *mode {
+            DrawMode::Stroke(stroke_mode)
+            | DrawMode::Outlined {
+                outline_mode: stroke_mode,
+                ..
+            } => stroke_mode.options.line_width,
+            DrawMode::Fill(_) | DrawMode::Wireframe(_) => continue,
+        };
+        if (current - width).abs() <= f32::EPSILON {
+            continue;
+        }
+
+        if let DrawMode::Stroke(stroke_mode)
+        | DrawMode::Outlined {
+            outline_mode: stroke_mode,
+            ..
+        } = &mut *mode
+        {
+            stroke_mode.options.line_width = width;
+        }
+    }
+}
+
+/// The factor applied to the line width of strokes when they are tessellated,
+/// as set by [`MeshConfig::scale_strokes_by_dpi`].
+struct StrokeScale(f32);
+
+impl Default for StrokeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Updates the [`StrokeScale`] from the scale factor of the windows, and marks
+/// all the shapes to be meshed again when it changes.
+fn update_stroke_scale_system(
+    config: Res<MeshConfig>,
+    windows: Option<Res<Windows>>,
+    mut stroke_scale: ResMut<StrokeScale>,
+    mut shapes: Query<&mut DrawMode>,
+) {
+    #[allow(clippy::cast_possible_truncation)]
+    let scale = match windows.filter(|_| config.scale_strokes_by_dpi) {
+        Some(windows) => windows
+            .iter()
+            .map(Window::scale_factor)
+            .reduce(f64::max)
+            .map_or(1.0, |scale| scale as f32),
+        None => 1.0,
+    };
+    if (stroke_scale.0 - scale).abs() <= f32::EPSILON {
+        return;
+    }
+
+    stroke_scale.0 = scale;
+    for mut mode in shapes.iter_mut() {
+        mode.set_changed();
+    }
+}
+
+/// The power of two by which the tolerances of [`AdaptiveTolerance`] shapes
+/// are multiplied, following the zoom of the cameras.
+struct ZoomLevel {
+    level: f32,
+}
+
+impl Default for ZoomLevel {
+    fn default() -> Self {
+        Self { level: 1.0 }
+    }
+}
+
+/// How far, in powers of two, the scale of the cameras must move from the
+/// current [`ZoomLevel`] for it to change.
+const ZOOM_HYSTERESIS: f32 = 0.75;
+
+/// Updates the [`ZoomLevel`] from the scale of the cameras, and marks the
+/// [`AdaptiveTolerance`] shapes to be meshed again when it changes.
+fn update_zoom_level_system(
+    cameras: Query<&OrthographicProjection, With<Camera>>,
+    mut zoom: ResMut<ZoomLevel>,
+    mut shapes: Query<&mut DrawMode, With<AdaptiveTolerance>>,
+) {
+    let scale = match cameras
+        .iter()
+        .map(|projection| projection.scale)
+        .filter(|&scale| scale > 0.0 && scale.is_finite())
+        .reduce(f32::min)
+    {
+        Some(scale) => scale,
+        None => return,
+    };
+    let exponent = scale.log2();
+    if (exponent - zoom.level.log2()).abs() <= ZOOM_HYSTERESIS {
+        return;
+    }
+
+    zoom.level = exponent.round().exp2();
+    for mut mode in shapes.iter_mut() {
+        mode.set_changed();
+    }
+}
+
+/// Marks all the shapes to be meshed again when the [`TessellationSettings`]
+/// change.
+fn update_tessellation_settings_system(
+    settings: Res<TessellationSettings>,
+    mut shapes: Query<&mut DrawMode>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut mode in shapes.iter_mut() {
+        mode.set_changed();
+    }
+}
+
+/// Marks the shapes filled from the [`Palette`] to be meshed again when it
+/// changes.
+fn update_palette_system(palette: Res<Palette>, mut shapes: Query<&mut DrawMode>) {
+    if !palette.is_changed() {
+        return;
+    }
+
+    for mut mode in shapes.iter_mut() {
+        let indexed = match *mode {
+            DrawMode::Fill(fill_mode) | DrawMode::Wireframe(fill_mode) => fill_mode.palette_index,
+            DrawMode::Outlined { fill_mode, .. } => fill_mode.palette_index,
+            DrawMode::Stroke(_) => None,
+        };
+        if indexed.is_some() {
+            mode.set_changed();
+        }
+    }
+}
+
+/// Advances the dash offset of each [`DashAnimation`] shape. Draw modes are
+/// only touched while the dashes move, to avoid needless re-tessellations.
+fn animate_dashes_system(time: Res<Time>, mut shapes: Query<(&DashAnimation, &mut DrawMode)>) {
+    let delta = time.delta_seconds();
+    for (animation, mut mode) in shapes.iter_mut() {
+        let dash = match *mode {
+            DrawMode::Stroke(stroke_mode)
+            | DrawMode::Outlined {
+                outline_mode: stroke_mode,
+                ..
+            } => stroke_mode.dash,
+            DrawMode::Fill(_) | DrawMode::Wireframe(_) => None,
+        };
+        let period = match dash {
+            Some(dash) if animation.speed != 0.0 && delta > 0.0 => dash.dash + dash.gap,
+            _ => continue,
+        };
+
+        if let DrawMode::Stroke(stroke_mode)
+        | DrawMode::Outlined {
+            outline_mode: stroke_mode,
+            ..
+        } = &mut *mode
+        {
+            if let Some(dash) = &mut stroke_mode.dash {
+                // Wrap the offset so that it doesn't lose precision over time.
+                dash.offset = animation
+                    .speed
+                    .mul_add(-delta, dash.offset)
+                    .rem_euclid(period.max(f32::EPSILON));
             }
         }
+    }
+}
 
-        mesh.0 = meshes.add(build_mesh(&buffers));
+/// Warns about shapes that produced no geometry. Shapes that stay empty
+/// across changes are only reported once. Shapes that are empty by design are
+/// not reported, and shapes with an invalid line width are reported by
+/// [`warn_if_unstroked`] instead.
+fn warn_if_empty(empty_shapes: &mut HashSet<Entity>, shape: &MeshedShape) {
+    if !shape.is_empty || shape.is_empty_by_design || shape.invalid_line_width.is_some() {
+        empty_shapes.remove(&shape.entity);
+    } else if empty_shapes.insert(shape.entity) {
+        if shape.is_finite {
+            warn!(
+                "The shape of entity {:?} produced no geometry. Its path may be empty or \
+                 degenerate.",
+                shape.entity
+            );
+        } else {
+            warn!(
+                "The path of entity {:?} has NaN or infinite coordinates, so it was not \
+                 tessellated.",
+                shape.entity
+            );
+        }
     }
 }
 
-#[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &FillOptions
-fn fill(
-    tess: &mut ResMut<FillTessellator>,
-    path: &tess::path::Path,
-    mode: &FillMode,
-    buffers: &mut VertexBuffers,
+/// Warns about shapes whose stroke has a zero or negative line width, so it
+/// is not drawn. Each shape is only reported once until its width is fixed.
+fn warn_if_unstroked(unstroked_shapes: &mut HashSet<Entity>, shape: &MeshedShape) {
+    match shape.invalid_line_width {
+        None => {
+            unstroked_shapes.remove(&shape.entity);
+        }
+        Some(width) if unstroked_shapes.insert(shape.entity) => warn!(
+            "The stroke of entity {:?} has a line width of {}, so it is not drawn.",
+            shape.entity, width
+        ),
+        Some(_) => {}
+    }
+}
+
+/// Returns the line width of the stroke of `mode`, if it is zero or negative.
+fn invalid_line_width(mode: &DrawMode) -> Option<f32> {
+    match mode {
+        DrawMode::Stroke(stroke_mode)
+        | DrawMode::Outlined {
+            outline_mode: stroke_mode,
+            ..
+        } => Some(stroke_mode.options.line_width).filter(|&width| width <= 0.0),
+        DrawMode::Fill(_) | DrawMode::Wireframe(_) => None,
+    }
+}
+
+/// Sends a [`ShapeTessellationError`] if the tessellation of `entity` failed.
+fn send_error(
+    errors: &mut EventWriter<ShapeTessellationError>,
+    entity: Entity,
+    error: Option<TessellationError>,
 ) {
-    if let Err(e) = tess.tessellate_path(
-        path,
-        &mode.options,
-        &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
-    ) {
-        error!("FillTessellator error: {:?}", e);
+    if let Some(kind) = error {
+        errors.send(ShapeTessellationError { entity, kind });
     }
 }
 
-#[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &StrokeOptions
-fn stroke(
-    tess: &mut ResMut<StrokeTessellator>,
-    path: &tess::path::Path,
-    mode: &StrokeMode,
-    buffers: &mut VertexBuffers,
+/// A pending tessellation of a shape marked with [`AsyncTessellation`].
+///
+/// Replacing this component drops the previous task, which cancels it.
+#[derive(Component)]
+struct TessellationTask {
+    task: Task<(VertexBuffers, Option<TessellationError>)>,
+    z: f32,
+    topology: PrimitiveTopology,
+    cache_key: Option<ShapeKey>,
+    uv_repeat: Option<Vec2>,
+    target: Option<Handle<Mesh>>,
+}
+
+/// Spawns a tessellation task for each changed [`AsyncTessellation`] shape.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn spawn_tessellation_tasks_system(
+    mut commands: Commands,
+    pool: Res<AsyncComputeTaskPool>,
+    config: Res<MeshConfig>,
+    settings: Res<TessellationSettings>,
+    stroke_scale: Res<StrokeScale>,
+    zoom: Res<ZoomLevel>,
+    palette: Res<Palette>,
+    cache: Res<ShapeMeshCache>,
+    mut query: Query<
+        (
+            Entity,
+            &DrawMode,
+            &Path,
+            (
+                Option<&ShapeDepth>,
+                Option<&ShapeReveal>,
+                Option<&MaterialColor>,
+                Option<&TextureTiling>,
+                Option<&TargetMesh>,
+                Option<&AdaptiveTolerance>,
+            ),
+            &mut Mesh2dHandle,
+        ),
+        (
+            With<AsyncTessellation>,
+            Without<SplitOutline>,
+            Or<(
+                Changed<Path>,
+                Changed<DrawMode>,
+                Changed<ShapeDepth>,
+                Changed<ShapeReveal>,
+                Changed<MaterialColor>,
+                Changed<TextureTiling>,
+                Changed<TargetMesh>,
+                Changed<AdaptiveTolerance>,
+            )>,
+        ),
+    >,
 ) {
-    if let Err(e) = tess.tessellate_path(
+    for (
+        entity,
+        tess_mode,
         path,
-        &mode.options,
-        &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
-    ) {
-        error!("StrokeTessellator error: {:?}", e);
+        (depth, reveal, material_color, tiling, target, adaptive),
+        mut mesh,
+    ) in query.iter_mut()
+    {
+        let z = depth.map_or(0.0, |depth| depth.0);
+        let reveal = reveal.map_or(1.0, |reveal| reveal.fraction);
+        let tess_mode = &default_options(*tess_mode, &settings);
+        let tess_mode = &palette_colors(*tess_mode, &palette);
+        let tess_mode = &vertex_colors(*tess_mode, material_color.is_some());
+        let tess_mode = &scale_strokes(*tess_mode, stroke_scale.0);
+        let tess_mode = &zoom_tolerance(*tess_mode, adaptive.is_some(), zoom.level);
+        let cache_key = (cache.enabled && reveal >= 1.0 && tiling.is_none() && target.is_none())
+            .then(|| ShapeMeshCache::key(path, tess_mode, z, &config));
+        if let Some(handle) = cache_key.as_ref().and_then(|key| cache.get(key)) {
+            mesh.0 = handle;
+            commands.entity(entity).remove::<TessellationTask>();
+            continue;
+        }
+
+        let path = path.0.clone();
+        let tess_mode = *tess_mode;
+        let max_vertices = config.max_vertices;
+        let task = pool.spawn(async move {
+            tessellate_within(
+                &mut FillTessellator::new(),
+                &mut StrokeTessellator::new(),
+                &path,
+                &tess_mode,
+                reveal,
+                max_vertices,
+            )
+        });
+        commands.entity(entity).insert(TessellationTask {
+            task,
+            z,
+            topology: topology(&tess_mode),
+            cache_key,
+            uv_repeat: tiling.map(|tiling| tiling.repeat),
+            target: target.map(|target| target.0.clone()),
+        });
     }
 }
 
-fn build_mesh(buffers: &VertexBuffers) -> Mesh {
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-    mesh.set_indices(Some(Indices::U32(buffers.indices.clone())));
-    mesh.insert_attribute(
-        Mesh::ATTRIBUTE_POSITION,
-        buffers
-            .vertices
-            .iter()
-            .map(|v| [v.position[0], v.position[1], 0.0])
-            .collect::<Vec<[f32; 3]>>(),
-    );
-    mesh.insert_attribute(
-        Mesh::ATTRIBUTE_COLOR,
-        buffers
-            .vertices
+/// Installs the mesh of each completed [`TessellationTask`].
+fn poll_tessellation_tasks_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<MeshConfig>,
+    mut cache: ResMut<ShapeMeshCache>,
+    mut errors: EventWriter<ShapeTessellationError>,
+    mut query: Query<(Entity, &mut TessellationTask, &mut Mesh2dHandle)>,
+) {
+    for (entity, mut task, mut mesh) in query.iter_mut() {
+        if let Some((buffers, error)) = future::block_on(future::poll_once(&mut task.task)) {
+            send_error(&mut errors, entity, error);
+            store_mesh(
+                &mut meshes,
+                &mut cache,
+                &mut mesh,
+                tile_uvs(
+                    build_mesh(&buffers, &config, task.z, task.topology),
+                    task.uv_repeat,
+                ),
+                task.cache_key.take(),
+                task.target.take(),
+            );
+            commands.entity(entity).remove::<TessellationTask>();
+        }
+    }
+}
+
+/// Merges the members of each [`BatchedShapes`] into a single mesh, when the
+/// batch or any of its members has changed.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn mesh_batches_system(
+    mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<MeshConfig>,
+    settings: Res<TessellationSettings>,
+    stroke_scale: Res<StrokeScale>,
+    zoom: Res<ZoomLevel>,
+    palette: Res<Palette>,
+    mut cache: ResMut<ShapeMeshCache>,
+    mut fill_tess: ResMut<FillTessellator>,
+    mut stroke_tess: ResMut<StrokeTessellator>,
+    mut errors: EventWriter<ShapeTessellationError>,
+    removed: RemovedComponents<Path>,
+    members: Query<(
+        Entity,
+        &Path,
+        &DrawMode,
+        (
+            Option<&ShapeDepth>,
+            Option<&MaterialColor>,
+            Option<&AdaptiveTolerance>,
+            Option<&Transform>,
+        ),
+    )>,
+    changed_members: Query<
+        (),
+        (
+            With<Path>,
+            Or<(
+                Changed<Path>,
+                Changed<DrawMode>,
+                Changed<ShapeDepth>,
+                Changed<MaterialColor>,
+                Changed<AdaptiveTolerance>,
+                Changed<Transform>,
+            )>,
+        ),
+    >,
+    mut batches: Query<(
+        &BatchedShapes,
+        ChangeTrackers<BatchedShapes>,
+        &mut Mesh2dHandle,
+    )>,
+) {
+    let removed = removed.iter().collect::<HashSet<_>>();
+    for (batch, tracker, mut mesh) in batches.iter_mut() {
+        let needs_update = tracker.is_changed()
+            || batch
+                .0
+                .iter()
+                .any(|member| removed.contains(member) || changed_members.get(*member).is_ok());
+        if !needs_update {
+            continue;
+        }
+
+        let mut buffers = VertexBuffers::new();
+        let mut depths = Vec::new();
+        for (member, path, mode, (depth, material_color, adaptive, transform)) in batch
+            .0
             .iter()
-            .map(|v| v.color)
-            .collect::<Vec<u32>>(),
+            .filter_map(|member| members.get(*member).ok())
+        {
+            let mode = default_options(*mode, &settings);
+            let mode = palette_colors(mode, &palette);
+            let mode = vertex_colors(mode, material_color.is_some());
+            let mode = scale_strokes(mode, stroke_scale.0);
+            let mode = zoom_tolerance(mode, adaptive.is_some(), zoom.level);
+            let (member_buffers, error) = tessellate_within(
+                &mut fill_tess,
+                &mut stroke_tess,
+                &path.0,
+                &mode,
+                1.0,
+                config.max_vertices,
+            );
+            send_error(&mut errors, member, error);
+
+            // Members are post-processed one by one, so that vertices of
+            // different members, which may have different depths, are never
+            // welded together.
+            let mut member_buffers = post_process(&member_buffers, &config).into_owned();
+            let transform = transform.copied().unwrap_or_default();
+            let z = depth.map_or(0.0, |depth| depth.0);
+            for vertex in &mut member_buffers.vertices {
+                let [x, y] = vertex.position;
+                let position = transform.mul_vec3(Vec3::new(x, y, z));
+                vertex.position = [position.x, position.y];
+                depths.push(position.z);
+            }
+            append_buffers(&mut buffers, &member_buffers);
+        }
+
+        store_mesh(
+            &mut meshes,
+            &mut cache,
+            &mut mesh,
+            assemble_mesh(
+                &buffers,
+                |i| depths[i],
+                &config,
+                PrimitiveTopology::TriangleList,
+            ),
+            None,
+            None,
+        );
+    }
+}
+
+/// The color of the boxes drawn by [`ShapePluginBuilder::with_bounding_boxes`].
+const BOUNDING_BOX_COLOR: Color = Color::FUCHSIA;
+
+/// Draws the world space bounding box of every shape as a gizmo.
+fn draw_bounding_boxes_system(
+    mut gizmos: ResMut<ShapeGizmos>,
+    query: Query<(&Path, &GlobalTransform)>,
+) {
+    for (path, transform) in query.iter() {
+        let bbox = world_bounding_box(path, transform);
+        gizmos.rect(
+            (bbox.min + bbox.max) / 2.0,
+            bbox.max - bbox.min,
+            BOUNDING_BOX_COLOR,
+        );
+    }
+}
+
+/// Merges the shapes drawn through [`ShapeGizmos`] into the mesh of a single
+/// entity, spawned the first time gizmos are drawn.
+#[allow(clippy::too_many_arguments)]
+fn draw_shape_gizmos_system(
+    mut commands: Commands,
+    mut gizmos: ResMut<ShapeGizmos>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<MeshConfig>,
+    mut cache: ResMut<ShapeMeshCache>,
+    mut fill_tess: ResMut<FillTessellator>,
+    mut stroke_tess: ResMut<StrokeTessellator>,
+    mut gizmo_entity: Local<Option<Entity>>,
+    mut was_drawn: Local<bool>,
+    mut query: Query<&mut Mesh2dHandle>,
+) {
+    let shapes = gizmos.take();
+    // Clearing the mesh is only needed once after the gizmos stop being drawn.
+    if shapes.is_empty() && !*was_drawn {
+        return;
+    }
+    *was_drawn = !shapes.is_empty();
+
+    let mut buffers = VertexBuffers::new();
+    for (path, mode) in &shapes {
+        let (shape_buffers, _) = tessellate_within(
+            &mut fill_tess,
+            &mut stroke_tess,
+            path,
+            mode,
+            1.0,
+            config.max_vertices,
+        );
+        append_buffers(&mut buffers, &shape_buffers);
+    }
+    let mesh = build_mesh(
+        &buffers,
+        &config,
+        gizmos.depth,
+        PrimitiveTopology::TriangleList,
     );
 
-    mesh
+    if let Some(mut handle) = gizmo_entity.and_then(|entity| query.get_mut(entity).ok()) {
+        store_mesh(&mut meshes, &mut cache, &mut handle, mesh, None, None);
+    } else {
+        let entity = commands
+            .spawn_bundle((
+                Shape,
+                Mesh2dHandle(meshes.add(mesh)),
+                Transform::default(),
+                GlobalTransform::default(),
+                Visibility::default(),
+                ComputedVisibility::default(),
+            ))
+            .id();
+        *gizmo_entity = Some(entity);
+    }
+}
+
+/// How far in front of the other part of a [`SplitOutline`] shape the part drawn
+/// on top is placed.
+const SPLIT_OUTLINE_Z_OFFSET: f32 = 1e-3;
+
+/// Returns the draw mode of the fill child of a [`SplitOutline`] shape, along
+/// with the draw mode and the depth of its outline child, if it has one.
+fn split_outline(mode: DrawMode) -> (DrawMode, Option<(DrawMode, f32)>) {
+    match mode {
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => {
+            let outline_z = if outline_mode.behind_fill {
+                -SPLIT_OUTLINE_Z_OFFSET
+            } else {
+                SPLIT_OUTLINE_Z_OFFSET
+            };
+            (
+                DrawMode::Fill(fill_mode),
+                Some((DrawMode::Stroke(outline_mode), outline_z)),
+            )
+        }
+        mode => (mode, None),
+    }
+}
+
+/// Spawns a child of a [`SplitOutline`] shape.
+fn spawn_split_part(
+    commands: &mut Commands,
+    parent: Entity,
+    path: &Path,
+    mode: DrawMode,
+    z: f32,
+) -> Entity {
+    let part = commands
+        .spawn_bundle(ShapeBundle {
+            path: Path(path.0.clone()),
+            mode,
+            transform: Transform::from_xyz(0.0, 0.0, z),
+            ..ShapeBundle::default()
+        })
+        .id();
+    commands.entity(parent).push_children(&[part]);
+    part
+}
+
+/// Spawns the children of the [`SplitOutline`] shapes, and passes them the
+/// changes of their parent.
+#[allow(clippy::type_complexity)]
+fn split_outlines_system(
+    mut commands: Commands,
+    mut shapes: Query<
+        (
+            Entity,
+            &Path,
+            &DrawMode,
+            &mut SplitOutline,
+            &mut Mesh2dHandle,
+        ),
+        Or<(Changed<Path>, Changed<DrawMode>, Changed<SplitOutline>)>,
+    >,
+    mut parts: Query<(&mut Path, &mut DrawMode, &mut Transform), Without<SplitOutline>>,
+) {
+    for (entity, path, mode, mut split, mut mesh) in shapes.iter_mut() {
+        let (fill_mode, outline) = split_outline(*mode);
+
+        match split.fill {
+            Some(fill) => {
+                if let Ok((mut part_path, mut part_mode, _)) = parts.get_mut(fill) {
+                    part_path.0 = path.0.clone();
+                    *part_mode = fill_mode;
+                }
+            }
+            None => {
+                split.fill = Some(spawn_split_part(
+                    &mut commands,
+                    entity,
+                    path,
+                    fill_mode,
+                    0.0,
+                ));
+                // The parent is no longer drawn itself.
+                *mesh = Mesh2dHandle::default();
+            }
+        }
+
+        match (split.outline, outline) {
+            (Some(part), Some((outline_mode, outline_z))) => {
+                if let Ok((mut part_path, mut part_mode, mut transform)) = parts.get_mut(part) {
+                    part_path.0 = path.0.clone();
+                    *part_mode = outline_mode;
+                    transform.translation.z = outline_z;
+                }
+            }
+            (None, Some((outline_mode, outline_z))) => {
+                split.outline = Some(spawn_split_part(
+                    &mut commands,
+                    entity,
+                    path,
+                    outline_mode,
+                    outline_z,
+                ));
+            }
+            (Some(part), None) => {
+                commands.entity(part).despawn_recursive();
+                split.outline = None;
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Appends the vertices and indices of `other` to `buffers`, offsetting the
+/// indices so that they keep pointing at the same vertices.
+fn append_buffers(buffers: &mut VertexBuffers, other: &VertexBuffers) {
+    let offset = buffers.vertices.len() as u32;
+    buffers.vertices.extend_from_slice(&other.vertices);
+    buffers
+        .indices
+        .extend(other.indices.iter().map(|index| index + offset));
+}
+
+/// Counts the entities using each cached mesh, so that the cache drops the
+/// meshes that are no longer used because their shapes were despawned or
+/// meshed again.
+///
+/// The meshes that are not shared through the cache don't need this, since
+/// they are freed along with the `Mesh2dHandle` of their shape.
+fn prune_mesh_cache_system(
+    mut cache: ResMut<ShapeMeshCache>,
+    removed: RemovedComponents<Mesh2dHandle>,
+    changed: Query<(Entity, &Mesh2dHandle), Changed<Mesh2dHandle>>,
+) {
+    if cache.is_empty() {
+        return;
+    }
+
+    for entity in removed.iter() {
+        cache.release(entity);
+    }
+    for (entity, mesh) in changed.iter() {
+        cache.track(entity, mesh.0.id);
+    }
+}
+
+/// Fills `path` and returns the resulting mesh, as [`ShapePlugin`] would with
+/// the default [`MeshConfig`].
+///
+/// This doesn't need any ECS resource, so it can be used by tools or outside
+/// of a Bevy app.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::plugin::tessellate_fill;
+/// #
+/// let rectangle = ShapePath::build_as(&shapes::Rectangle::default());
+/// let mesh = tessellate_fill(&rectangle, &FillMode::color(Color::RED));
+///
+/// assert_eq!(mesh.count_vertices(), 4);
+/// assert_eq!(mesh.indices().unwrap().len(), 6);
+/// ```
+#[must_use]
+pub fn tessellate_fill(path: &Path, mode: &FillMode) -> Mesh {
+    let mut buffers = VertexBuffers::new();
+    if let Err(e) = fill(&mut FillTessellator::new(), &path.0, mode, &mut buffers) {
+        error!("FillTessellator error: {:?}", e);
+    }
+
+    build_mesh(
+        &buffers,
+        &MeshConfig::default(),
+        0.0,
+        PrimitiveTopology::TriangleList,
+    )
+}
+
+/// Strokes `path` and returns the resulting mesh, as [`ShapePlugin`] would
+/// with the default [`MeshConfig`].
+///
+/// This doesn't need any ECS resource, so it can be used by tools or outside
+/// of a Bevy app.
+///
+/// Strokes with a zero line width produce an empty mesh. Negative line widths
+/// are an error, which is logged, and also produce an empty mesh.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::plugin::tessellate_stroke;
+/// #
+/// let circle = ShapePath::build_as(&shapes::Circle::default());
+/// let vertex_count =
+///     |width| tessellate_stroke(&circle, &StrokeMode::new(Color::BLACK, width)).count_vertices();
+///
+/// assert!(vertex_count(1.0) > 0);
+/// assert_eq!(vertex_count(0.0), 0);
+/// assert_eq!(vertex_count(-1.0), 0);
+/// ```
+#[must_use]
+pub fn tessellate_stroke(path: &Path, mode: &StrokeMode) -> Mesh {
+    let mut buffers = VertexBuffers::new();
+    if let Err(e) = stroke(
+        &mut StrokeTessellator::new(),
+        &path.0,
+        mode,
+        1.0,
+        &mut buffers,
+    ) {
+        error!("StrokeTessellator error: {:?}", e);
+    }
+
+    build_mesh(
+        &buffers,
+        &MeshConfig::default(),
+        0.0,
+        PrimitiveTopology::TriangleList,
+    )
+}
+
+/// Tessellates `path` with the given mode and returns the resulting mesh, as
+/// [`ShapePlugin`] would with `config` as its [`MeshConfig`].
+///
+/// This gives control over the topology, the winding and the attributes of
+/// meshes exported for other uses, like extruding them in 3D. Tessellation
+/// errors are logged.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::{
+/// #     prelude::*,
+/// #     render::mesh::{PrimitiveTopology, VertexAttributeValues},
+/// # };
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::plugin::tessellate_with_config;
+/// #
+/// let rectangle = ShapePath::build_as(&shapes::Rectangle::default());
+/// let mode = DrawMode::Fill(FillMode::color(Color::RED));
+///
+/// let list = tessellate_with_config(&rectangle, &mode, &MeshConfig::default());
+/// assert_eq!(list.primitive_topology(), PrimitiveTopology::TriangleList);
+/// assert_eq!(list.indices().unwrap().len(), 6);
+/// assert!(list.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+///
+/// let strip = tessellate_with_config(
+///     &rectangle,
+///     &mode,
+///     &MeshConfig {
+///         triangle_strips: true,
+///         include_normals: true,
+///         include_uvs: true,
+///         ..MeshConfig::default()
+///     },
+/// );
+/// assert_eq!(strip.primitive_topology(), PrimitiveTopology::TriangleStrip);
+/// assert_eq!(strip.indices().unwrap().len(), 4);
+/// assert!(strip.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+/// assert!(strip.attribute(Mesh::ATTRIBUTE_UV_0).is_some());
+///
+/// // The first triangle of a clockwise mesh has a negative signed area.
+/// let cw = tessellate_with_config(
+///     &rectangle,
+///     &mode,
+///     &MeshConfig {
+///         winding: MeshWinding::Cw,
+///         ..MeshConfig::default()
+///     },
+/// );
+/// let positions = match cw.attribute(Mesh::ATTRIBUTE_POSITION) {
+///     Some(VertexAttributeValues::Float32x3(positions)) => positions,
+///     _ => unreachable!(),
+/// };
+/// let triangle = cw
+///     .indices()
+///     .unwrap()
+///     .iter()
+///     .take(3)
+///     .map(|i| Vec3::from(positions[i]).truncate())
+///     .collect::<Vec<_>>();
+/// assert!((triangle[1] - triangle[0]).perp_dot(triangle[2] - triangle[0]) < 0.0);
+///
+/// let wireframe = DrawMode::Wireframe(FillMode::color(Color::RED));
+/// let lines = tessellate_with_config(&rectangle, &wireframe, &MeshConfig::default());
+/// assert_eq!(lines.primitive_topology(), PrimitiveTopology::LineList);
+/// ```
+#[must_use]
+pub fn tessellate_with_config(path: &Path, mode: &DrawMode, config: &MeshConfig) -> Mesh {
+    let (buffers, error) = tessellate_within(
+        &mut FillTessellator::new(),
+        &mut StrokeTessellator::new(),
+        &path.0,
+        mode,
+        1.0,
+        config.max_vertices,
+    );
+    if let Some(e) = error {
+        error!("Tessellation error: {:?}", e);
+    }
+
+    build_mesh(&buffers, config, 0.0, topology(mode))
+}
+
+/// Returns the number of triangles in the mesh that [`ShapePlugin`] would
+/// generate for `path` with the given mode, without building the mesh.
+///
+/// The triangles are only counted, except for fills with antialiasing and
+/// strokes with a [`StrokeGradient`], which are tessellated as usual. For a
+/// [`DrawMode::Wireframe`], this is the number of triangles whose edges are
+/// drawn.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use bevy_prototype_lyon::plugin::{estimate_triangle_count, tessellate_fill};
+/// #
+/// let circle = ShapePath::build_as(&shapes::Circle::default());
+/// let fill_mode = FillMode::color(Color::RED);
+/// let mesh = tessellate_fill(&circle, &fill_mode);
+///
+/// assert_eq!(
+///     estimate_triangle_count(&circle, &DrawMode::Fill(fill_mode)),
+///     mesh.indices().unwrap().len() / 3,
+/// );
+/// ```
+#[must_use]
+pub fn estimate_triangle_count(path: &Path, mode: &DrawMode) -> usize {
+    let path = &path.0;
+    if !is_finite(path) {
+        return 0;
+    }
+
+    let tessellated = |mode: DrawMode| {
+        let mut fill_tess = FillTessellator::new();
+        let mut stroke_tess = StrokeTessellator::new();
+        let (buffers, _) = tessellate(&mut fill_tess, &mut stroke_tess, path, &mode, 1.0);
+        buffers.indices.len() / 3
+    };
+    let count_fill = |mode: &FillMode| {
+        if mode.antialiasing.filter(|&width| width > 0.0).is_some() {
+            return tessellated(DrawMode::Fill(*mode));
+        }
+        let mut counter = TriangleCounter::default();
+        FillTessellator::new()
+            .tessellate_path(&fill_path(path, mode), &mode.options, &mut counter)
+            .map_or(0, |_| counter.triangles)
+    };
+    let count_stroke = |mode: &StrokeMode| {
+        if mode.options.line_width <= 0.0 {
+            return 0;
+        }
+        if mode.gradient.is_some() {
+            return tessellated(DrawMode::Stroke(*mode));
+        }
+        let mut counter = TriangleCounter::default();
+        StrokeTessellator::new()
+            .tessellate_path(&stroke_path(path, mode, 1.0), &mode.options, &mut counter)
+            .map_or(0, |_| counter.triangles)
+    };
+
+    match mode {
+        DrawMode::Fill(mode) | DrawMode::Wireframe(mode) => count_fill(mode),
+        DrawMode::Stroke(mode) => count_stroke(mode),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => count_fill(fill_mode) + count_stroke(outline_mode),
+    }
+}
+
+/// Stores the mesh of a shape, into the `target` asset of a [`TargetMesh`]
+/// shape, or sharing it through the cache if a `cache_key` is given. The mesh
+/// is dropped if the cache already holds one for that key.
+fn store_mesh(
+    meshes: &mut Assets<Mesh>,
+    cache: &mut ShapeMeshCache,
+    handle: &mut Mut<Mesh2dHandle>,
+    new_mesh: Mesh,
+    cache_key: Option<ShapeKey>,
+    target: Option<Handle<Mesh>>,
+) {
+    if let Some(target) = target {
+        // Also creates the asset if the user removed it.
+        meshes.set_untracked(&target, new_mesh);
+        handle.0 = target;
+        return;
+    }
+    if let Some(key) = cache_key {
+        // An identical shape may have been stored since the cache was checked,
+        // for example when both were spawned in the same frame.
+        handle.0 = cache
+            .get(&key)
+            .unwrap_or_else(|| cache.insert(key, meshes.add(new_mesh)));
+        return;
+    }
+
+    // Overwrite the existing asset in place, so that we don't churn mesh
+    // assets each time the shape changes. Meshes that may be used by other
+    // shapes are left untouched.
+    let existing = if cache.is_shared(&handle.0) {
+        None
+    } else {
+        meshes.get_mut(&handle.0)
+    };
+    if let Some(existing) = existing {
+        *existing = new_mesh;
+    } else {
+        handle.0 = meshes.add(new_mesh);
+    }
+}
+
+/// The number of times [`tessellate_within`] tries a coarser tolerance.
+const MAX_COARSENING_ATTEMPTS: usize = 8;
+
+/// Tessellates like [`tessellate`], then tessellates again with coarser
+/// tolerances while the shape has more than `max_vertices` vertices.
+fn tessellate_within(
+    fill_tess: &mut FillTessellator,
+    stroke_tess: &mut StrokeTessellator,
+    path: &tess::path::Path,
+    mode: &DrawMode,
+    reveal: f32,
+    max_vertices: Option<usize>,
+) -> (VertexBuffers, Option<TessellationError>) {
+    let mut tessellated = tessellate(fill_tess, stroke_tess, path, mode, reveal);
+    let max_vertices = match max_vertices {
+        Some(max_vertices) => max_vertices,
+        None => return tessellated,
+    };
+
+    let mut mode = *mode;
+    for _ in 0..MAX_COARSENING_ATTEMPTS {
+        let count = tessellated.0.vertices.len();
+        if count <= max_vertices {
+            return tessellated;
+        }
+
+        // The number of vertices approximating a curve grows with the inverse
+        // square root of the tolerance.
+        let ratio = count as f32 / max_vertices.max(1) as f32;
+        mode = coarsen(mode, ratio.powi(2).max(2.0));
+        let coarser = tessellate(fill_tess, stroke_tess, path, &mode, reveal);
+        if coarser.0.vertices.len() >= count {
+            break;
+        }
+        tessellated = coarser;
+    }
+
+    let count = tessellated.0.vertices.len();
+    if count > max_vertices {
+        warn!(
+            "A shape has {} vertices, over the budget of {}, even with a coarser tolerance.",
+            count, max_vertices
+        );
+    }
+    tessellated
+}
+
+/// Returns `mode` with its fills colored from the palette, as done by
+/// [`Palette::resolve`].
+fn palette_colors(mode: DrawMode, palette: &Palette) -> DrawMode {
+    match mode {
+        DrawMode::Fill(fill_mode) => DrawMode::Fill(palette.resolve(fill_mode)),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => DrawMode::Outlined {
+            fill_mode: palette.resolve(fill_mode),
+            outline_mode,
+        },
+        DrawMode::Wireframe(fill_mode) => DrawMode::Wireframe(palette.resolve(fill_mode)),
+        DrawMode::Stroke(_) => mode,
+    }
+}
+
+/// Returns the draw mode whose colors end up in the vertices of a shape: `mode`
+/// itself, or `mode` in plain white if the shape has a [`MaterialColor`].
+fn vertex_colors(mode: DrawMode, material_color: bool) -> DrawMode {
+    if !material_color {
+        return mode;
+    }
+
+    let white_fill = |fill: FillMode| FillMode {
+        color: Color::WHITE,
+        gradient: None,
+        ..fill
+    };
+    let white_stroke = |stroke: StrokeMode| StrokeMode {
+        color: Color::WHITE,
+        gradient: None,
+        ..stroke
+    };
+    match mode {
+        DrawMode::Fill(fill_mode) => DrawMode::Fill(white_fill(fill_mode)),
+        DrawMode::Stroke(stroke_mode) => DrawMode::Stroke(white_stroke(stroke_mode)),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => DrawMode::Outlined {
+            fill_mode: white_fill(fill_mode),
+            outline_mode: white_stroke(outline_mode),
+        },
+        DrawMode::Wireframe(fill_mode) => DrawMode::Wireframe(white_fill(fill_mode)),
+    }
+}
+
+/// Replaces the options of `mode` that it does not override with the
+/// [`TessellationSettings`].
+fn default_options(mode: DrawMode, settings: &TessellationSettings) -> DrawMode {
+    let default_fill = |mut fill: FillMode| {
+        let options = &mut fill.options;
+        if !fill.overrides.tolerance {
+            options.tolerance = settings.tolerance;
+        }
+        if !fill.overrides.fill_rule {
+            options.fill_rule = settings.fill_rule;
+        }
+        if !fill.overrides.handle_intersections {
+            options.handle_intersections = settings.handle_intersections;
+        }
+        fill
+    };
+    let default_stroke = |mut stroke: StrokeMode| {
+        if !stroke.overrides.tolerance {
+            stroke.options.tolerance = settings.tolerance;
+        }
+        stroke
+    };
+    match mode {
+        DrawMode::Fill(fill_mode) => DrawMode::Fill(default_fill(fill_mode)),
+        DrawMode::Stroke(stroke_mode) => DrawMode::Stroke(default_stroke(stroke_mode)),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => DrawMode::Outlined {
+            fill_mode: default_fill(fill_mode),
+            outline_mode: default_stroke(outline_mode),
+        },
+        DrawMode::Wireframe(fill_mode) => DrawMode::Wireframe(default_fill(fill_mode)),
+    }
+}
+
+/// Multiplies the tolerances of `mode` by the zoom `level`, if the shape has
+/// an [`AdaptiveTolerance`].
+fn zoom_tolerance(mode: DrawMode, adaptive: bool, level: f32) -> DrawMode {
+    if adaptive {
+        coarsen(mode, level)
+    } else {
+        mode
+    }
+}
+
+/// Multiplies the line width of the strokes of `mode` by `scale`.
+fn scale_strokes(mut mode: DrawMode, scale: f32) -> DrawMode {
+    if let DrawMode::Stroke(stroke_mode)
+    | DrawMode::Outlined {
+        outline_mode: stroke_mode,
+        ..
+    } = &mut mode
+    {
+        stroke_mode.options.line_width *= scale;
+    }
+
+    mode
+}
+
+/// Multiplies the tessellation tolerances of `mode` by `factor`.
+fn coarsen(mode: DrawMode, factor: f32) -> DrawMode {
+    let coarsen_fill = |fill: FillMode| fill.with_tolerance(fill.options.tolerance * factor);
+    let coarsen_stroke =
+        |stroke: StrokeMode| stroke.with_tolerance(stroke.options.tolerance * factor);
+    match mode {
+        DrawMode::Fill(fill_mode) => DrawMode::Fill(coarsen_fill(fill_mode)),
+        DrawMode::Stroke(stroke_mode) => DrawMode::Stroke(coarsen_stroke(stroke_mode)),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => DrawMode::Outlined {
+            fill_mode: coarsen_fill(fill_mode),
+            outline_mode: coarsen_stroke(outline_mode),
+        },
+        DrawMode::Wireframe(fill_mode) => DrawMode::Wireframe(coarsen_fill(fill_mode)),
+    }
+}
+
+/// Tessellates `path` with the given mode, only stroking the first `reveal`
+/// fraction of its length.
+///
+/// Also returns the first tessellation error, if any. The buffers then hold
+/// what could be tessellated. Paths with NaN or infinite coordinates are not
+/// tessellated at all, since lyon may panic on them.
+pub(crate) fn tessellate(
+    fill_tess: &mut FillTessellator,
+    stroke_tess: &mut StrokeTessellator,
+    path: &tess::path::Path,
+    mode: &DrawMode,
+    reveal: f32,
+) -> (VertexBuffers, Option<TessellationError>) {
+    let mut buffers = VertexBuffers::new();
+    if !is_finite(path) {
+        return (buffers, None);
+    }
+
+    let result = match mode {
+        DrawMode::Fill(mode) | DrawMode::Wireframe(mode) => {
+            fill(fill_tess, path, mode, &mut buffers)
+        }
+        DrawMode::Stroke(mode) => stroke(stroke_tess, path, mode, reveal, &mut buffers),
+        DrawMode::Outlined {
+            fill_mode,
+            outline_mode,
+        } => {
+            // Later triangles are drawn over the earlier ones.
+            if outline_mode.behind_fill {
+                let stroked = stroke(stroke_tess, path, outline_mode, reveal, &mut buffers);
+                stroked.and(fill(fill_tess, path, fill_mode, &mut buffers))
+            } else {
+                let filled = fill(fill_tess, path, fill_mode, &mut buffers);
+                filled.and(stroke(
+                    stroke_tess,
+                    path,
+                    outline_mode,
+                    reveal,
+                    &mut buffers,
+                ))
+            }
+        }
+    };
+
+    (buffers, result.err())
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &FillOptions
+fn fill(
+    tess: &mut FillTessellator,
+    path: &tess::path::Path,
+    mode: &FillMode,
+    buffers: &mut VertexBuffers,
+) -> Result<(), TessellationError> {
+    let path = &fill_path(path, mode);
+    let start = buffers.indices.len();
+    let result = if let Some(gradient) = mode.gradient {
+        tess.tessellate_path(
+            path,
+            &mode.options,
+            &mut BuffersBuilder::new(buffers, GradientVertexConstructor { gradient }),
+        )
+    } else {
+        tess.tessellate_path(
+            path,
+            &mode.options,
+            &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
+        )
+    };
+
+    if result.is_ok() {
+        if mode.delaunay_refinement {
+            refine_delaunay_from(buffers, start);
+        }
+        if let Some(width) = mode.antialiasing.filter(|&width| width > 0.0) {
+            antialiasing::feather(path, mode, width, buffers);
+        }
+    }
+
+    result
+}
+
+/// Returns the path actually filled for `path`, once its open sub-paths are
+/// closed as set by `mode`.
+fn fill_path<'a>(path: &'a tess::path::Path, mode: &FillMode) -> Cow<'a, tess::path::Path> {
+    if mode.closing == FillClosing::CloseStraight || !has_open_sub_path(path) {
+        return Cow::Borrowed(path);
+    }
+    if mode.closing == FillClosing::CloseNone {
+        warn!("An open sub-path is not filled, as set by FillClosing::CloseNone.");
+    }
+
+    let mut builder = tess::path::Path::builder();
+    for sub_path in flatten(path, mode.options.tolerance) {
+        let points = match (sub_path.closed, mode.closing) {
+            (true, _) => sub_path.points,
+            (false, FillClosing::CloseConvexHull) => convex_hull(&sub_path.points),
+            (false, _) => continue,
+        };
+        builder.add_polygon(Polygon {
+            points: &points,
+            closed: true,
+        });
+    }
+
+    Cow::Owned(builder.build())
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // lyon takes &StrokeOptions
+fn stroke(
+    tess: &mut StrokeTessellator,
+    path: &tess::path::Path,
+    mode: &StrokeMode,
+    reveal: f32,
+    buffers: &mut VertexBuffers,
+) -> Result<(), TessellationError> {
+    // lyon would tessellate negative widths as positive ones.
+    if mode.options.line_width < 0.0 {
+        return Err(TessellationError::UnsupportedParamater);
+    }
+    if mode.options.line_width <= 0.0 {
+        return Ok(());
+    }
+
+    let path = &stroke_path(path, mode, reveal);
+    match mode.gradient {
+        None => tess.tessellate_path(
+            path,
+            &mode.options,
+            &mut BuffersBuilder::new(buffers, VertexConstructor { color: mode.color }),
+        ),
+        Some(gradient) => stroke_gradient(tess, path, mode, gradient, buffers),
+    }
+}
+
+/// Returns the path actually stroked for `path`, once aligned, revealed and
+/// dashed as set by `mode`.
+fn stroke_path<'a>(
+    path: &'a tess::path::Path,
+    mode: &StrokeMode,
+    reveal: f32,
+) -> Cow<'a, tess::path::Path> {
+    let half_width = mode.options.line_width / 2.0;
+    let tolerance = mode.options.tolerance;
+    let mut path = match mode.alignment {
+        StrokeAlignment::Center => Cow::Borrowed(path),
+        StrokeAlignment::Inside => Cow::Owned(offset(path, -half_width, tolerance)),
+        StrokeAlignment::Outside => Cow::Owned(offset(path, half_width, tolerance)),
+    };
+    // Revealing after the alignment keeps the offset of partly revealed closed
+    // sub-paths, which are no longer closed.
+    if reveal < 1.0 {
+        path = Cow::Owned(truncate(&path, reveal, tolerance));
+    }
+    if let Some(dash) = mode.dash {
+        path = Cow::Owned(dash.apply(&path, tolerance));
+    }
+
+    path
+}
+
+/// Strokes each sub-path of `path` on its own, so that the vertices can be
+/// colored by their distance from the start of the whole path.
+///
+/// Closed sub-paths are stroked as open ones going back to their first point,
+/// since the advancement of the vertices joining their ends is ambiguous.
+fn stroke_gradient(
+    tess: &mut StrokeTessellator,
+    path: &tess::path::Path,
+    mode: &StrokeMode,
+    gradient: StrokeGradient,
+    buffers: &mut VertexBuffers,
+) -> Result<(), TessellationError> {
+    let sub_paths = flatten(path, mode.options.tolerance);
+    let length = sub_paths.iter().map(FlatSubPath::length).sum();
+    let mut start = 0.0;
+    for sub_path in &sub_paths {
+        let mut points = sub_path.points.clone();
+        let mut options = mode.options;
+        if sub_path.closed {
+            points.extend(sub_path.points.first());
+            options = options.with_line_cap(LineCap::Butt);
+        }
+        let mut builder = tess::path::Path::builder();
+        builder.add_polygon(Polygon {
+            points: &points,
+            closed: false,
+        });
+
+        let constructor = StrokeGradientVertexConstructor {
+            gradient,
+            start,
+            length,
+        };
+        tess.tessellate_path(
+            &builder.build(),
+            &options,
+            &mut BuffersBuilder::new(buffers, constructor),
+        )?;
+        start += sub_path.length();
+    }
+
+    Ok(())
+}
+
+/// Returns the topology of the mesh of a shape drawn with the given mode.
+const fn topology(mode: &DrawMode) -> PrimitiveTopology {
+    match mode {
+        DrawMode::Wireframe(_) => PrimitiveTopology::LineList,
+        _ => PrimitiveTopology::TriangleList,
+    }
+}
+
+/// The distance within which [`MeshConfig::weld_vertices`] merges vertices.
+const WELD_EPSILON: f32 = 1e-4;
+
+/// Welds, reorders and premultiplies the vertices of `buffers` as set by
+/// `config`.
+fn post_process<'a>(buffers: &'a VertexBuffers, config: &MeshConfig) -> Cow<'a, VertexBuffers> {
+    if !config.weld_vertices && !config.canonical_order && !config.premultiply_alpha {
+        return Cow::Borrowed(buffers);
+    }
+
+    let mut buffers = buffers.clone();
+    if config.weld_vertices {
+        weld(&mut buffers, WELD_EPSILON);
+    }
+    if config.canonical_order {
+        canonicalize(&mut buffers);
+    }
+    if config.premultiply_alpha {
+        for vertex in &mut buffers.vertices {
+            vertex.color = premultiply_alpha(vertex.color);
+        }
+    }
+    Cow::Owned(buffers)
+}
+
+fn build_mesh(
+    buffers: &VertexBuffers,
+    config: &MeshConfig,
+    z: f32,
+    topology: PrimitiveTopology,
+) -> Mesh {
+    assemble_mesh(&post_process(buffers, config), |_| z, config, topology)
+}
+
+/// Builds the mesh of already post-processed `buffers`, taking the Z
+/// coordinate of each vertex from `depth`.
+fn assemble_mesh(
+    buffers: &VertexBuffers,
+    depth: impl Fn(usize) -> f32,
+    config: &MeshConfig,
+    topology: PrimitiveTopology,
+) -> Mesh {
+    let (topology, indices) = mesh_indices(buffers, config, topology);
+    let mut mesh = Mesh::new(topology);
+    mesh.set_indices(Some(build_indices(
+        indices,
+        buffers.vertices.len(),
+        config.index_format,
+    )));
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        buffers
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| [v.position[0], v.position[1], depth(i)])
+            .collect::<Vec<[f32; 3]>>(),
+    );
+    let colors = buffers.vertices.iter().map(|v| v.color);
+    match config.color_format {
+        MeshColorFormat::Packed => {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors.collect::<Vec<u32>>());
+        }
+        MeshColorFormat::Float32x4 => mesh.insert_attribute(
+            ATTRIBUTE_COLOR_FLOAT32X4,
+            colors.map(unpack_color).collect::<Vec<[f32; 4]>>(),
+        ),
+    }
+    if config.include_uvs {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, compute_uvs(buffers));
+    }
+    if config.include_normals {
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            vec![[0.0_f32, 0.0, 1.0]; buffers.vertices.len()],
+        );
+    }
+
+    mesh
+}
+
+/// `Mesh::ATTRIBUTE_COLOR`, holding `Float32x4` colors instead of packed ones.
+/// It keeps the id of the attribute, so that it is read as the color of the
+/// mesh.
+const ATTRIBUTE_COLOR_FLOAT32X4: MeshVertexAttribute = MeshVertexAttribute {
+    format: VertexFormat::Float32x4,
+    ..Mesh::ATTRIBUTE_COLOR
+};
+
+fn build_indices(indices: Vec<u32>, vertex_count: usize, format: MeshIndexFormat) -> Indices {
+    let fits_u16 = vertex_count <= usize::from(u16::MAX) + 1;
+
+    match format {
+        MeshIndexFormat::Auto if fits_u16 => {
+            Indices::U16(indices.into_iter().map(|index| index as u16).collect())
+        }
+        _ => Indices::U32(indices),
+    }
+}
+
+/// Returns the indices of the mesh of `buffers`, along with the topology to
+/// use them with.
+fn mesh_indices(
+    buffers: &VertexBuffers,
+    config: &MeshConfig,
+    topology: PrimitiveTopology,
+) -> (PrimitiveTopology, Vec<u32>) {
+    if topology == PrimitiveTopology::LineList {
+        return (topology, wireframe_indices(&buffers.indices));
+    }
+
+    let mut triangles = buffers.indices.clone();
+    set_winding(&buffers.vertices, &mut triangles, config.winding);
+    let strip = config
+        .triangle_strips
+        .then(|| triangle_strip(&triangles))
+        .flatten();
+    match strip {
+        Some(strip) => (PrimitiveTopology::TriangleStrip, strip),
+        None => (topology, triangles),
+    }
+}
+
+/// Converts a triangle list to a strip, if each triangle shares an edge with
+/// the previous one, made of the last two indices of the strip so far.
+///
+/// The GPU flips the winding of every other triangle of a strip, so a triangle
+/// is only appended if it keeps its winding once flipped that way. Otherwise
+/// the list is kept, so that the winding set by [`MeshConfig::winding`] holds.
+fn triangle_strip(triangles: &[u32]) -> Option<Vec<u32>> {
+    // Whether the edge from `a` to `b` goes along the winding of `triangle`.
+    let follows = |triangle: &[u32], a: u32, b: u32| {
+        (0..3).any(|i| triangle[i] == a && triangle[(i + 1) % 3] == b)
+    };
+    let mut chunks = triangles.chunks_exact(3);
+    let first = chunks.next()?;
+
+    // The strip can start from any edge of the first triangle.
+    (0..3).find_map(|rotation| {
+        let mut strip = (0..3)
+            .map(|i| first[(i + rotation) % 3])
+            .collect::<Vec<_>>();
+        for (i, triangle) in chunks.clone().enumerate() {
+            let (a, b) = (strip[strip.len() - 2], strip[strip.len() - 1]);
+            // The first triangle is even, so this one is odd for an even `i`:
+            // it is drawn as `b, a, c`.
+            if !triangle.contains(&a)
+                || !triangle.contains(&b)
+                || follows(triangle, a, b) == (i % 2 == 0)
+            {
+                return None;
+            }
+            strip.push(*triangle.iter().find(|&&index| index != a && index != b)?);
+        }
+
+        Some(strip)
+    })
+}
+
+/// Turns a triangle list into the line list of the edges of each triangle.
+fn wireframe_indices(triangles: &[u32]) -> Vec<u32> {
+    triangles
+        .chunks_exact(3)
+        .flat_map(|t| [t[0], t[1], t[1], t[2], t[2], t[0]])
+        .collect()
+}
+
+/// Scales the UVs of `mesh` by `repeat`, for shapes with a [`TextureTiling`].
+fn tile_uvs(mut mesh: Mesh, repeat: Option<Vec2>) -> Mesh {
+    if let (Some(repeat), Some(VertexAttributeValues::Float32x2(uvs))) =
+        (repeat, mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0))
+    {
+        for uv in uvs {
+            uv[0] *= repeat.x;
+            uv[1] *= repeat.y;
+        }
+    }
+
+    mesh
+}
+
+/// Maps each vertex position to the `[0, 1]` range of the bounding box of the
+/// vertices. The `v` coordinate is flipped, since textures have their origin
+/// at the top left corner while Bevy's y axis points up.
+fn compute_uvs(buffers: &VertexBuffers) -> Vec<[f32; 2]> {
+    let (min, max) = buffers.vertices.iter().fold(
+        ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]),
+        |(min, max), v| {
+            (
+                [min[0].min(v.position[0]), min[1].min(v.position[1])],
+                [max[0].max(v.position[0]), max[1].max(v.position[1])],
+            )
+        },
+    );
+    let normalize = |value: f32, min: f32, max: f32| {
+        let extent = max - min;
+        if extent > 0.0 {
+            (value - min) / extent
+        } else {
+            0.0
+        }
+    };
+
+    buffers
+        .vertices
+        .iter()
+        .map(|v| {
+            [
+                normalize(v.position[0], min[0], max[0]),
+                1.0 - normalize(v.position[1], min[1], max[1]),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)] // The tests compare exact results.
+mod tests {
+    use std::{
+        fmt::Debug,
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use bevy::{
+        asset::{AddAsset, AssetPlugin, Handle},
+        core::CorePlugin,
+        ecs::{entity::Entity, event::Events, schedule::Stage as _, world::World},
+        hierarchy::Children,
+        math::Vec2,
+        render::{color::Color, mesh::VertexAttributeValues},
+        transform::components::Transform,
+        utils::tracing::{field::Field, span, subscriber, Event, Level, Metadata, Subscriber},
+        window::{WindowDescriptor, WindowId},
+    };
+    use raw_window_handle::{RawWindowHandle, WebHandle};
+
+    use super::*;
+    use crate::{
+        draw::{DashPattern, RadialGradient},
+        entity::BatchedShapesBundle,
+        geometry::GeometryBuilder,
+        path::ShapePath,
+        shapes,
+        vertex::pack_color,
+    };
+
+    /// Returns a headless app with the plugin.
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_plugin(ShapePlugin);
+        app
+    }
+
+    /// Returns a headless app with the plugin and an enabled mesh cache.
+    fn cached_app() -> App {
+        let mut app = app();
+        app.insert_resource(ShapeMeshCache::new());
+        app
+    }
+
+    fn circle(radius: f32) -> shapes::Circle {
+        shapes::Circle {
+            radius,
+            ..shapes::Circle::default()
+        }
+    }
+
+    fn fill() -> DrawMode {
+        DrawMode::Fill(FillMode::color(Color::RED))
+    }
+
+    fn spawn_circle(app: &mut App, radius: f32, mode: DrawMode) -> Entity {
+        app.world
+            .spawn()
+            .insert_bundle(GeometryBuilder::build_as(
+                &circle(radius),
+                mode,
+                Transform::default(),
+            ))
+            .id()
+    }
+
+    fn handle(app: &App, entity: Entity) -> Handle<Mesh> {
+        app.world.get::<Mesh2dHandle>(entity).unwrap().0.clone()
+    }
+
+    fn mesh(app: &App, entity: Entity) -> &Mesh {
+        let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
+        meshes
+            .get(&app.world.get::<Mesh2dHandle>(entity).unwrap().0)
+            .unwrap()
+    }
+
+    fn mesh_count(app: &App) -> usize {
+        app.world.get_resource::<Assets<Mesh>>().unwrap().len()
+    }
+
+    fn positions(mesh: &Mesh) -> &[[f32; 3]] {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => panic!("the mesh has no positions"),
+        }
+    }
+
+    /// Returns the extent of the vertices of `mesh` along the x axis.
+    fn width(mesh: &Mesh) -> f32 {
+        let xs = positions(mesh).iter().map(|position| position[0]);
+        xs.clone().fold(f32::MIN, f32::max) - xs.fold(f32::MAX, f32::min)
+    }
+
+    fn colors(mesh: &Mesh) -> Vec<[f32; 4]> {
+        match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Uint32(colors)) => {
+                colors.iter().copied().map(unpack_color).collect()
+            }
+            Some(VertexAttributeValues::Float32x4(colors)) => colors.clone(),
+            _ => panic!("the mesh has no colors"),
+        }
+    }
+
+    fn assert_color_eq(actual: [f32; 4], expected: Color) {
+        let expected = expected.as_linear_rgba_f32();
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| (a - e).abs() <= 1.0 / 255.0 + f32::EPSILON),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// A subscriber recording the messages of the warnings logged on the
+    /// current thread.
+    #[derive(Default, Clone)]
+    struct Warnings(Arc<Mutex<Vec<String>>>);
+
+    impl Warnings {
+        /// Returns the warnings logged while running `f`.
+        fn capture(f: impl FnOnce()) -> Vec<String> {
+            let warnings = Self::default();
+            subscriber::with_default(warnings.clone(), f);
+            let messages = lock(&warnings.0);
+            messages.clone()
+        }
+    }
+
+    impl Subscriber for Warnings {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            *metadata.level() == Level::WARN
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut |field: &Field, value: &dyn Debug| {
+                if field.name() == "message" {
+                    message = format!("{:?}", value);
+                }
+            });
+            lock(&self.0).push(message);
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn changing_a_path_overwrites_its_mesh() {
+        let mut app = app();
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        let count = mesh_count(&app);
+        let original = handle(&app, entity);
+
+        for step in 1..=5 {
+            let radius = 10.0 + step as f32;
+            *app.world.get_mut::<Path>(entity).unwrap() = ShapePath::build_as(&circle(radius));
+            app.update();
+
+            assert_eq!(mesh_count(&app), count);
+            assert_eq!(handle(&app, entity), original);
+            let max_x = positions(mesh(&app, entity))
+                .iter()
+                .fold(f32::NEG_INFINITY, |max, position| max.max(position[0]));
+            assert!((max_x - radius).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn uvs_map_the_corners_of_a_unit_rectangle() {
+        let rectangle = shapes::Rectangle {
+            origin: shapes::RectangleOrigin::BottomLeft,
+            ..shapes::Rectangle::default()
+        };
+        let config = MeshConfig {
+            include_uvs: true,
+            ..MeshConfig::default()
+        };
+        let mesh = tessellate_with_config(&ShapePath::build_as(&rectangle), &fill(), &config);
+        let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) => uvs,
+            _ => panic!("the mesh has no UVs"),
+        };
+
+        let mut corners: Vec<[f32; 2]> = positions(&mesh)
+            .iter()
+            .zip(uvs)
+            .map(|(position, uv)| {
+                // The V axis points down, like in textures.
+                assert_eq!(*uv, [position[0], 1.0 - position[1]]);
+                *uv
+            })
+            .collect();
+        corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(corners, [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]]);
+    }
+
+    #[test]
+    fn shape_depth_is_applied_to_every_vertex() {
+        let mut app = app();
+        let flat = spawn_circle(&mut app, 10.0, fill());
+        let raised = spawn_circle(&mut app, 10.0, fill());
+        app.world.entity_mut(raised).insert(ShapeDepth(2.5));
+        app.update();
+
+        assert!(positions(mesh(&app, flat)).iter().all(|p| p[2] == 0.0));
+        assert!(positions(mesh(&app, raised)).iter().all(|p| p[2] == 2.5));
+    }
+
+    #[test]
+    fn radial_gradient_goes_from_the_center_to_the_rim() {
+        let sector = shapes::Sector {
+            radius: 10.0,
+            ..shapes::Sector::default()
+        };
+        let gradient = RadialGradient {
+            center: Vec2::ZERO,
+            radius: 10.0,
+            inner_color: Color::RED,
+            outer_color: Color::BLUE,
+        };
+        let mesh = tessellate_fill(
+            &ShapePath::build_as(&sector),
+            &FillMode::radial_gradient(gradient),
+        );
+
+        let mut centers = 0;
+        for (position, color) in positions(&mesh).iter().zip(colors(&mesh)) {
+            let distance = Vec2::new(position[0], position[1]).length();
+            if distance < 0.01 {
+                centers += 1;
+                assert_color_eq(color, Color::RED);
+            } else if (distance - 10.0).abs() < 0.01 {
+                assert_color_eq(color, Color::BLUE);
+            }
+        }
+        assert_eq!(centers, 1);
+
+        let degenerate = RadialGradient {
+            radius: 0.0,
+            ..gradient
+        };
+        assert_eq!(degenerate.color_at(Vec2::ZERO), Color::BLUE);
+    }
+
+    #[test]
+    fn identical_shapes_share_a_cached_mesh() {
+        let mut app = cached_app();
+        let first = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        let second = spawn_circle(&mut app, 10.0, fill());
+        let other = spawn_circle(&mut app, 10.0, DrawMode::Fill(FillMode::color(Color::BLUE)));
+        app.update();
+
+        assert_eq!(handle(&app, first), handle(&app, second));
+        assert_ne!(handle(&app, first), handle(&app, other));
+        assert_eq!(mesh_count(&app), 2);
+        assert_eq!(app.world.get_resource::<ShapeMeshCache>().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn clearing_the_cache_stops_sharing_meshes() {
+        let mut app = cached_app();
+        let first = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+
+        let mut cache = app.world.get_resource_mut::<ShapeMeshCache>().unwrap();
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let second = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        assert_ne!(handle(&app, first), handle(&app, second));
+        assert_eq!(app.world.get_resource::<ShapeMeshCache>().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn remeshed_shapes_release_their_previous_cached_mesh() {
+        let mut app = cached_app();
+        let first = spawn_circle(&mut app, 10.0, fill());
+        let second = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+
+        for radius in [20.0, 30.0] {
+            *app.world.get_mut::<Path>(first).unwrap() = ShapePath::build_as(&circle(radius));
+            app.update();
+        }
+        // The mesh of the first radius is still used by the second shape.
+        assert_eq!(app.world.get_resource::<ShapeMeshCache>().unwrap().len(), 2);
+
+        *app.world.get_mut::<Path>(second).unwrap() = ShapePath::build_as(&circle(30.0));
+        app.update();
+        assert_eq!(handle(&app, first), handle(&app, second));
+        assert_eq!(app.world.get_resource::<ShapeMeshCache>().unwrap().len(), 1);
+        free_unused_meshes(&mut app);
+        assert_eq!(mesh_count(&app), 1);
+    }
+
+    #[test]
+    fn clearing_the_cache_forgets_shared_meshes() {
+        let mut app = cached_app();
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        let shared = handle(&app, entity);
+
+        let mut cache = app.world.get_resource_mut::<ShapeMeshCache>().unwrap();
+        assert!(cache.is_shared(&shared));
+        cache.clear();
+        assert!(!cache.is_shared(&shared));
+    }
+
+    #[test]
+    fn small_meshes_get_u16_indices() {
+        let triangle = ShapePath::build_as(&shapes::Polygon {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+            closed: true,
+        });
+        let auto = tessellate_with_config(&triangle, &fill(), &MeshConfig::default());
+        assert!(matches!(auto.indices(), Some(Indices::U16(indices)) if indices.len() == 3));
+
+        let config = MeshConfig {
+            index_format: MeshIndexFormat::U32,
+            ..MeshConfig::default()
+        };
+        let forced = tessellate_with_config(&triangle, &fill(), &config);
+        assert!(matches!(forced.indices(), Some(Indices::U32(_))));
+    }
+
+    #[test]
+    fn large_meshes_get_u32_indices() {
+        // The most vertices u16 indices can address.
+        let fitting = usize::from(u16::MAX) + 1;
+        let indices = |count: usize| (0..count as u32).collect::<Vec<u32>>();
+
+        assert!(matches!(
+            build_indices(indices(fitting), fitting, MeshIndexFormat::Auto),
+            Indices::U16(indices) if indices.last() == Some(&u16::MAX)
+        ));
+        assert!(matches!(
+            build_indices(indices(fitting + 1), fitting + 1, MeshIndexFormat::Auto),
+            Indices::U32(indices) if indices.len() == fitting + 1
+        ));
+    }
+
+    #[test]
+    fn smaller_tolerances_give_more_vertices() {
+        let path = ShapePath::build_as(&circle(100.0));
+        let fill_vertices = |tolerance| {
+            let mode = FillMode::color(Color::RED).with_tolerance(tolerance);
+            positions(&tessellate_fill(&path, &mode)).len()
+        };
+        let stroke_vertices = |tolerance| {
+            let mode = StrokeMode::new(Color::RED, 1.0).with_tolerance(tolerance);
+            positions(&tessellate_stroke(&path, &mode)).len()
+        };
+
+        assert!(fill_vertices(0.01) > fill_vertices(1.0));
+        assert!(stroke_vertices(0.01) > stroke_vertices(1.0));
+    }
+
+    #[test]
+    fn empty_shapes_are_reported_once() {
+        let mut app = app();
+        let empty = spawn_circle(&mut app, 10.0, fill());
+        let valid = spawn_circle(&mut app, 10.0, fill());
+        // A stroke that is not revealed yet is empty on purpose.
+        let unrevealed = spawn_circle(
+            &mut app,
+            10.0,
+            DrawMode::Stroke(StrokeMode::new(Color::RED, 1.0)),
+        );
+        app.world
+            .entity_mut(unrevealed)
+            .insert(ShapeReveal { fraction: 0.0 });
+        let remesh = |app: &mut App, path: tess::path::Path| {
+            app.world.get_mut::<Path>(empty).unwrap().0 = path;
+            app.world.get_mut::<Path>(valid).unwrap().set_changed();
+            app.world.get_mut::<Path>(unrevealed).unwrap().set_changed();
+            app.update();
+        };
+
+        let warnings = Warnings::capture(|| {
+            for _ in 0..3 {
+                remesh(&mut app, tess::path::Path::new());
+            }
+        });
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(&format!("{:?}", empty)));
+
+        // Shapes that get some geometry are reported again if they lose it.
+        let circle = ShapePath::build_as(&circle(10.0)).0;
+        let warnings = Warnings::capture(|| {
+            remesh(&mut app, circle);
+            remesh(&mut app, tess::path::Path::new());
+        });
+        assert_eq!(warnings.len(), 1);
+    }
+
+    /// Runs enough frames for the asset server to free the meshes that lost
+    /// their last handle.
+    fn free_unused_meshes(app: &mut App) {
+        for _ in 0..3 {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn despawned_shapes_free_their_mesh() {
+        let mut app = app();
+        let before = mesh_count(&app);
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        assert_eq!(mesh_count(&app), before + 1);
+
+        app.world.despawn(entity);
+        free_unused_meshes(&mut app);
+        assert_eq!(mesh_count(&app), before);
+    }
+
+    #[test]
+    fn cached_meshes_are_freed_with_their_last_shape() {
+        let mut app = cached_app();
+        let first = spawn_circle(&mut app, 10.0, fill());
+        let second = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        assert_eq!(mesh_count(&app), 1);
+
+        app.world.despawn(first);
+        free_unused_meshes(&mut app);
+        assert_eq!(mesh_count(&app), 1);
+
+        app.world.despawn(second);
+        free_unused_meshes(&mut app);
+        assert_eq!(mesh_count(&app), 0);
+        assert!(app
+            .world
+            .get_resource::<ShapeMeshCache>()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn batched_shapes_share_one_mesh() {
+        let mut app = app();
+        let members = (0..3_u8)
+            .map(|i| {
+                let offset = Vec2::new(f32::from(i) * 10.0, 0.0);
+                let triangle = ShapePath::build_as(&shapes::Polygon {
+                    points: vec![offset, offset + Vec2::X, offset + Vec2::Y],
+                    closed: true,
+                });
+                app.world.spawn().insert_bundle((triangle, fill())).id()
+            })
+            .collect();
+        let batch = app
+            .world
+            .spawn()
+            .insert_bundle(BatchedShapesBundle {
+                batch: BatchedShapes(members),
+                ..BatchedShapesBundle::default()
+            })
+            .id();
+        app.update();
+
+        assert_eq!(mesh_count(&app), 1);
+        let mesh = mesh(&app, batch);
+        assert_eq!(positions(mesh).len(), 9);
+        assert_eq!(mesh.indices().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn batches_place_their_members_by_their_transform_and_depth() {
+        let mut app = app();
+        let triangle = ShapePath::build_as(&shapes::Polygon {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+            closed: true,
+        });
+        let member = app
+            .world
+            .spawn()
+            .insert_bundle((triangle, fill(), ShapeDepth(2.0), Transform::default()))
+            .id();
+        let batch = app
+            .world
+            .spawn()
+            .insert_bundle(BatchedShapesBundle {
+                batch: BatchedShapes(vec![member]),
+                ..BatchedShapesBundle::default()
+            })
+            .id();
+        app.update();
+        assert!(positions(mesh(&app, batch))
+            .iter()
+            .all(|position| position[0] <= 1.0 && position[2] == 2.0));
+
+        app.world.get_mut::<Transform>(member).unwrap().translation = Vec3::new(10.0, 0.0, 1.0);
+        app.update();
+        assert!(positions(mesh(&app, batch))
+            .iter()
+            .all(|position| position[0] >= 10.0 && position[2] == 3.0));
+    }
+
+    #[test]
+    fn normals_are_only_written_when_enabled() {
+        let path = ShapePath::build_as(&circle(10.0));
+        let without = tessellate_with_config(&path, &fill(), &MeshConfig::default());
+        assert!(without.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+
+        let config = MeshConfig {
+            include_normals: true,
+            ..MeshConfig::default()
+        };
+        let with = tessellate_with_config(&path, &fill(), &config);
+        let normals = match with.attribute(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => normals,
+            _ => panic!("the mesh has no normals"),
+        };
+        assert_eq!(normals.len(), positions(&with).len());
+        assert!(normals.iter().all(|normal| *normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn async_shapes_get_their_mesh_once_the_task_completes() {
+        let mut app = app();
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.world.entity_mut(entity).insert(AsyncTessellation);
+
+        app.update();
+        assert!(app.world.get::<TessellationTask>(entity).is_some());
+
+        for _ in 0..1000 {
+            if app.world.get::<TessellationTask>(entity).is_none() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+            app.update();
+        }
+        assert!(app.world.get::<TessellationTask>(entity).is_none());
+        assert!(!positions(mesh(&app, entity)).is_empty());
+    }
+
+    #[test]
+    fn parallel_meshing_matches_serial_meshing() {
+        let mut app = app();
+        let shapes = (1..=100_u8)
+            .map(|radius| {
+                let radius = f32::from(radius);
+                (radius, spawn_circle(&mut app, radius, fill()))
+            })
+            .collect::<Vec<_>>();
+        app.update();
+
+        for (radius, entity) in shapes {
+            let path = ShapePath::build_as(&circle(radius));
+            let serial = tessellate_with_config(&path, &fill(), &MeshConfig::default());
+            let parallel = mesh(&app, entity);
+            assert_eq!(positions(parallel), positions(&serial));
+            assert_eq!(
+                parallel.indices().unwrap().iter().collect::<Vec<_>>(),
+                serial.indices().unwrap().iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn wireframes_draw_each_edge_of_a_triangle() {
+        let triangle = ShapePath::build_as(&shapes::Polygon {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+            closed: true,
+        });
+        let wireframe = DrawMode::Wireframe(FillMode::color(Color::RED));
+        let mesh = tessellate_with_config(&triangle, &wireframe, &MeshConfig::default());
+
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::LineList);
+        assert_eq!(mesh.indices().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn shapes_are_meshed_before_a_custom_stage() {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+        struct PostProcessing;
+
+        #[derive(Default)]
+        struct MeshedShapes(usize);
+
+        fn count_meshed_shapes(mut meshed: ResMut<MeshedShapes>, shapes: Query<&Mesh2dHandle>) {
+            meshed.0 = shapes
+                .iter()
+                .filter(|mesh| mesh.0 != Handle::default())
+                .count();
+        }
+
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .init_resource::<MeshedShapes>()
+            .add_stage_after(CoreStage::Update, PostProcessing, SystemStage::parallel())
+            .add_system_to_stage(PostProcessing, count_meshed_shapes)
+            .add_plugin(ShapePluginBuilder::default().before_stage(PostProcessing));
+        spawn_circle(&mut app, 10.0, fill());
+        app.update();
+
+        // The shape was meshed within the frame, before `PostProcessing`.
+        assert_eq!(app.world.get_resource::<MeshedShapes>().unwrap().0, 1);
+        assert_eq!(mesh_count(&app), 1);
+    }
+
+    #[test]
+    fn antialiasing_adds_a_fringe_of_vertices() {
+        let path = ShapePath::build_as(&circle(10.0));
+        let mode = FillMode::color(Color::RED);
+        let plain = tessellate_fill(&path, &mode);
+        let antialiased = tessellate_fill(&path, &mode.with_antialiasing(1.0));
+
+        assert!(positions(&antialiased).len() > positions(&plain).len());
+    }
+
+    #[test]
+    fn outside_strokes_grow_the_shape_by_the_line_width() {
+        let square = ShapePath::build_as(&shapes::Rectangle {
+            extents: Vec2::splat(100.0),
+            ..shapes::Rectangle::default()
+        });
+        let aligned_width = |alignment| {
+            let mode = StrokeMode::new(Color::BLACK, 10.0).with_alignment(alignment);
+            width(&tessellate_stroke(&square, &mode))
+        };
+
+        assert!((aligned_width(StrokeAlignment::Center) - 110.0).abs() < 0.1);
+        assert!((aligned_width(StrokeAlignment::Outside) - 120.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn hidden_shapes_are_meshed_once_shown() {
+        let mut app = app();
+        app.world
+            .get_resource_mut::<MeshConfig>()
+            .unwrap()
+            .defer_hidden = true;
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.world.get_mut::<Visibility>(entity).unwrap().is_visible = false;
+        app.update();
+        assert_eq!(mesh_count(&app), 0);
+
+        app.world.get_mut::<Visibility>(entity).unwrap().is_visible = true;
+        app.update();
+        assert_eq!(mesh_count(&app), 1);
+        assert!(!positions(mesh(&app, entity)).is_empty());
+    }
+
+    #[test]
+    fn screen_space_strokes_follow_the_camera_scale() {
+        let mut app = app();
+        app.world.spawn().insert_bundle((
+            Camera::default(),
+            OrthographicProjection {
+                scale: 2.0,
+                ..OrthographicProjection::default()
+            },
+        ));
+        let entity = spawn_circle(
+            &mut app,
+            10.0,
+            DrawMode::Stroke(StrokeMode::new(Color::BLACK, 1.0)),
+        );
+        app.world.entity_mut(entity).insert(ScreenSpaceStroke(3.0));
+        app.update();
+
+        match app.world.get::<DrawMode>(entity).unwrap() {
+            DrawMode::Stroke(stroke) => assert_eq!(stroke.options.line_width, 6.0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn thick_lines_are_strips_that_keep_their_winding() {
+        let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(10.0, 0.0)));
+        let mode = DrawMode::Stroke(StrokeMode::new(Color::BLACK, 2.0));
+        let config = MeshConfig {
+            triangle_strips: true,
+            ..MeshConfig::default()
+        };
+        let mesh = tessellate_with_config(&line, &mode, &config);
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::TriangleStrip);
+
+        let positions = positions(&mesh);
+        let strip = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+        assert_eq!(strip.len(), 4);
+        for (i, triangle) in strip.windows(3).enumerate() {
+            // The GPU draws every other triangle of a strip flipped.
+            let [a, b, c] = if i % 2 == 0 {
+                [triangle[0], triangle[1], triangle[2]]
+            } else {
+                [triangle[1], triangle[0], triangle[2]]
+            }
+            .map(|index| Vec3::from(positions[index]).truncate());
+            // Counter-clockwise triangles have a positive signed area.
+            assert!((b - a).perp_dot(c - a) > 0.0);
+        }
+    }
+
+    #[test]
+    fn strips_reject_triangles_that_would_flip() {
+        // The second triangle goes from 0 to 1 like the first one, so it would
+        // be drawn with the opposite winding as the second triangle of a strip.
+        assert_eq!(triangle_strip(&[0, 1, 2, 0, 1, 3]), None);
+        assert_eq!(triangle_strip(&[0, 1, 2, 2, 1, 3]), Some(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn colors_are_written_in_the_configured_format() {
+        let path = ShapePath::build_as(&circle(10.0));
+        let gray = DrawMode::Fill(FillMode::color(Color::rgb(0.5, 0.5, 0.5)));
+        let mesh = |color_format| {
+            let config = MeshConfig {
+                color_format,
+                ..MeshConfig::default()
+            };
+            tessellate_with_config(&path, &gray, &config)
+        };
+
+        let packed = mesh(MeshColorFormat::Packed);
+        match packed.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Uint32(colors)) => {
+                assert!(colors
+                    .iter()
+                    .all(|color| *color == pack_color(Color::rgb(0.5, 0.5, 0.5))));
+            }
+            _ => panic!("the colors are not packed"),
+        }
+
+        let unpacked = mesh(MeshColorFormat::Float32x4);
+        match unpacked.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => {
+                for &[r, g, b, a] in colors {
+                    // A mid-gray is about 0.214 in linear space.
+                    for channel in [r, g, b] {
+                        assert!((channel - 0.214).abs() < 0.005, "{}", channel);
+                    }
+                    assert!((a - 1.0).abs() < f32::EPSILON);
+                }
+            }
+            _ => panic!("the colors are not unpacked"),
+        }
+    }
+
+    #[test]
+    fn failed_tessellations_are_reported_for_their_shape() {
+        let mut app = app();
+        spawn_circle(&mut app, 10.0, fill());
+        let broken = spawn_circle(
+            &mut app,
+            10.0,
+            DrawMode::Stroke(StrokeMode::new(Color::BLACK, -1.0)),
+        );
+        app.update();
+
+        let events = app
+            .world
+            .get_resource::<Events<ShapeTessellationError>>()
+            .unwrap();
+        let errors = events.get_reader().iter(events).collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].entity, broken);
+        assert!(matches!(
+            errors[0].kind,
+            TessellationError::UnsupportedParamater
+        ));
+    }
+
+    #[test]
+    fn identical_shapes_spawned_together_share_a_cached_mesh() {
+        let mut app = cached_app();
+        let shapes = (0..100)
+            .map(|_| spawn_circle(&mut app, 10.0, fill()))
+            .collect::<Vec<_>>();
+        app.update();
+
+        for shape in &shapes {
+            assert_eq!(handle(&app, *shape), handle(&app, shapes[0]));
+        }
+        assert_eq!(mesh_count(&app), 1);
+    }
+
+    #[test]
+    fn dash_animations_move_the_offset_over_time() {
+        let mut world = World::new();
+        let mode = StrokeMode::new(Color::BLACK, 1.0).with_dash(DashPattern::new(2.0, 1.0));
+        let entity = world
+            .spawn()
+            .insert_bundle((DrawMode::Stroke(mode), DashAnimation { speed: 1.0 }))
+            .id();
+        let start = Instant::now();
+        let mut time = Time::default();
+        time.update_with_instant(start);
+        world.insert_resource(time);
+        let mut stage = SystemStage::single(animate_dashes_system);
+        let offset = |world: &World| match world.get::<DrawMode>(entity).unwrap() {
+            DrawMode::Stroke(stroke) => stroke.dash.unwrap().offset,
+            _ => unreachable!(),
+        };
+
+        // No time has passed during the first frame.
+        stage.run(&mut world);
+        assert_eq!(offset(&world), 0.0);
+
+        // The dashes move towards the end of the path, the offset wrapping
+        // around the period of the pattern.
+        world
+            .get_resource_mut::<Time>()
+            .unwrap()
+            .update_with_instant(start + Duration::from_millis(500));
+        stage.run(&mut world);
+        assert_eq!(offset(&world), 2.5);
+    }
+
+    #[test]
+    fn stroke_gradients_go_from_the_start_to_the_end_of_the_path() {
+        let line = ShapePath::build_as(&shapes::Line(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+        let mode = StrokeMode::new(Color::BLACK, 2.0).with_gradient(StrokeGradient::Linear {
+            start_color: Color::BLUE,
+            end_color: Color::RED,
+        });
+        let mesh = tessellate_stroke(&line, &mode);
+
+        let ends = positions(&mesh)
+            .iter()
+            .zip(colors(&mesh))
+            .filter_map(|(position, color)| match position[0] {
+                x if x.abs() < 0.01 => Some((color, Color::BLUE)),
+                x if (x - 100.0).abs() < 0.01 => Some((color, Color::RED)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Each end has a vertex on both sides of the line.
+        assert!(ends.len() >= 4);
+        for (actual, expected) in ends {
+            assert_color_eq(actual, expected);
+        }
+    }
+
+    #[test]
+    fn vertex_budgets_coarsen_fine_shapes() {
+        let path = ShapePath::build_as(&circle(100.0));
+        let fine = DrawMode::Fill(FillMode::color(Color::RED).with_tolerance(0.001));
+        let unbounded = tessellate_with_config(&path, &fine, &MeshConfig::default());
+        assert!(positions(&unbounded).len() > 64);
+
+        let config = MeshConfig {
+            max_vertices: Some(64),
+            ..MeshConfig::default()
+        };
+        let bounded = tessellate_with_config(&path, &fine, &config);
+        assert!(!positions(&bounded).is_empty());
+        assert!(positions(&bounded).len() <= 64);
+    }
+
+    #[test]
+    fn mesh_budgets_spread_shapes_over_frames() {
+        let mut app = app();
+        app.world
+            .get_resource_mut::<MeshConfig>()
+            .unwrap()
+            .max_meshes_per_frame = Some(3);
+        let shapes = (0..10)
+            .map(|_| spawn_circle(&mut app, 10.0, fill()))
+            .collect::<Vec<_>>();
+
+        let mut meshed = Vec::new();
+        for _ in 0..4 {
+            app.update();
+            meshed.push(
+                shapes
+                    .iter()
+                    .filter(|shape| handle(&app, **shape) != Handle::default())
+                    .count(),
+            );
+        }
+        assert_eq!(meshed, [3, 6, 9, 10]);
+    }
+
+    #[test]
+    fn cached_shapes_do_not_count_against_the_meshing_budget() {
+        let mut app = cached_app();
+        app.world
+            .get_resource_mut::<MeshConfig>()
+            .unwrap()
+            .max_meshes_per_frame = Some(1);
+        spawn_circle(&mut app, 10.0, fill());
+        app.update();
+
+        let mut shapes = (0..3)
+            .map(|_| spawn_circle(&mut app, 10.0, fill()))
+            .collect::<Vec<_>>();
+        shapes.push(spawn_circle(&mut app, 20.0, fill()));
+        app.update();
+        for shape in shapes {
+            assert_ne!(handle(&app, shape), Handle::default());
+        }
+    }
+
+    #[test]
+    fn material_colored_shapes_share_a_mesh_whatever_their_colors() {
+        let mut app = cached_app();
+        let red = spawn_circle(&mut app, 10.0, fill());
+        let blue = spawn_circle(&mut app, 10.0, DrawMode::Fill(FillMode::color(Color::BLUE)));
+        for shape in [red, blue] {
+            app.world.entity_mut(shape).insert(MaterialColor);
+        }
+        app.update();
+
+        assert_eq!(handle(&app, red), handle(&app, blue));
+        assert_eq!(mesh_count(&app), 1);
+        for color in colors(mesh(&app, red)) {
+            assert_color_eq(color, Color::WHITE);
+        }
+    }
+
+    #[test]
+    fn inserting_a_material_color_remeshes_the_shape_in_white() {
+        let mut app = app();
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.update();
+        for color in colors(mesh(&app, entity)) {
+            assert_color_eq(color, Color::RED);
+        }
+
+        app.world.entity_mut(entity).insert(MaterialColor);
+        app.update();
+        for color in colors(mesh(&app, entity)) {
+            assert_color_eq(color, Color::WHITE);
+        }
+    }
+
+    #[test]
+    fn strokes_follow_the_scale_factor_of_the_window() {
+        let mut app = app();
+        app.world
+            .get_resource_mut::<MeshConfig>()
+            .unwrap()
+            .scale_strokes_by_dpi = true;
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            800,
+            600,
+            1.0,
+            None,
+            RawWindowHandle::Web(WebHandle::empty()),
+        ));
+        app.insert_resource(windows);
+        let entity = spawn_circle(
+            &mut app,
+            10.0,
+            DrawMode::Stroke(StrokeMode::new(Color::BLACK, 2.0)),
+        );
+
+        app.update();
+        assert!((width(mesh(&app, entity)) - 22.0).abs() < 0.1);
+
+        app.world
+            .get_resource_mut::<Windows>()
+            .unwrap()
+            .get_primary_mut()
+            .unwrap()
+            .update_scale_factor_from_backend(2.0);
+        app.update();
+        assert!((width(mesh(&app, entity)) - 24.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn split_outlines_draw_the_fill_and_the_outline_as_children() {
+        let mut app = app();
+        let outlined = DrawMode::Outlined {
+            fill_mode: FillMode::color(Color::RED),
+            outline_mode: StrokeMode::new(Color::BLACK, 2.0),
+        };
+        let entity = spawn_circle(&mut app, 10.0, outlined);
+        app.world.entity_mut(entity).insert(SplitOutline::default());
+        // The children are spawned during the first frame, and meshed during
+        // the second one.
+        app.update();
+        app.update();
+
+        let split = *app.world.get::<SplitOutline>(entity).unwrap();
+        let parts = [split.fill().unwrap(), split.outline().unwrap()];
+        let children = app.world.get::<Children>(entity).unwrap();
+        assert_eq!(children.iter().copied().collect::<Vec<_>>(), parts);
+        assert_ne!(handle(&app, parts[0]), handle(&app, parts[1]));
+        assert_eq!(mesh_count(&app), 2);
+
+        *app.world.get_mut::<DrawMode>(entity).unwrap() = fill();
+        app.update();
+        let split = *app.world.get::<SplitOutline>(entity).unwrap();
+        assert_eq!(split.fill(), Some(parts[0]));
+        assert_eq!(split.outline(), None);
+        assert!(app.world.get_entity(parts[1]).is_none());
+    }
+
+    #[test]
+    fn split_outlines_only_spawn_an_outline_for_outlined_shapes() {
+        let mut app = app();
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.world.entity_mut(entity).insert(SplitOutline::default());
+        app.update();
+
+        let split = app.world.get::<SplitOutline>(entity).unwrap();
+        assert!(split.fill().is_some());
+        assert_eq!(split.outline(), None);
+        assert_eq!(app.world.get::<Children>(entity).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn target_meshes_are_overwritten_in_place() {
+        let mut app = app();
+        let target = app
+            .world
+            .get_resource_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(Mesh::new(PrimitiveTopology::TriangleList));
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.world
+            .entity_mut(entity)
+            .insert(TargetMesh(target.clone()));
+        app.update();
+
+        assert_eq!(handle(&app, entity), target);
+        assert_eq!(mesh_count(&app), 1);
+        assert!(!positions(mesh(&app, entity)).is_empty());
+    }
+
+    #[test]
+    fn removing_the_target_mesh_meshes_the_shape_on_its_own() {
+        let mut app = app();
+        let target = app
+            .world
+            .get_resource_mut::<Assets<Mesh>>()
+            .unwrap()
+            .add(Mesh::new(PrimitiveTopology::TriangleList));
+        let entity = spawn_circle(&mut app, 10.0, fill());
+        app.world
+            .entity_mut(entity)
+            .insert(TargetMesh(target.clone()));
+        app.update();
+        let targeted = positions(mesh(&app, entity)).len();
+
+        app.world.entity_mut(entity).remove::<TargetMesh>();
+        app.update();
+        assert_ne!(handle(&app, entity), target);
+        assert_eq!(positions(mesh(&app, entity)).len(), targeted);
+
+        // Remeshing the shape leaves the former target alone.
+        *app.world.get_mut::<Path>(entity).unwrap() = ShapePath::build_as(&circle(100.0));
+        app.update();
+        let meshes = app.world.get_resource::<Assets<Mesh>>().unwrap();
+        assert_eq!(positions(meshes.get(&target).unwrap()).len(), targeted);
+    }
+
+    #[test]
+    fn zooming_in_refines_adaptive_shapes() {
+        let mut app = app();
+        let camera = app
+            .world
+            .spawn()
+            .insert_bundle((Camera::default(), OrthographicProjection::default()))
+            .id();
+        let entity = spawn_circle(&mut app, 100.0, fill());
+        app.world.entity_mut(entity).insert(AdaptiveTolerance);
+        app.update();
+        let coarse = positions(mesh(&app, entity)).len();
+
+        app.world
+            .get_mut::<OrthographicProjection>(camera)
+            .unwrap()
+            .scale = 0.25;
+        app.update();
+        assert!(positions(mesh(&app, entity)).len() > coarse);
+    }
+
+    #[test]
+    fn inserting_an_adaptive_tolerance_remeshes_the_shape() {
+        let mut app = app();
+        app.world.spawn().insert_bundle((
+            Camera::default(),
+            OrthographicProjection {
+                scale: 0.25,
+                ..OrthographicProjection::default()
+            },
+        ));
+        let entity = spawn_circle(&mut app, 100.0, fill());
+        app.update();
+        let coarse = positions(mesh(&app, entity)).len();
+
+        app.world.entity_mut(entity).insert(AdaptiveTolerance);
+        app.update();
+        assert!(positions(mesh(&app, entity)).len() > coarse);
+    }
+
+    #[test]
+    fn changing_the_tessellation_settings_remeshes_shapes() {
+        let mut app = app();
+        let entity = spawn_circle(&mut app, 100.0, fill());
+        app.update();
+        let coarse = positions(mesh(&app, entity)).len();
+
+        app.world
+            .get_resource_mut::<TessellationSettings>()
+            .unwrap()
+            .tolerance = 0.001;
+        app.update();
+        assert!(positions(mesh(&app, entity)).len() > coarse);
+    }
+
+    #[test]
+    fn options_set_on_a_mode_override_the_settings_even_at_their_default() {
+        let mut app = app();
+        app.insert_resource(TessellationSettings {
+            tolerance: 5.0,
+            ..TessellationSettings::default()
+        });
+        let inherited = spawn_circle(&mut app, 100.0, fill());
+        let explicit = spawn_circle(
+            &mut app,
+            100.0,
+            DrawMode::Fill(
+                FillMode::color(Color::RED).with_tolerance(FillOptions::DEFAULT_TOLERANCE),
+            ),
+        );
+        app.update();
+
+        assert!(positions(mesh(&app, explicit)).len() > positions(mesh(&app, inherited)).len());
+    }
 }
@@ -0,0 +1,345 @@
+//! Instanced rendering of [`ShapeInstances`].
+
+use bevy::{
+    core::FloatOrd,
+    core_pipeline::Transparent2d,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{
+            lifetimeless::{Read, SQuery, SRes},
+            Commands, Local, Query, Res, ResMut, SystemParamItem,
+        },
+        world::{FromWorld, World},
+    },
+    log::error,
+    render::{
+        mesh::{GpuBufferInfo, Mesh, MeshVertexBufferLayout},
+        render_asset::RenderAssets,
+        render_phase::{
+            DrawFunctions, EntityRenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+            TrackedRenderPass,
+        },
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, PipelineCache, RenderPipelineDescriptor,
+            SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+            VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        view::{ComputedVisibility, Msaa, VisibleEntities},
+    },
+    sprite::{Mesh2dHandle, Mesh2dUniform, SetMesh2dBindGroup, SetMesh2dViewBindGroup},
+    utils::HashMap,
+};
+
+use super::{PremultipliedAlpha, ShapePipeline, ShapePipelineKey};
+use crate::{
+    entity::{ShapeInstance, ShapeInstances},
+    plugin::MeshConfig,
+};
+
+/// The size in bytes of the data of an instance: its position, rotation and
+/// scale, followed by its linear RGBA color.
+const INSTANCE_SIZE: u64 = 8 * 4;
+
+/// The instances of a shape, packed for the GPU.
+#[derive(Component)]
+pub(super) struct ExtractedShapeInstances {
+    data: Vec<u8>,
+    count: u32,
+}
+
+/// The GPU buffer holding the [`ExtractedShapeInstances`] of a shape.
+#[derive(Component)]
+pub(super) struct ShapeInstanceBuffer {
+    buffer: Buffer,
+    count: u32,
+}
+
+/// The instance buffer of each shape and its capacity in bytes, kept across
+/// frames so that it is only reallocated when the instances outgrow it.
+#[derive(Default)]
+pub(super) struct ShapeInstanceBuffers(HashMap<Entity, (Buffer, u64)>);
+
+/// The [`ShapePipeline`], with a second vertex buffer holding the instances.
+pub(super) struct InstancedShapePipeline {
+    shape_pipeline: ShapePipeline,
+}
+
+impl FromWorld for InstancedShapePipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            shape_pipeline: ShapePipeline::from_world(world),
+        }
+    }
+}
+
+impl SpecializedMeshPipeline for InstancedShapePipeline {
+    type Key = ShapePipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.shape_pipeline.specialize(key, layout)?;
+        descriptor.vertex.shader_defs.push("INSTANCED".to_string());
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: INSTANCE_SIZE,
+            step_mode: VertexStepMode::Instance,
+            attributes: vec![
+                // Position, rotation and scale
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 2,
+                },
+                // Color
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: INSTANCE_SIZE / 2,
+                    shader_location: 3,
+                },
+            ],
+        });
+        descriptor.label = Some("instanced_shape_pipeline".into());
+
+        Ok(descriptor)
+    }
+}
+
+// This specifies how to render the instances of a colored 2d mesh
+pub(super) type DrawInstancedShape = (
+    // Set the pipeline
+    SetItemPipeline,
+    // Set the view uniform as bind group 0
+    SetMesh2dViewBindGroup<0>,
+    // Set the mesh uniform as bind group 1
+    SetMesh2dBindGroup<1>,
+    // Draw every instance of the mesh
+    DrawShapeInstances,
+);
+
+/// Draws the mesh of a shape once for each of its instances.
+pub(super) struct DrawShapeInstances;
+
+impl EntityRenderCommand for DrawShapeInstances {
+    type Param = (
+        SRes<RenderAssets<Mesh>>,
+        SQuery<Read<Mesh2dHandle>>,
+        SQuery<Read<ShapeInstanceBuffer>>,
+    );
+
+    fn render<'w>(
+        _view: Entity,
+        item: Entity,
+        (meshes, mesh_query, instance_buffers): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let (mesh, instances) = match (mesh_query.get(item), instance_buffers.get_inner(item)) {
+            (Ok(handle), Ok(instances)) => match meshes.into_inner().get(&handle.0) {
+                Some(mesh) => (mesh, instances),
+                None => return RenderCommandResult::Failure,
+            },
+            _ => return RenderCommandResult::Failure,
+        };
+
+        pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, instances.buffer.slice(..));
+        match &mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, 0..instances.count);
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, 0..instances.count);
+            }
+        }
+
+        RenderCommandResult::Success
+    }
+}
+
+/// Extract the visible [`ShapeInstances`] into the render app
+pub(super) fn extract_shape_instances(
+    mut commands: Commands,
+    mut previous_len: Local<usize>,
+    config: Res<MeshConfig>,
+    query: Query<(Entity, &ComputedVisibility, &ShapeInstances)>,
+) {
+    let mut values = Vec::with_capacity(*previous_len);
+    for (entity, computed_visibility, instances) in query.iter() {
+        if !computed_visibility.is_visible || instances.0.is_empty() {
+            continue;
+        }
+
+        let data = pack_instances(&instances.0, config.premultiply_alpha);
+        let count = instances.0.len() as u32;
+        values.push((entity, (ExtractedShapeInstances { data, count },)));
+    }
+    *previous_len = values.len();
+    commands.insert_or_spawn_batch(values);
+}
+
+/// Packs instances for the GPU, as `[x, y, rotation, scale, r, g, b, a]` in
+/// native endianness. The color is in linear space, and premultiplied if
+/// `premultiply` is set: since it multiplies the vertex colors, it must have
+/// the same alpha as them.
+fn pack_instances(instances: &[ShapeInstance], premultiply: bool) -> Vec<u8> {
+    instances
+        .iter()
+        .flat_map(|instance| {
+            let [x, y] = instance.position.to_array();
+            let [r, g, b, a] = instance.color.as_linear_rgba_f32();
+            let rgb_scale = if premultiply { a } else { 1.0 };
+            [
+                x,
+                y,
+                instance.rotation,
+                instance.scale,
+                r * rgb_scale,
+                g * rgb_scale,
+                b * rgb_scale,
+                a,
+            ]
+        })
+        .flat_map(f32::to_ne_bytes)
+        .collect()
+}
+
+/// Upload the extracted instances to the GPU, reusing the buffer of the
+/// previous frame when they fit in it
+pub(super) fn prepare_shape_instances(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut buffers: ResMut<ShapeInstanceBuffers>,
+    query: Query<(Entity, &ExtractedShapeInstances)>,
+) {
+    let mut used = HashMap::default();
+    for (entity, instances) in query.iter() {
+        let size = instances.data.len() as u64;
+        let (buffer, capacity) = match buffers.0.remove(&entity) {
+            Some((buffer, capacity)) if capacity >= size => (buffer, capacity),
+            _ => {
+                let capacity = size.next_power_of_two();
+                let buffer = render_device.create_buffer(&BufferDescriptor {
+                    label: Some("shape_instance_buffer"),
+                    size: capacity,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                (buffer, capacity)
+            }
+        };
+        render_queue.write_buffer(&buffer, 0, &instances.data);
+        commands.entity(entity).insert(ShapeInstanceBuffer {
+            buffer: buffer.clone(),
+            count: instances.count,
+        });
+        used.insert(entity, (buffer, capacity));
+    }
+    // The buffers of the shapes that are no longer drawn are freed
+    buffers.0 = used;
+}
+
+/// Queue the shapes with instances using the instanced pipeline and draw
+/// function
+#[allow(clippy::too_many_arguments)]
+pub(super) fn queue_shape_instances(
+    transparent_draw_functions: Res<DrawFunctions<Transparent2d>>,
+    pipeline: Res<InstancedShapePipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<InstancedShapePipeline>>,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    msaa: Res<Msaa>,
+    alpha: Res<PremultipliedAlpha>,
+    render_meshes: Res<RenderAssets<Mesh>>,
+    shape: Query<(&Mesh2dHandle, &Mesh2dUniform), With<ExtractedShapeInstances>>,
+    mut views: Query<(&VisibleEntities, &mut RenderPhase<Transparent2d>)>,
+) {
+    if shape.is_empty() {
+        return;
+    }
+    for (visible_entities, mut transparent_phase) in views.iter_mut() {
+        let draw_shape = transparent_draw_functions
+            .read()
+            .get_id::<DrawInstancedShape>()
+            .unwrap();
+
+        for visible_entity in &visible_entities.entities {
+            if let Ok((mesh2d_handle, mesh2d_uniform)) = shape.get(*visible_entity) {
+                let mesh = match render_meshes.get(&mesh2d_handle.0) {
+                    Some(mesh) => mesh,
+                    None => continue,
+                };
+
+                let pipeline_id = match pipelines.specialize(
+                    &mut pipeline_cache,
+                    &pipeline,
+                    ShapePipelineKey::new(&msaa, *alpha, mesh),
+                    &mesh.layout,
+                ) {
+                    Ok(pipeline_id) => pipeline_id,
+                    Err(err) => {
+                        error!("{}", err);
+                        continue;
+                    }
+                };
+
+                let mesh_z = mesh2d_uniform.transform.w_axis.z;
+                transparent_phase.add(Transparent2d {
+                    entity: *visible_entity,
+                    draw_function: draw_shape,
+                    pipeline: pipeline_id,
+                    sort_key: FloatOrd(mesh_z),
+                    // All the instances are drawn by a single draw call
+                    batch_range: None,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)] // The packing is exact.
+mod tests {
+    use bevy::{math::Vec2, render::color::Color};
+
+    use super::*;
+
+    fn unpack(data: &[u8]) -> Vec<f32> {
+        data.chunks_exact(4)
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn instances_are_packed_with_linear_colors() {
+        let instances = [
+            ShapeInstance::default(),
+            ShapeInstance {
+                position: Vec2::new(1.0, 2.0),
+                rotation: 3.0,
+                scale: 4.0,
+                color: Color::rgba(0.5, 0.0, 1.0, 0.5),
+            },
+        ];
+        let data = pack_instances(&instances, false);
+        assert_eq!(data.len() as u64, 2 * INSTANCE_SIZE);
+
+        let [r, g, b, a] = Color::rgba(0.5, 0.0, 1.0, 0.5).as_linear_rgba_f32();
+        assert_eq!(
+            unpack(&data),
+            [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 3.0, 4.0, r, g, b, a]
+        );
+        assert!(r < 0.5);
+
+        let premultiplied = unpack(&pack_instances(&instances[1..], true));
+        assert_eq!(premultiplied[4..], [r * a, g * a, b * a, a]);
+    }
+}
@@ -12,16 +12,18 @@ use bevy::{
         system::{Commands, Local, Query, Res, ResMut},
         world::{FromWorld, World},
     },
+    log::error,
     reflect::TypeUuid,
     render::{
-        mesh::Mesh,
+        mesh::{GpuBufferInfo, GpuMesh, Mesh, MeshVertexBufferLayout},
         render_asset::RenderAssets,
         render_phase::{AddRenderCommand, DrawFunctions, RenderPhase, SetItemPipeline},
         render_resource::{
-            BlendState, ColorTargetState, ColorWrites, FragmentState, FrontFace, MultisampleState,
-            PipelineCache, PolygonMode, PrimitiveState, RenderPipelineDescriptor, Shader,
-            SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat,
-            VertexBufferLayout, VertexFormat, VertexState, VertexStepMode,
+            BlendState, ColorTargetState, ColorWrites, FragmentState, FrontFace, IndexFormat,
+            MultisampleState, PipelineCache, PolygonMode, PrimitiveState, PrimitiveTopology,
+            RenderPipelineDescriptor, Shader, SpecializedMeshPipeline,
+            SpecializedMeshPipelineError, SpecializedMeshPipelines, TextureFormat, VertexFormat,
+            VertexState,
         },
         texture::BevyDefault,
         view::{ComputedVisibility, Msaa, VisibleEntities},
@@ -33,6 +35,10 @@ use bevy::{
     },
 };
 
+use crate::plugin::MeshConfig;
+
+mod instancing;
+
 /// A marker component for colored 2d meshes
 #[derive(Component, Default)]
 pub struct Shape;
@@ -51,42 +57,91 @@ impl FromWorld for ShapePipeline {
     }
 }
 
-// We implement `SpecializedPipeline` tp customize the default rendering from
+/// The key of the [`ShapePipeline`]: the key of the [`Mesh2dPipeline`], along
+/// with the index format of strip meshes, which their pipeline must declare,
+/// and whether the vertex colors have premultiplied alpha.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct ShapePipelineKey {
+    mesh: Mesh2dPipelineKey,
+    strip_index_format: Option<IndexFormat>,
+    premultiplied_alpha: bool,
+}
+
+impl ShapePipelineKey {
+    /// Returns the key of the pipeline drawing `mesh` with the given MSAA.
+    fn new(msaa: &Msaa, alpha: PremultipliedAlpha, mesh: &GpuMesh) -> Self {
+        let is_strip = matches!(
+            mesh.primitive_topology,
+            PrimitiveTopology::TriangleStrip | PrimitiveTopology::LineStrip
+        );
+        let strip_index_format = match mesh.buffer_info {
+            GpuBufferInfo::Indexed { index_format, .. } if is_strip => Some(index_format),
+            _ => None,
+        };
+
+        Self {
+            mesh: Mesh2dPipelineKey::from_msaa_samples(msaa.samples)
+                | Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology),
+            strip_index_format,
+            premultiplied_alpha: alpha.0,
+        }
+    }
+}
+
+/// Whether the meshes are built with
+/// [`MeshConfig::premultiply_alpha`](crate::plugin::MeshConfig::premultiply_alpha),
+/// extracted into the render app.
+#[derive(Clone, Copy, Default)]
+struct PremultipliedAlpha(bool);
+
+// We implement `SpecializedMeshPipeline` to customize the default rendering from
 // `Mesh2dPipeline`
 #[allow(clippy::too_many_lines)]
-impl SpecializedRenderPipeline for ShapePipeline {
-    type Key = Mesh2dPipelineKey;
-
-    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
-        // Customize how to store the meshes' vertex attributes in the vertex buffer
-        // Our meshes only have position and color
-        let formats = vec![
-            // Position
-            VertexFormat::Float32x3,
-            // Color
-            VertexFormat::Uint32,
-        ];
-
-        let vertex_layout =
-            VertexBufferLayout::from_vertex_formats(VertexStepMode::Vertex, formats);
-
-        RenderPipelineDescriptor {
+impl SpecializedMeshPipeline for ShapePipeline {
+    type Key = ShapePipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        // The shader only reads the position and the color of the vertices,
+        // the layout skips the other attributes of the mesh, like UVs and normals
+        let vertex_layout = layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(1),
+        ])?;
+        // The colors are packed, unless the mesh was built with
+        // `MeshColorFormat::Float32x4`
+        let shader_defs = if vertex_layout.attributes[1].format == VertexFormat::Float32x4 {
+            vec!["FLOAT32X4_COLOR".to_string()]
+        } else {
+            Vec::new()
+        };
+
+        Ok(RenderPipelineDescriptor {
             vertex: VertexState {
                 // Use our custom shader
                 shader: SHAPE_SHADER_HANDLE.typed::<Shader>(),
                 entry_point: "vertex".into(),
-                shader_defs: Vec::new(),
+                shader_defs: shader_defs.clone(),
                 // Use our custom vertex buffer
                 buffers: vec![vertex_layout],
             },
             fragment: Some(FragmentState {
                 // Use our custom shader
                 shader: SHAPE_SHADER_HANDLE.typed::<Shader>(),
-                shader_defs: Vec::new(),
+                shader_defs,
                 entry_point: "fragment".into(),
                 targets: vec![ColorTargetState {
                     format: TextureFormat::bevy_default(),
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    // Premultiplied colors must not be multiplied by their
+                    // alpha again
+                    blend: Some(if key.premultiplied_alpha {
+                        BlendState::PREMULTIPLIED_ALPHA_BLENDING
+                    } else {
+                        BlendState::ALPHA_BLENDING
+                    }),
                     write_mask: ColorWrites::ALL,
                 }],
             }),
@@ -103,17 +158,17 @@ impl SpecializedRenderPipeline for ShapePipeline {
                 unclipped_depth: false,
                 polygon_mode: PolygonMode::Fill,
                 conservative: false,
-                topology: key.primitive_topology(),
-                strip_index_format: None,
+                topology: key.mesh.primitive_topology(),
+                strip_index_format: key.strip_index_format,
             },
             depth_stencil: None,
             multisample: MultisampleState {
-                count: key.msaa_samples(),
+                count: key.mesh.msaa_samples(),
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
             label: Some("shape_pipeline".into()),
-        }
+        })
     }
 }
 
@@ -129,7 +184,8 @@ type DrawShape = (
     DrawMesh2d,
 );
 
-/// Plugin that renders [`Shape`]s
+/// Plugin that renders [`Shape`]s and
+/// [`ShapeInstances`](crate::entity::ShapeInstances)
 pub struct RenderShapePlugin;
 
 /// Handle to the custom shader with a unique random ID
@@ -139,23 +195,42 @@ pub const SHAPE_SHADER_HANDLE: HandleUntyped =
 impl Plugin for RenderShapePlugin {
     fn build(&self, app: &mut App) {
         // Load our custom shader
-        let mut shaders = app.world.get_resource_mut::<Assets<Shader>>().unwrap();
-        shaders.set_untracked(
-            SHAPE_SHADER_HANDLE,
-            Shader::from_wgsl(include_str!("shape.wgsl")),
-        );
+        if let Some(mut shaders) = app.world.get_resource_mut::<Assets<Shader>>() {
+            shaders.set_untracked(
+                SHAPE_SHADER_HANDLE,
+                Shader::from_wgsl(include_str!("shape.wgsl")),
+            );
+        }
 
-        // Register our custom draw function and pipeline, and add our render systems
-        let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+        // Register our custom draw function and pipeline, and add our render systems.
+        // Headless apps, like the ones of the tests, have nothing to render to.
+        let render_app = match app.get_sub_app_mut(RenderApp) {
+            Ok(render_app) => render_app,
+            Err(_) => return,
+        };
         render_app
             .add_render_command::<Transparent2d, DrawShape>()
+            .add_render_command::<Transparent2d, instancing::DrawInstancedShape>()
+            .init_resource::<PremultipliedAlpha>()
             .init_resource::<ShapePipeline>()
-            .init_resource::<SpecializedRenderPipelines<ShapePipeline>>()
+            .init_resource::<SpecializedMeshPipelines<ShapePipeline>>()
+            .init_resource::<instancing::InstancedShapePipeline>()
+            .init_resource::<instancing::ShapeInstanceBuffers>()
+            .init_resource::<SpecializedMeshPipelines<instancing::InstancedShapePipeline>>()
+            .add_system_to_stage(RenderStage::Extract, extract_premultiplied_alpha)
             .add_system_to_stage(RenderStage::Extract, extract_shape)
-            .add_system_to_stage(RenderStage::Queue, queue_shape);
+            .add_system_to_stage(RenderStage::Extract, instancing::extract_shape_instances)
+            .add_system_to_stage(RenderStage::Prepare, instancing::prepare_shape_instances)
+            .add_system_to_stage(RenderStage::Queue, queue_shape)
+            .add_system_to_stage(RenderStage::Queue, instancing::queue_shape_instances);
     }
 }
 
+/// Extract whether the meshes have premultiplied alpha into the render app
+fn extract_premultiplied_alpha(mut commands: Commands, config: Res<MeshConfig>) {
+    commands.insert_resource(PremultipliedAlpha(config.premultiply_alpha));
+}
+
 /// Extract the [`Shape`] marker component into the render app
 fn extract_shape(
     mut commands: Commands,
@@ -179,9 +254,10 @@ fn extract_shape(
 fn queue_shape(
     transparent_draw_functions: Res<DrawFunctions<Transparent2d>>,
     shape_pipeline: Res<ShapePipeline>,
-    mut pipelines: ResMut<SpecializedRenderPipelines<ShapePipeline>>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<ShapePipeline>>,
     mut pipeline_cache: ResMut<PipelineCache>,
     msaa: Res<Msaa>,
+    alpha: Res<PremultipliedAlpha>,
     render_meshes: Res<RenderAssets<Mesh>>,
     shape: Query<(&Mesh2dHandle, &Mesh2dUniform), With<Shape>>,
     mut views: Query<(&VisibleEntities, &mut RenderPhase<Transparent2d>)>,
@@ -196,20 +272,28 @@ fn queue_shape(
             .get_id::<DrawShape>()
             .unwrap();
 
-        let mesh_key = Mesh2dPipelineKey::from_msaa_samples(msaa.samples);
-
         // Queue all entities visible to that view
         for visible_entity in &visible_entities.entities {
             if let Ok((mesh2d_handle, mesh2d_uniform)) = shape.get(*visible_entity) {
+                // Meshes that are not on the GPU yet can't be drawn
+                let mesh = match render_meshes.get(&mesh2d_handle.0) {
+                    Some(mesh) => mesh,
+                    None => continue,
+                };
+
                 // Get our specialized pipeline
-                let mut mesh2d_key = mesh_key;
-                if let Some(mesh) = render_meshes.get(&mesh2d_handle.0) {
-                    mesh2d_key |=
-                        Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology);
-                }
-
-                let pipeline_id =
-                    pipelines.specialize(&mut pipeline_cache, &shape_pipeline, mesh2d_key);
+                let pipeline_id = match pipelines.specialize(
+                    &mut pipeline_cache,
+                    &shape_pipeline,
+                    ShapePipelineKey::new(&msaa, *alpha, mesh),
+                    &mesh.layout,
+                ) {
+                    Ok(pipeline_id) => pipeline_id,
+                    Err(err) => {
+                        error!("{}", err);
+                        continue;
+                    }
+                };
 
                 let mesh_z = mesh2d_uniform.transform.w_axis.z;
                 transparent_phase.add(Transparent2d {
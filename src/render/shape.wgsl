@@ -8,7 +8,17 @@ var<uniform> mesh: Mesh2d;
 // The structure of the vertex buffer is as specified in `specialize()`
 struct Vertex {
     [[location(0)]] position: vec3<f32>;
+#ifdef FLOAT32X4_COLOR
+    [[location(1)]] color: vec4<f32>;
+#endif
+#ifndef FLOAT32X4_COLOR
     [[location(1)]] color: u32;
+#endif
+#ifdef INSTANCED
+    // The position, rotation and scale of the instance
+    [[location(2)]] i_transform: vec4<f32>;
+    [[location(3)]] i_color: vec4<f32>;
+#endif
 };
 struct VertexOutput {
     // The vertex shader must set the on-screen position of the vertex
@@ -20,9 +30,30 @@ struct VertexOutput {
 [[stage(vertex)]]
 fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
+    var position = vertex.position;
+#ifdef INSTANCED
+    // Rotate, scale and move the vertex to its instance
+    let c = cos(vertex.i_transform.z);
+    let s = sin(vertex.i_transform.z);
+    let scaled = vertex.position.xy * vertex.i_transform.w;
+    position = vec3<f32>(
+        scaled.x * c - scaled.y * s + vertex.i_transform.x,
+        scaled.x * s + scaled.y * c + vertex.i_transform.y,
+        vertex.position.z
+    );
+#endif
     // Project the world position of the mesh into screen position
-    out.clip_position = view.view_proj * mesh.model * vec4<f32>(vertex.position, 1.0);
+    out.clip_position = view.view_proj * mesh.model * vec4<f32>(position, 1.0);
+#ifdef FLOAT32X4_COLOR
+    out.color = vertex.color;
+#endif
+#ifndef FLOAT32X4_COLOR
+    // Unpack the linear RGBA color, stored with red in the least significant byte
     out.color = vec4<f32>((vec4<u32>(vertex.color) >> vec4<u32>(0u, 8u, 16u, 24u)) & vec4<u32>(255u)) / 255.0;
+#endif
+#ifdef INSTANCED
+    out.color = out.color * vertex.i_color;
+#endif
     return out;
 }
 // The input of the fragment shader must correspond to the output of the vertex shader for all `location`s
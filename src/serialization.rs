@@ -0,0 +1,215 @@
+//! Serde support for the foreign types stored in draw modes.
+//!
+//! These modules are meant to be used with `#[serde(with = "...")]`. Colors
+//! are stored as sRGB `[r, g, b, a]` arrays, and lyon's tessellation options
+//! as mirror structs holding their fields.
+
+use lyon_tessellation::{FillRule, LineCap, LineJoin, Orientation};
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)] // Only used through `serde(with)`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "FillRule")]
+enum FillRuleDef {
+    EvenOdd,
+    NonZero,
+}
+
+#[allow(dead_code)] // Only used through `serde(with)`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Orientation")]
+enum OrientationDef {
+    Vertical,
+    Horizontal,
+}
+
+#[allow(dead_code)] // Only used through `serde(with)`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LineCap")]
+enum LineCapDef {
+    Butt,
+    Square,
+    Round,
+}
+
+#[allow(dead_code)] // Only used through `serde(with)`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LineJoin")]
+enum LineJoinDef {
+    Miter,
+    MiterClip,
+    Round,
+    Bevel,
+}
+
+pub mod color {
+    use bevy::render::color::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        color.as_rgba_f32().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let [r, g, b, a] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Color::rgba(r, g, b, a))
+    }
+}
+
+pub mod fill_options {
+    use lyon_tessellation::{FillOptions, FillRule, Orientation};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{FillRuleDef, OrientationDef};
+
+    #[derive(Serialize, Deserialize)]
+    struct FillOptionsDef {
+        tolerance: f32,
+        #[serde(with = "FillRuleDef")]
+        fill_rule: FillRule,
+        #[serde(with = "OrientationDef")]
+        sweep_orientation: Orientation,
+        handle_intersections: bool,
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+    pub fn serialize<S: Serializer>(
+        options: &FillOptions,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        FillOptionsDef {
+            tolerance: options.tolerance,
+            fill_rule: options.fill_rule,
+            sweep_orientation: options.sweep_orientation,
+            handle_intersections: options.handle_intersections,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FillOptions, D::Error> {
+        let def = FillOptionsDef::deserialize(deserializer)?;
+        let mut options = FillOptions::default();
+        options.tolerance = def.tolerance;
+        options.fill_rule = def.fill_rule;
+        options.sweep_orientation = def.sweep_orientation;
+        options.handle_intersections = def.handle_intersections;
+
+        Ok(options)
+    }
+}
+
+pub mod stroke_options {
+    use lyon_tessellation::{LineCap, LineJoin, StrokeOptions};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{LineCapDef, LineJoinDef};
+
+    #[derive(Serialize, Deserialize)]
+    struct StrokeOptionsDef {
+        #[serde(with = "LineCapDef")]
+        start_cap: LineCap,
+        #[serde(with = "LineCapDef")]
+        end_cap: LineCap,
+        #[serde(with = "LineJoinDef")]
+        line_join: LineJoin,
+        line_width: f32,
+        miter_limit: f32,
+        tolerance: f32,
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+    pub fn serialize<S: Serializer>(
+        options: &StrokeOptions,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        StrokeOptionsDef {
+            start_cap: options.start_cap,
+            end_cap: options.end_cap,
+            line_join: options.line_join,
+            line_width: options.line_width,
+            miter_limit: options.miter_limit,
+            tolerance: options.tolerance,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<StrokeOptions, D::Error> {
+        let def = StrokeOptionsDef::deserialize(deserializer)?;
+        let mut options = StrokeOptions::default();
+        options.start_cap = def.start_cap;
+        options.end_cap = def.end_cap;
+        options.line_join = def.line_join;
+        options.line_width = def.line_width;
+        options.miter_limit = def.miter_limit;
+        options.tolerance = def.tolerance;
+
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::Vec2, render::color::Color};
+
+    use crate::{
+        draw::{DrawMode, FillMode, StrokeGradient, StrokeMode},
+        shapes::{RegularPolygon, RegularPolygonFeature},
+    };
+
+    #[test]
+    fn outlined_regular_polygon_round_trips() {
+        let shape = RegularPolygon {
+            sides: 6,
+            center: Vec2::new(1.0, -2.0),
+            feature: RegularPolygonFeature::Radius(50.0),
+        };
+        let mode = DrawMode::Outlined {
+            fill_mode: FillMode::color(Color::rgba(0.1, 0.2, 0.3, 0.4)).with_tolerance(0.05),
+            outline_mode: StrokeMode::new(Color::BLACK, 4.0),
+        };
+
+        let json = serde_json::to_string(&(shape, mode)).unwrap();
+        let (shape_back, mode_back): (RegularPolygon, DrawMode) =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(shape_back, shape);
+        assert_eq!(mode_back, mode);
+    }
+
+    #[test]
+    fn custom_gradients_fail_to_serialize() {
+        let fill = FillMode::per_vertex(|_| Color::RED);
+        assert!(serde_json::to_string(&fill).is_err());
+
+        let stroke =
+            StrokeMode::new(Color::RED, 2.0).with_gradient(StrokeGradient::Custom(|_| Color::RED));
+        assert!(serde_json::to_string(&stroke).is_err());
+    }
+
+    #[test]
+    fn modes_saved_before_the_newer_fields_still_load() {
+        let mut fill = serde_json::to_value(FillMode::color(Color::RED)).unwrap();
+        let mut stroke = serde_json::to_value(StrokeMode::new(Color::RED, 2.0)).unwrap();
+        for field in [
+            "gradient",
+            "antialiasing",
+            "closing",
+            "palette_index",
+            "delaunay_refinement",
+            "overrides",
+        ] {
+            fill.as_object_mut().unwrap().remove(field);
+        }
+        for field in ["gradient", "alignment", "behind_fill", "overrides"] {
+            stroke.as_object_mut().unwrap().remove(field);
+        }
+
+        let fill: FillMode = serde_json::from_value(fill).unwrap();
+        let stroke: StrokeMode = serde_json::from_value(stroke).unwrap();
+        assert_eq!(fill, FillMode::color(Color::RED));
+        assert_eq!(stroke, StrokeMode::new(Color::RED, 2.0));
+    }
+}
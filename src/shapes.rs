@@ -4,26 +4,34 @@
 //! [`Geometry`](crate::geometry::Geometry) trait. You can also implement
 //! the trait for your own shapes.
 
-use bevy::math::Vec2;
+use bevy::{
+    log::error,
+    math::{UVec2, Vec2},
+};
 use lyon_tessellation::{
-    math::{point, Angle, Point, Rect, Size, Vector},
+    geom::{Arc as LyonArc, SvgArc},
+    math::{point, Angle, Point, Rect, Size, Transform, Vector},
     path::{
         builder::WithSvg,
         path::Builder,
         traits::{PathBuilder, SvgPathBuilder},
-        ArcFlags, Polygon as LyonPolygon, Winding,
+        ArcFlags, Path as LyonPath, Polygon as LyonPolygon, Winding,
     },
+    FillOptions,
 };
+pub use svgtypes::Error as SvgPathError;
 use svgtypes::{Path, PathSegment};
 
 use crate::{
+    convert::{ToPoint, ToVec2, ToVector},
     geometry::Geometry,
-    utils::{ToPoint, ToVector},
+    utils::flatten,
 };
 
 /// Defines where the origin, or pivot of the `Rectangle` should be positioned.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RectangleOrigin {
     Center,
     BottomLeft,
@@ -41,6 +49,7 @@ impl Default for RectangleOrigin {
 
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     pub extents: Vec2,
     pub origin: RectangleOrigin,
@@ -55,18 +64,24 @@ impl Default for Rectangle {
     }
 }
 
+impl RectangleOrigin {
+    /// Returns the position of the bottom left corner of a rectangle with the
+    /// given extents.
+    fn bottom_left(self, extents: Vec2) -> Point {
+        match self {
+            Self::Center => Point::new(-extents.x / 2.0, -extents.y / 2.0),
+            Self::BottomLeft => Point::new(0.0, 0.0),
+            Self::BottomRight => Point::new(-extents.x, 0.0),
+            Self::TopRight => Point::new(-extents.x, -extents.y),
+            Self::TopLeft => Point::new(0.0, -extents.y),
+            Self::CustomCenter(v) => Point::new(v.x - extents.x / 2.0, v.y - extents.y / 2.0),
+        }
+    }
+}
+
 impl Geometry for Rectangle {
     fn add_geometry(&self, b: &mut Builder) {
-        let origin = match self.origin {
-            RectangleOrigin::Center => Point::new(-self.extents.x / 2.0, -self.extents.y / 2.0),
-            RectangleOrigin::BottomLeft => Point::new(0.0, 0.0),
-            RectangleOrigin::BottomRight => Point::new(-self.extents.x, 0.0),
-            RectangleOrigin::TopRight => Point::new(-self.extents.x, -self.extents.y),
-            RectangleOrigin::TopLeft => Point::new(0.0, -self.extents.y),
-            RectangleOrigin::CustomCenter(v) => {
-                Point::new(v.x - self.extents.x / 2.0, v.y - self.extents.y / 2.0)
-            }
-        };
+        let origin = self.origin.bottom_left(self.extents);
 
         b.add_rectangle(
             &Rect::new(origin, Size::new(self.extents.x, self.extents.y)),
@@ -75,11 +90,254 @@ impl Geometry for Rectangle {
     }
 }
 
+/// The radius of each corner of a [`RoundedRectangle`].
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CornerRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadii {
+    /// Returns radii with the same value for every corner.
+    #[must_use]
+    pub const fn uniform(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+}
+
+/// A rectangle with rounded corners.
+///
+/// Each radius is clamped between zero and half the shorter side, so that
+/// corners never overlap. When all the radii are zero, the shape is the same as
+/// a [`Rectangle`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundedRectangle {
+    pub extents: Vec2,
+    pub radii: CornerRadii,
+    pub origin: RectangleOrigin,
+}
+
+impl Default for RoundedRectangle {
+    fn default() -> Self {
+        Self {
+            extents: Vec2::ONE,
+            radii: CornerRadii::uniform(0.1),
+            origin: RectangleOrigin::default(),
+        }
+    }
+}
+
+impl Geometry for RoundedRectangle {
+    fn add_geometry(&self, b: &mut Builder) {
+        // Distance of the control points of a cubic bezier approximating a
+        // quarter circle, relative to the radius.
+        const KAPPA: f32 = 0.552_284_8;
+
+        let max_radius = self.extents.x.min(self.extents.y) / 2.0;
+        let clamp = |radius: f32| radius.clamp(0.0, max_radius.max(0.0));
+        let radii = CornerRadii {
+            top_left: clamp(self.radii.top_left),
+            top_right: clamp(self.radii.top_right),
+            bottom_right: clamp(self.radii.bottom_right),
+            bottom_left: clamp(self.radii.bottom_left),
+        };
+        if radii == CornerRadii::default() {
+            Rectangle {
+                extents: self.extents,
+                origin: self.origin,
+            }
+            .add_geometry(b);
+            return;
+        }
+
+        let min = self.origin.bottom_left(self.extents);
+        let max = min + self.extents.to_vector();
+        // Counter-clockwise, starting after the bottom left corner.
+        let start = point(min.x + radii.bottom_left, min.y);
+        b.begin(start);
+        let mut corner = |corner: Point, from: Point, to: Point| {
+            b.line_to(from);
+            if from != to {
+                b.cubic_bezier_to(from.lerp(corner, KAPPA), to.lerp(corner, KAPPA), to);
+            }
+        };
+        corner(
+            point(max.x, min.y),
+            point(max.x - radii.bottom_right, min.y),
+            point(max.x, min.y + radii.bottom_right),
+        );
+        corner(
+            max,
+            point(max.x, max.y - radii.top_right),
+            point(max.x - radii.top_right, max.y),
+        );
+        corner(
+            point(min.x, max.y),
+            point(min.x + radii.top_left, max.y),
+            point(min.x, max.y - radii.top_left),
+        );
+        if radii.bottom_left > 0.0 {
+            corner(min, point(min.x, min.y + radii.bottom_left), start);
+        }
+        b.end(true);
+    }
+}
+
+/// A rectangle whose short sides are replaced by half circles, also called a
+/// stadium, for health bars or buttons.
+///
+/// A capsule as wide as it is high is a circle.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let capsule = ShapePath::build_as(&shapes::Capsule {
+///     extents: Vec2::new(200.0, 40.0),
+///     ..shapes::Capsule::default()
+/// });
+///
+/// let bounds = capsule.bounding_box();
+/// assert!(bounds.min.abs_diff_eq(Vec2::new(-100.0, -20.0), 1e-3));
+/// assert!(bounds.max.abs_diff_eq(Vec2::new(100.0, 20.0), 1e-3));
+/// ```
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capsule {
+    pub extents: Vec2,
+    pub origin: RectangleOrigin,
+}
+
+impl Default for Capsule {
+    fn default() -> Self {
+        Self {
+            extents: Vec2::new(2.0, 1.0),
+            origin: RectangleOrigin::default(),
+        }
+    }
+}
+
+impl Geometry for Capsule {
+    fn add_geometry(&self, b: &mut Builder) {
+        RoundedRectangle {
+            extents: self.extents,
+            radii: CornerRadii::uniform(self.extents.min_element() / 2.0),
+            origin: self.origin,
+        }
+        .add_geometry(b);
+    }
+}
+
+/// A heart, made of two mirrored cubic beziers going from the cusp at its top
+/// to the point at its bottom.
+///
+/// The heart fits in a square of `size` sides centered on `center`. The path
+/// winds counter-clockwise, like the other closed shapes.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use tess::path::PathEvent;
+/// #
+/// let heart = ShapePath::build_as(&shapes::Heart {
+///     size: 100.0,
+///     ..shapes::Heart::default()
+/// });
+///
+/// let mut points = Vec::new();
+/// for event in heart.0.iter() {
+///     match event {
+///         PathEvent::Begin { at } => points.push(at),
+///         PathEvent::Cubic { ctrl1, ctrl2, to, .. } => points.extend([ctrl1, ctrl2, to]),
+///         PathEvent::End { close, .. } => assert!(close),
+///         _ => unreachable!(),
+///     }
+/// }
+/// // Each point has a mirror image across the vertical axis.
+/// for p in &points {
+///     assert!(points.iter().any(|q| q.x == -p.x && q.y == p.y));
+/// }
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heart {
+    pub size: f32,
+    pub center: Vec2,
+}
+
+impl Default for Heart {
+    fn default() -> Self {
+        Self {
+            size: 1.0,
+            center: Vec2::ZERO,
+        }
+    }
+}
+
+impl Geometry for Heart {
+    fn add_geometry(&self, b: &mut Builder) {
+        // The control points of the left half of a heart of unit size, from
+        // the cusp to the bottom point.
+        const CUSP: Vec2 = Vec2::new(0.0, 0.3);
+        const CTRL1: Vec2 = Vec2::new(-0.42, 0.83);
+        const CTRL2: Vec2 = Vec2::new(-0.88, 0.19);
+        const BOTTOM: Vec2 = Vec2::new(0.0, -0.5);
+
+        // A negative size would flip the heart upside down.
+        let place = |p: Vec2| (p * self.size.abs() + self.center).to_point();
+        let mirror = |p: Vec2| Vec2::new(-p.x, p.y);
+        b.begin(place(CUSP));
+        b.cubic_bezier_to(place(CTRL1), place(CTRL2), place(BOTTOM));
+        b.cubic_bezier_to(place(mirror(CTRL2)), place(mirror(CTRL1)), place(CUSP));
+        b.end(true);
+    }
+}
+
+/// A circle.
+///
+/// When `segments` is set, the circle is drawn as a regular polygon with that
+/// many sides, whatever the tolerance, for a low-poly look. Fewer than three
+/// segments give an empty path.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let hexagon = ShapePath::build_as(&shapes::Circle {
+///     radius: 10.0,
+///     segments: Some(6),
+///     ..shapes::Circle::default()
+/// });
+/// assert_eq!(hexagon.vertex_count(), 6);
+/// assert!(hexagon.contains_point(Vec2::new(9.9, 0.0), FillRule::NonZero));
+/// assert!(!hexagon.contains_point(Vec2::new(0.0, 9.9), FillRule::NonZero));
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Circle {
     pub radius: f32,
     pub center: Vec2,
+    pub segments: Option<u32>,
 }
 
 impl Default for Circle {
@@ -87,21 +345,49 @@ impl Default for Circle {
         Self {
             radius: 1.0,
             center: Vec2::ZERO,
+            segments: None,
         }
     }
 }
 
 impl Geometry for Circle {
     fn add_geometry(&self, b: &mut Builder) {
-        b.add_circle(self.center.to_point(), self.radius, Winding::Positive);
+        if let Some(segments) = self.segments {
+            add_ellipse_polygon(b, self.center, Vec2::splat(self.radius), 0.0, segments);
+        } else {
+            b.add_circle(self.center.to_point(), self.radius, Winding::Positive);
+        }
     }
 }
 
+/// An ellipse, with its `radii` along the x and y axes before being rotated
+/// counter-clockwise by `rotation` radians around its center.
+///
+/// The rotation is baked into the path, so the shape doesn't need a rotated
+/// `Transform`. As with [`Circle`], setting `segments` draws the ellipse with
+/// exactly that many straight segments.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let ellipse = shapes::Ellipse {
+///     radii: Vec2::new(40.0, 20.0),
+///     ..shapes::Ellipse::default()
+/// };
+/// let bounds = ShapePath::build_as(&ellipse).bounding_box();
+/// assert!((bounds.max - bounds.min).abs_diff_eq(Vec2::new(80.0, 40.0), 1e-3));
+/// ```
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ellipse {
     pub radii: Vec2,
     pub center: Vec2,
+    pub rotation: f32,
+    pub segments: Option<u32>,
 }
 
 impl Default for Ellipse {
@@ -109,23 +395,174 @@ impl Default for Ellipse {
         Self {
             radii: Vec2::ONE,
             center: Vec2::ZERO,
+            rotation: 0.0,
+            segments: None,
         }
     }
 }
 
 impl Geometry for Ellipse {
     fn add_geometry(&self, b: &mut Builder) {
+        if let Some(segments) = self.segments {
+            add_ellipse_polygon(b, self.center, self.radii, self.rotation, segments);
+        } else {
+            b.add_ellipse(
+                self.center.to_point(),
+                self.radii.to_vector(),
+                Angle::radians(self.rotation),
+                Winding::Positive,
+            );
+        }
+    }
+}
+
+/// A ring, or annulus: the region between two concentric circles.
+///
+/// The inner circle winds the other way around, so the hole is cut with both
+/// fill rules. A ring whose `inner_radius` is not smaller than its
+/// `outer_radius` produces no geometry, and one whose `inner_radius` is zero
+/// or negative is a plain circle.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::{prelude::*, render::mesh::VertexAttributeValues};
+/// # use bevy_prototype_lyon::{plugin::tessellate_fill, prelude::*};
+/// #
+/// let ring = ShapePath::build_as(&shapes::Ring {
+///     inner_radius: 50.0,
+///     outer_radius: 100.0,
+///     ..shapes::Ring::default()
+/// });
+/// let mesh = tessellate_fill(&ring, &FillMode::color(Color::RED));
+///
+/// let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+///     Some(VertexAttributeValues::Float32x3(positions)) => positions,
+///     _ => unreachable!(),
+/// };
+/// let indices = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+/// let area: f32 = indices
+///     .chunks_exact(3)
+///     .map(|t| {
+///         let [a, b, c] = [t[0], t[1], t[2]].map(|i| Vec3::from(positions[i]).truncate());
+///         (b - a).perp_dot(c - a).abs() / 2.0
+///     })
+///     .sum();
+/// let expected = std::f32::consts::PI * (100.0_f32.powi(2) - 50.0_f32.powi(2));
+/// assert!((area - expected).abs() / expected < 1e-2);
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ring {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub center: Vec2,
+}
+
+impl Default for Ring {
+    fn default() -> Self {
+        Self {
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            center: Vec2::ZERO,
+        }
+    }
+}
+
+impl Geometry for Ring {
+    fn add_geometry(&self, b: &mut Builder) {
+        EllipseRing {
+            inner_radii: Vec2::splat(self.inner_radius),
+            outer_radii: Vec2::splat(self.outer_radius),
+            center: self.center,
+            rotation: 0.0,
+        }
+        .add_geometry(b);
+    }
+}
+
+/// The region between two concentric ellipses, both rotated counter-clockwise
+/// by `rotation` radians around their center.
+///
+/// Like [`Ring`], it produces no geometry unless both `inner_radii` are
+/// smaller than the `outer_radii`, and is a plain ellipse if an inner radius
+/// is zero or negative.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EllipseRing {
+    pub inner_radii: Vec2,
+    pub outer_radii: Vec2,
+    pub center: Vec2,
+    pub rotation: f32,
+}
+
+impl Default for EllipseRing {
+    fn default() -> Self {
+        Self {
+            inner_radii: Vec2::new(1.0, 0.5),
+            outer_radii: Vec2::new(2.0, 1.0),
+            center: Vec2::ZERO,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Geometry for EllipseRing {
+    fn add_geometry(&self, b: &mut Builder) {
+        if self.inner_radii.cmpge(self.outer_radii).any() {
+            return;
+        }
+
+        let center = self.center.to_point();
+        let rotation = Angle::radians(self.rotation);
         b.add_ellipse(
-            self.center.to_point(),
-            self.radii.to_vector(),
-            Angle::zero(),
+            center,
+            self.outer_radii.to_vector(),
+            rotation,
             Winding::Positive,
         );
+        if self.inner_radii.cmpgt(Vec2::ZERO).all() {
+            b.add_ellipse(
+                center,
+                self.inner_radii.to_vector(),
+                rotation,
+                Winding::Negative,
+            );
+        }
+    }
+}
+
+/// Adds a polygon with `segments` vertices evenly spaced in angle around an
+/// ellipse, counter-clockwise from the end of its rotated x axis.
+fn add_ellipse_polygon(b: &mut Builder, center: Vec2, radii: Vec2, rotation: f32, segments: u32) {
+    if segments < 3 {
+        return;
     }
+
+    let (sin, cos) = rotation.sin_cos();
+    let points = (0..segments)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * i as f32 / segments as f32;
+            let local = radii * Vec2::new(angle.cos(), angle.sin());
+            let rotated = Vec2::new(
+                local.x.mul_add(cos, -local.y * sin),
+                local.x.mul_add(sin, local.y * cos),
+            );
+            (center + rotated).to_point()
+        })
+        .collect::<Vec<Point>>();
+
+    b.add_polygon(LyonPolygon {
+        points: &points,
+        closed: true,
+    });
 }
 
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polygon {
     pub points: Vec<Vec2>,
     pub closed: bool,
@@ -156,9 +593,77 @@ impl Geometry for Polygon {
     }
 }
 
+/// A closed polygon with holes cut out of it.
+///
+/// The `outer` ring is emitted counter-clockwise and each of the `holes`
+/// clockwise, whatever the order of their points, so the holes stay empty
+/// with both [`FillRule`](crate::prelude::FillRule)s.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let square = |half_size: f32| {
+///     vec![
+///         Vec2::new(-half_size, -half_size),
+///         Vec2::new(half_size, -half_size),
+///         Vec2::new(half_size, half_size),
+///         Vec2::new(-half_size, half_size),
+///     ]
+/// };
+/// let frame = shapes::PolygonWithHoles {
+///     outer: square(100.0),
+///     holes: vec![square(50.0)],
+/// };
+/// let path = ShapePath::build_as(&frame);
+///
+/// assert!(path.contains_point(Vec2::new(75.0, 0.0), FillRule::NonZero));
+/// assert!(!path.contains_point(Vec2::ZERO, FillRule::NonZero));
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolygonWithHoles {
+    pub outer: Vec<Vec2>,
+    pub holes: Vec<Vec<Vec2>>,
+}
+
+impl Geometry for PolygonWithHoles {
+    fn add_geometry(&self, b: &mut Builder) {
+        add_ring(b, &self.outer, true);
+        for hole in &self.holes {
+            add_ring(b, hole, false);
+        }
+    }
+}
+
+/// Adds a closed ring to the builder, reversing its points if needed to make
+/// it counter-clockwise or clockwise.
+fn add_ring(b: &mut Builder, ring: &[Vec2], counter_clockwise: bool) {
+    let mut points = ring.iter().map(|p| p.to_point()).collect::<Vec<Point>>();
+    // Shoelace formula: the signed area is positive for counter-clockwise
+    // rings, since the y axis points up.
+    let area = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(p, q)| p.x.mul_add(q.y, -q.x * p.y))
+        .sum::<f32>();
+    if (area > 0.0) != counter_clockwise {
+        points.reverse();
+    }
+
+    b.add_polygon(LyonPolygon {
+        points: &points,
+        closed: true,
+    });
+}
+
 /// The regular polygon feature used to determine the dimensions of the polygon.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegularPolygonFeature {
     /// The radius of the polygon's circumcircle.
     Radius(f32),
@@ -170,6 +675,7 @@ pub enum RegularPolygonFeature {
 
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RegularPolygon {
     pub sides: usize,
     pub center: Vec2,
@@ -177,8 +683,24 @@ pub struct RegularPolygon {
 }
 
 impl RegularPolygon {
-    /// Gets the radius of the polygon.
-    fn radius(&self) -> f32 {
+    /// Returns the radius of the polygon's circumcircle, whichever
+    /// [`RegularPolygonFeature`] it is sized by.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// #
+    /// // The sides of a regular hexagon are as long as its circumradius.
+    /// let hexagon = RegularPolygon {
+    ///     sides: 6,
+    ///     feature: RegularPolygonFeature::SideLength(10.0),
+    ///     ..RegularPolygon::default()
+    /// };
+    /// assert!((hexagon.circumradius() - 10.0).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn circumradius(&self) -> f32 {
         let ratio = std::f32::consts::PI / self.sides as f32;
 
         match self.feature {
@@ -209,7 +731,7 @@ impl Geometry for RegularPolygon {
         use std::f32::consts::PI;
         assert!(self.sides > 2, "Polygons must have at least 3 sides");
         let n = self.sides as f32;
-        let radius = self.radius();
+        let radius = self.circumradius();
         let internal = (n - 2.0) * PI / n;
         let offset = -internal / 2.0;
 
@@ -234,6 +756,7 @@ impl Geometry for RegularPolygon {
 /// A simple line segment, specified by two points.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line(pub Vec2, pub Vec2);
 
 impl Geometry for Line {
@@ -244,6 +767,506 @@ impl Geometry for Line {
         });
     }
 }
+
+/// An arrow going from `start` to `end`: a line for its shaft, and a
+/// triangle for its head, whose tip is at `end`.
+///
+/// The head is `head_length` long along the arrow, and `head_width` wide
+/// across it. Stroking the arrow draws the shaft and the outline of the head,
+/// filling it only draws the head. A head longer than the arrow is shortened
+/// to its length, leaving no shaft. Zero-length arrows produce no geometry.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use tess::path::PathEvent;
+/// #
+/// let arrow = ShapePath::build_as(&shapes::Arrow {
+///     start: Vec2::ZERO,
+///     end: Vec2::new(30.0, 40.0),
+///     head_length: 10.0,
+///     head_width: 6.0,
+/// });
+///
+/// // The last sub-path is the head: its tip, then the corners of its base.
+/// let head = arrow
+///     .0
+///     .iter()
+///     .filter_map(|event| match event {
+///         PathEvent::Begin { at } => Some(Vec2::new(at.x, at.y)),
+///         PathEvent::Line { to, .. } => Some(Vec2::new(to.x, to.y)),
+///         _ => None,
+///     })
+///     .skip(2)
+///     .collect::<Vec<_>>();
+/// let direction = Vec2::new(0.6, 0.8);
+/// assert!(head[0].abs_diff_eq(Vec2::new(30.0, 40.0), 1e-4));
+/// assert!(((head[1] + head[2]) / 2.0).abs_diff_eq(head[0] - direction * 10.0, 1e-4));
+/// assert!((head[0] - head[1]).normalize().dot(direction) > 0.9);
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arrow {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub head_length: f32,
+    pub head_width: f32,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        Self {
+            start: Vec2::ZERO,
+            end: Vec2::X,
+            head_length: 0.25,
+            head_width: 0.25,
+        }
+    }
+}
+
+impl Geometry for Arrow {
+    fn add_geometry(&self, b: &mut Builder) {
+        let length = self.start.distance(self.end);
+        if length <= 0.0 {
+            return;
+        }
+
+        let direction = (self.end - self.start) / length;
+        let head_length = self.head_length.clamp(0.0, length);
+        let base = self.end - direction * head_length;
+        let half_width = direction.perp() * self.head_width / 2.0;
+        if head_length < length {
+            b.add_polygon(LyonPolygon {
+                points: &[self.start.to_point(), base.to_point()],
+                closed: false,
+            });
+        }
+        b.add_polygon(LyonPolygon {
+            points: &[
+                self.end.to_point(),
+                (base + half_width).to_point(),
+                (base - half_width).to_point(),
+            ],
+            closed: true,
+        });
+    }
+}
+
+/// A chain of line segments going through `points`, meant to be stroked.
+///
+/// If `closed` is `true`, the last point is also connected to the first one.
+/// Less than two points result in an empty path.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let zig_zag = shapes::Polyline {
+///     points: vec![Vec2::ZERO, Vec2::new(10.0, 10.0), Vec2::new(20.0, 0.0)],
+///     ..shapes::Polyline::default()
+/// };
+/// let path = ShapePath::build_as(&zig_zag);
+/// // One `Begin` and two `Line` events, one for each point.
+/// assert_eq!(path.0.iter().count() - 1, zig_zag.points.len());
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+impl Geometry for Polyline {
+    fn add_geometry(&self, b: &mut Builder) {
+        if self.points.len() < 2 {
+            return;
+        }
+
+        let points = self
+            .points
+            .iter()
+            .map(|p| p.to_point())
+            .collect::<Vec<Point>>();
+        b.add_polygon(LyonPolygon {
+            points: points.as_slice(),
+            closed: self.closed,
+        });
+    }
+}
+
+/// A grid of `count.x` columns and `count.y` rows of cells, all of them
+/// `cell_size` wide, placed around `origin` like a [`Rectangle`] covering the
+/// whole grid.
+///
+/// With [`GridStyle::Lines`], the path has an open sub-path for each line
+/// between or around the cells, meant to be stroked. With
+/// [`GridStyle::Cells`], it has a closed rectangle for each cell, meant to be
+/// filled. Grids without columns or rows result in an empty path.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// # use tess::path::PathEvent;
+/// #
+/// let grid = shapes::Grid {
+///     cell_size: Vec2::splat(10.0),
+///     count: UVec2::new(10, 5),
+///     ..shapes::Grid::default()
+/// };
+/// let path = ShapePath::build_as(&grid);
+/// let lines = path
+///     .0
+///     .iter()
+///     .filter(|event| matches!(event, PathEvent::Begin { .. }))
+///     .count();
+/// // 11 vertical lines and 6 horizontal ones.
+/// assert_eq!(lines, 17);
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid {
+    pub cell_size: Vec2,
+    pub count: UVec2,
+    pub origin: RectangleOrigin,
+    pub style: GridStyle,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            cell_size: Vec2::ONE,
+            count: UVec2::ONE,
+            origin: RectangleOrigin::default(),
+            style: GridStyle::default(),
+        }
+    }
+}
+
+/// What the path of a [`Grid`] is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridStyle {
+    /// The lines between and around the cells.
+    Lines,
+    /// The outlines of the cells.
+    Cells,
+}
+
+impl Default for GridStyle {
+    fn default() -> Self {
+        Self::Lines
+    }
+}
+
+impl Geometry for Grid {
+    fn add_geometry(&self, b: &mut Builder) {
+        if self.count.x == 0 || self.count.y == 0 {
+            return;
+        }
+
+        let extents = self.cell_size * self.count.as_vec2();
+        let origin = self.origin.bottom_left(extents).to_vec2();
+        let corner =
+            |column: u32, row: u32| origin + self.cell_size * UVec2::new(column, row).as_vec2();
+        match self.style {
+            GridStyle::Lines => {
+                for column in 0..=self.count.x {
+                    Line(corner(column, 0), corner(column, self.count.y)).add_geometry(b);
+                }
+                for row in 0..=self.count.y {
+                    Line(corner(0, row), corner(self.count.x, row)).add_geometry(b);
+                }
+            }
+            GridStyle::Cells => {
+                let size = Size::new(self.cell_size.x, self.cell_size.y);
+                for row in 0..self.count.y {
+                    for column in 0..self.count.x {
+                        let cell = Rect::new(corner(column, row).to_point(), size);
+                        b.add_rectangle(&cell, Winding::Positive);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An open circular arc, suitable for stroking.
+///
+/// The arc starts at `start_angle` and covers `sweep_angle`, both in radians.
+/// Positive sweeps go counter-clockwise, negative ones go clockwise. Sweeps
+/// of a full turn or more are clamped to a full circle, which is closed so that
+/// its stroke has no seam. Arcs with no sweep or no radius are empty.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arc {
+    pub radius: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+    pub center: Vec2,
+}
+
+impl Default for Arc {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            start_angle: 0.0,
+            sweep_angle: std::f32::consts::PI,
+            center: Vec2::ZERO,
+        }
+    }
+}
+
+impl Arc {
+    fn lyon_arc(&self) -> LyonArc<f32> {
+        use std::f32::consts::TAU;
+
+        LyonArc {
+            center: self.center.to_point(),
+            radii: Vector::new(self.radius, self.radius),
+            start_angle: Angle::radians(self.start_angle),
+            sweep_angle: Angle::radians(self.sweep_angle.clamp(-TAU, TAU)),
+            x_rotation: Angle::zero(),
+        }
+    }
+}
+
+impl Geometry for Arc {
+    fn add_geometry(&self, b: &mut Builder) {
+        if self.sweep_angle == 0.0 || self.radius <= 0.0 {
+            return;
+        }
+
+        let arc = self.lyon_arc();
+        b.begin(arc.from());
+        arc.for_each_cubic_bezier(&mut |segment| {
+            b.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+        b.end(self.sweep_angle.abs() >= std::f32::consts::TAU);
+    }
+}
+
+/// A closed circular sector ("pie slice"), suitable for filling.
+///
+/// The sector is delimited by the two radii at `start_angle` and at
+/// `start_angle + sweep_angle`, both in radians. A sweep of a full turn or more
+/// produces a circle.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sector {
+    pub radius: f32,
+    pub start_angle: f32,
+    pub sweep_angle: f32,
+    pub center: Vec2,
+}
+
+impl Default for Sector {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            start_angle: 0.0,
+            sweep_angle: std::f32::consts::FRAC_PI_2,
+            center: Vec2::ZERO,
+        }
+    }
+}
+
+impl Geometry for Sector {
+    fn add_geometry(&self, b: &mut Builder) {
+        if self.sweep_angle == 0.0 || self.radius <= 0.0 {
+            return;
+        }
+        // Avoid the seam between the two radii of a full turn.
+        if self.sweep_angle.abs() >= std::f32::consts::TAU {
+            b.add_circle(self.center.to_point(), self.radius, Winding::Positive);
+            return;
+        }
+
+        let arc = Arc {
+            radius: self.radius,
+            start_angle: self.start_angle,
+            sweep_angle: self.sweep_angle,
+            center: self.center,
+        }
+        .lyon_arc();
+        b.begin(self.center.to_point());
+        b.line_to(arc.from());
+        arc.for_each_cubic_bezier(&mut |segment| {
+            b.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+        b.end(true);
+    }
+}
+
+/// A regular star polygon, alternating between `tips` vertices at
+/// `outer_radius` and as many vertices at `inner_radius`.
+///
+/// The first tip points up. Stars with less than 2 tips produce no geometry.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Star {
+    pub tips: usize,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub center: Vec2,
+}
+
+impl Default for Star {
+    fn default() -> Self {
+        Self {
+            tips: 5,
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            center: Vec2::ZERO,
+        }
+    }
+}
+
+impl Geometry for Star {
+    fn add_geometry(&self, b: &mut Builder) {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        if self.tips < 2 {
+            return;
+        }
+
+        let step = PI / self.tips as f32;
+        let points = (0..2 * self.tips)
+            .map(|i| {
+                let radius = if i % 2 == 0 {
+                    self.outer_radius
+                } else {
+                    self.inner_radius
+                };
+                let angle = (i as f32).mul_add(step, FRAC_PI_2);
+                point(
+                    radius.mul_add(angle.cos(), self.center.x),
+                    radius.mul_add(angle.sin(), self.center.y),
+                )
+            })
+            .collect::<Vec<Point>>();
+
+        b.add_polygon(LyonPolygon {
+            points: points.as_slice(),
+            closed: true,
+        });
+    }
+}
+
+/// A line of text, drawn using the outlines of the glyphs of a font.
+///
+/// `size` is the height of an em in world units, and `position` is where the
+/// baseline of the first glyph starts. Characters that are missing from the
+/// font are skipped.
+///
+/// Glyphs with holes, like `o` or `A`, are made of contours with opposite
+/// windings, so they are filled correctly with both fill rules.
+#[cfg(feature = "text")]
+#[allow(missing_docs)]
+#[derive(Clone)]
+pub struct Text {
+    pub text: String,
+    pub font: ab_glyph::FontArc,
+    pub size: f32,
+    pub position: Vec2,
+}
+
+#[cfg(feature = "text")]
+impl Geometry for Text {
+    fn add_geometry(&self, b: &mut Builder) {
+        use ab_glyph::Font;
+
+        let scale = self.size / self.font.units_per_em().unwrap_or(1.0);
+        let to_point = |p: ab_glyph::Point, x_offset: f32| {
+            point(
+                p.x.mul_add(scale, x_offset),
+                p.y.mul_add(scale, self.position.y),
+            )
+        };
+
+        let mut x_offset = self.position.x;
+        let mut previous = None;
+        for c in self.text.chars() {
+            let id = self.font.glyph_id(c);
+            // The glyph with id 0 is used for missing characters.
+            if id.0 == 0 {
+                continue;
+            }
+            if let Some(previous) = previous {
+                x_offset += self.font.kern_unscaled(previous, id) * scale;
+            }
+            previous = Some(id);
+
+            if let Some(outline) = self.font.outline(id) {
+                add_outline(b, outline.curves, |p| to_point(p, x_offset));
+            }
+
+            x_offset += self.font.h_advance_unscaled(id) * scale;
+        }
+    }
+}
+
+/// Adds the contours of a glyph outline to a path, mapping font units to
+/// world space with `to_point`.
+///
+/// The curves of a contour are contiguous, so a contour starts wherever a
+/// curve does not begin at the end of the previous one.
+#[cfg(feature = "text")]
+fn add_outline(
+    b: &mut Builder,
+    curves: impl IntoIterator<Item = ab_glyph::OutlineCurve>,
+    to_point: impl Fn(ab_glyph::Point) -> Point,
+) {
+    use ab_glyph::OutlineCurve;
+
+    // Font units are coarse, so points this close are the same point.
+    const EPSILON: f32 = 1e-3;
+
+    let mut current: Option<ab_glyph::Point> = None;
+    for curve in curves {
+        let (from, to) = match curve {
+            OutlineCurve::Line(from, to)
+            | OutlineCurve::Quad(from, _, to)
+            | OutlineCurve::Cubic(from, _, _, to) => (from, to),
+        };
+        let contiguous = current.map_or(false, |c| {
+            (c.x - from.x).abs() <= EPSILON && (c.y - from.y).abs() <= EPSILON
+        });
+        if !contiguous {
+            if current.is_some() {
+                b.end(true);
+            }
+            b.begin(to_point(from));
+        }
+        match curve {
+            OutlineCurve::Line(..) => {
+                b.line_to(to_point(to));
+            }
+            OutlineCurve::Quad(_, ctrl, _) => {
+                b.quadratic_bezier_to(to_point(ctrl), to_point(to));
+            }
+            OutlineCurve::Cubic(_, ctrl1, ctrl2, _) => {
+                b.cubic_bezier_to(to_point(ctrl1), to_point(ctrl2), to_point(to));
+            }
+        }
+        current = Some(to);
+    }
+    if current.is_some() {
+        b.end(true);
+    }
+}
+
 ///An easy way to display svg paths as a shape, takes an svg path string and a
 ///document size(Vec2).
 ///
@@ -263,6 +1286,7 @@ impl Geometry for Line {
 /// unit per px
 ///
 ///Example exists in the examples folder
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvgPathShape {
     ///The document size of the svg art, make sure the units are in pixels
     pub svg_doc_size_in_px: Vec2,
@@ -290,12 +1314,75 @@ fn get_point_after_offset(x: f64, y: f64, offset_x: f32, offset_y: f32) -> Point
 fn get_corrected_relative_vector(x: f64, y: f64) -> Vector {
     Vector::new(x as f32, get_y_in_bevy_orientation(y))
 }
-impl Geometry for SvgPathShape {
+impl SvgPathShape {
+    /// Parses the SVG path string into a [`Path`](crate::entity::Path).
+    ///
+    /// Each `M` or `m` command starts a new sub-path, a relative one being
+    /// taken from the current point, or from the start of the sub-path that
+    /// was just closed. Compound shapes therefore keep their holes, as long as
+    /// they are wound against their outer sub-path when filled with
+    /// [`FillRule::NonZero`](lyon_tessellation::FillRule::NonZero).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_prototype_lyon::prelude::*;
+    /// # use bevy_prototype_lyon::plugin::tessellate_fill;
+    /// # use tess::path::iterator::PathIterator;
+    /// #
+    /// let donut = shapes::SvgPathShape {
+    ///     svg_doc_size_in_px: Vec2::ZERO,
+    ///     // The hole is wound the other way, and starts relatively to the
+    ///     // start of the outer square.
+    ///     svg_path_string: "M -10 -10 h 20 v 20 h -20 z m 5 5 v 10 h 10 v -10 z".to_owned(),
+    /// }
+    /// .parse()
+    /// .unwrap();
+    /// let mode = FillMode::color(Color::RED).with_fill_rule(FillRule::NonZero);
+    /// let mesh = tessellate_fill(&donut, &mode);
+    ///
+    /// let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+    ///     Some(bevy::render::mesh::VertexAttributeValues::Float32x3(positions)) => positions,
+    ///     _ => unreachable!(),
+    /// };
+    /// let indices = mesh.indices().unwrap().iter().collect::<Vec<_>>();
+    /// let area = indices
+    ///     .chunks_exact(3)
+    ///     .map(|triangle| {
+    ///         let [a, b, c] = [0, 1, 2].map(|i| Vec2::from_slice(&positions[triangle[i]]));
+    ///         (b - a).perp_dot(c - a).abs() / 2.0
+    ///     })
+    ///     .sum::<f32>();
+    /// // A ring, not a filled square.
+    /// assert!((area - 300.0).abs() < 1e-3);
+    ///
+    /// // Elliptical arcs closely follow the ellipse they belong to.
+    /// let circle = shapes::SvgPathShape {
+    ///     svg_doc_size_in_px: Vec2::ZERO,
+    ///     svg_path_string: "M 0 -100 A 100 100 0 0 1 0 100 A 100 100 0 0 1 0 -100 Z".to_owned(),
+    /// }
+    /// .parse()
+    /// .unwrap();
+    /// for event in circle.0.iter().flattened(1e-3) {
+    ///     if let tess::path::PathEvent::Line { to, .. } = event {
+    ///         assert!((to.to_vector().length() - 100.0).abs() < 1e-2);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `svg_path_string` is not valid SVG path data.
+    pub fn parse(&self) -> Result<crate::entity::Path, SvgPathError> {
+        self.build_path().map(crate::entity::Path)
+    }
+
     #[allow(clippy::too_many_lines)]
-    fn add_geometry(&self, b: &mut Builder) {
+    fn build_path(&self) -> Result<LyonPath, SvgPathError> {
         let builder = Builder::new();
         let mut svg_builder = WithSvg::new(builder);
-        let p: Path = self.svg_path_string.parse().unwrap();
+        let p: Path = self.svg_path_string.parse()?;
         let offset_x = self.svg_doc_size_in_px.x / 2.;
         let offset_y = self.svg_doc_size_in_px.y / 2.;
         let mut used_move_command = false;
@@ -408,32 +1495,469 @@ impl Geometry for SvgPathShape {
                     x,
                     y,
                 } => {
-                    if abs {
-                        svg_builder.arc_to(
-                            Vector::new(rx as f32, ry as f32),
-                            Angle {
-                                radians: x_axis_rotation as f32,
-                            },
-                            ArcFlags { large_arc, sweep },
-                            get_point_after_offset(x, y, offset_x, offset_y),
-                        );
+                    let from = svg_builder.current_position();
+                    let to = if abs {
+                        get_point_after_offset(x, y, offset_x, offset_y)
                     } else {
-                        svg_builder.relative_arc_to(
-                            Vector::new(rx as f32, ry as f32),
-                            Angle {
-                                radians: x_axis_rotation as f32,
-                            },
-                            ArcFlags { large_arc, sweep },
-                            get_corrected_relative_vector(x, y),
-                        );
-                    }
+                        from + get_corrected_relative_vector(x, y)
+                    };
+                    add_svg_arc(
+                        &mut svg_builder,
+                        &SvgArc {
+                            from,
+                            to,
+                            radii: Vector::new(rx as f32, ry as f32),
+                            x_rotation: Angle::radians(x_axis_rotation as f32),
+                            flags: ArcFlags { large_arc, sweep },
+                        },
+                    );
                 }
                 PathSegment::ClosePath { abs: _ } => {
                     svg_builder.close();
                 }
             }
         }
-        let path = svg_builder.build();
-        b.concatenate(&[path.as_slice()]);
+        Ok(svg_builder.build())
+    }
+}
+
+/// The largest sweep angle of the pieces of the elliptical arcs of SVG paths.
+/// Each piece is approximated by a cubic bezier curve, whose distance to the
+/// arc is about a hundred thousandth of the radius at this angle.
+const SVG_ARC_PIECE_SWEEP: f32 = std::f32::consts::FRAC_PI_4;
+
+/// Adds an elliptical arc of an SVG path to `builder`, as cubic bezier curves.
+///
+/// The arc is split into pieces of at most [`SVG_ARC_PIECE_SWEEP`], which
+/// follow it much more closely than the curves used by lyon. Following the
+/// SVG specification, an arc to the current point is skipped, and an arc
+/// with a zero radius is a straight line.
+fn add_svg_arc(builder: &mut WithSvg<Builder>, svg_arc: &SvgArc<f32>) {
+    if svg_arc.from == svg_arc.to {
+        return;
+    }
+    if svg_arc.is_straight_line() {
+        builder.line_to(svg_arc.to);
+        return;
+    }
+
+    let arc = svg_arc.to_arc();
+    let sweep = arc.sweep_angle.radians;
+    #[allow(clippy::cast_sign_loss)]
+    let pieces = (sweep.abs() / SVG_ARC_PIECE_SWEEP).ceil().max(1.0) as u32;
+    let piece_sweep = sweep / pieces as f32;
+    for i in 0..pieces {
+        let piece = LyonArc {
+            start_angle: Angle::radians(arc.start_angle.radians + piece_sweep * i as f32),
+            sweep_angle: Angle::radians(piece_sweep),
+            ..arc
+        };
+        piece.for_each_cubic_bezier(&mut |segment| {
+            builder.cubic_bezier_to(segment.ctrl1, segment.ctrl2, segment.to);
+        });
+    }
+}
+
+impl Geometry for SvgPathShape {
+    /// Adds the parsed path to the builder. Invalid path data is logged as an
+    /// error and results in no geometry; use [`SvgPathShape::parse`] to handle
+    /// the error.
+    fn add_geometry(&self, b: &mut Builder) {
+        match self.build_path() {
+            Ok(path) => b.concatenate(&[path.as_slice()]),
+            Err(e) => error!("Invalid SVG path data: {}", e),
+        }
+    }
+}
+
+/// The point of a shape that [`WithOrigin`] moves to the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapeOrigin {
+    /// The center of the bounding box of the shape.
+    Center,
+    /// The top left corner of the bounding box of the shape.
+    TopLeft,
+    /// The bottom left corner of the bounding box of the shape.
+    BottomLeft,
+    /// A point given in the coordinates of the shape.
+    Custom(Vec2),
+}
+
+impl Default for ShapeOrigin {
+    fn default() -> Self {
+        Self::Center
+    }
+}
+
+/// Moves any shape so that its `origin` lies at the origin of the path, which
+/// makes UI-style layout easier.
+///
+/// The bounding box of curved shapes is measured on their flattened outline,
+/// so it is exact within the default tolerance.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::prelude::*;
+/// #
+/// let bounds = |origin| {
+///     ShapePath::build_as(&shapes::WithOrigin {
+///         shape: shapes::Polygon {
+///             points: vec![Vec2::new(40.0, 40.0), Vec2::new(60.0, 40.0), Vec2::new(50.0, 70.0)],
+///             closed: true,
+///         },
+///         origin,
+///     })
+///     .bounding_box()
+/// };
+///
+/// let bottom_left = bounds(ShapeOrigin::BottomLeft);
+/// assert!(bottom_left.min.abs_diff_eq(Vec2::ZERO, 1e-3));
+///
+/// let top_left = bounds(ShapeOrigin::TopLeft);
+/// assert!(Vec2::new(top_left.min.x, top_left.max.y).abs_diff_eq(Vec2::ZERO, 1e-3));
+///
+/// let center = bounds(ShapeOrigin::Center);
+/// assert!(center.min.abs_diff_eq(-center.max, 1e-3));
+/// ```
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithOrigin<G> {
+    pub shape: G,
+    pub origin: ShapeOrigin,
+}
+
+impl<G: Geometry> Geometry for WithOrigin<G> {
+    fn add_geometry(&self, b: &mut Builder) {
+        let mut builder = Builder::new();
+        self.shape.add_geometry(&mut builder);
+        let path = builder.build();
+
+        let pivot = match (self.origin, flat_bounds(&path)) {
+            (ShapeOrigin::Custom(pivot), _) => pivot,
+            (_, None) => return,
+            (ShapeOrigin::Center, Some((min, max))) => (min + max) / 2.0,
+            (ShapeOrigin::TopLeft, Some((min, max))) => Vec2::new(min.x, max.y),
+            (ShapeOrigin::BottomLeft, Some((min, _))) => min,
+        };
+
+        let translation = Transform::translation(-pivot.x, -pivot.y);
+        b.concatenate(&[path.transformed(&translation).as_slice()]);
+    }
+}
+
+/// Returns the minimum and maximum corners of the bounding box of the
+/// flattened path, or `None` if the path is empty.
+fn flat_bounds(path: &LyonPath) -> Option<(Vec2, Vec2)> {
+    let mut points = flatten(path, FillOptions::DEFAULT_TOLERANCE)
+        .into_iter()
+        .flat_map(|sub_path| sub_path.points)
+        .map(ToVec2::to_vec2);
+    let first = points.next()?;
+
+    Some(points.fold((first, first), |(min, max), p| (min.min(p), max.max(p))))
+}
+
+#[cfg(test)]
+mod tests {
+    use lyon_tessellation::{
+        path::PathEvent, BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers,
+    };
+
+    use super::*;
+    use crate::path::ShapePath;
+
+    fn events(shape: &impl Geometry) -> Vec<PathEvent> {
+        ShapePath::build_as(shape).0.iter().collect()
+    }
+
+    /// Returns the summed area of the triangles of the fill of a shape.
+    fn fill_area(shape: &impl Geometry) -> f32 {
+        let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                &ShapePath::build_as(shape).0,
+                &FillOptions::tolerance(0.01),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| vertex.position()),
+            )
+            .unwrap();
+
+        buffers
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| buffers.vertices[triangle[i] as usize]);
+                (b - a).cross(c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn quarter_sector_has_a_quarter_of_the_circle_area() {
+        let circle = fill_area(&Circle {
+            radius: 10.0,
+            ..Circle::default()
+        });
+        let sector = fill_area(&Sector {
+            radius: 10.0,
+            ..Sector::default()
+        });
+        let full = fill_area(&Sector {
+            radius: 10.0,
+            sweep_angle: std::f32::consts::TAU,
+            ..Sector::default()
+        });
+
+        assert!((sector / circle - 0.25).abs() < 0.01);
+        assert!((full / circle - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn arcs_handle_degenerate_and_full_sweeps() {
+        use std::f32::consts::{FRAC_PI_2, TAU};
+
+        let arc = |radius, sweep_angle| Arc {
+            radius,
+            sweep_angle,
+            ..Arc::default()
+        };
+        assert!(events(&arc(10.0, 0.0)).is_empty());
+        assert!(events(&arc(0.0, FRAC_PI_2)).is_empty());
+
+        for (sweep_angle, closed) in [(FRAC_PI_2, false), (TAU, true), (2.0 * TAU, true)] {
+            assert!(matches!(
+                events(&arc(10.0, sweep_angle)).last(),
+                Some(&PathEvent::End { close, .. }) if close == closed
+            ));
+        }
+
+        // Negative sweeps go clockwise, so a quarter turn from the positive x
+        // axis ends on the negative y axis.
+        match events(&arc(10.0, -FRAC_PI_2)).last() {
+            Some(&PathEvent::End { last, close, .. }) => {
+                assert!(!close);
+                assert!(last.x.abs() < 1e-4 && (last.y + 10.0).abs() < 1e-4);
+            }
+            event => panic!("unexpected last event {:?}", event),
+        }
+    }
+
+    #[test]
+    fn star_has_two_endpoints_per_tip() {
+        let endpoints = |tips| {
+            events(&Star {
+                tips,
+                ..Star::default()
+            })
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. } | PathEvent::Line { .. }))
+            .count()
+        };
+
+        assert_eq!(endpoints(5), 10);
+        assert_eq!(endpoints(2), 4);
+        assert_eq!(endpoints(1), 0);
+    }
+
+    #[test]
+    fn rounded_rectangle_rounds_and_clamps_its_corners() {
+        let rounded = |radius| RoundedRectangle {
+            extents: Vec2::new(10.0, 4.0),
+            radii: CornerRadii::uniform(radius),
+            origin: RectangleOrigin::Center,
+        };
+        let rectangle = Rectangle {
+            extents: Vec2::new(10.0, 4.0),
+            origin: RectangleOrigin::Center,
+        };
+        let segments = |events: &[PathEvent]| {
+            events
+                .iter()
+                .filter(|event| !matches!(event, PathEvent::Begin { .. } | PathEvent::End { .. }))
+                .count()
+        };
+
+        assert_eq!(events(&rounded(0.0)), events(&rectangle));
+        assert!(segments(&events(&rounded(1.0))) > segments(&events(&rectangle)));
+
+        // The radii can't exceed half the shorter side, so the corners meet
+        // without overlapping and the shape stays within its extents.
+        assert_eq!(events(&rounded(100.0)), events(&rounded(2.0)));
+        let ends: Vec<Point> = events(&rounded(100.0)).iter().map(PathEvent::to).collect();
+        assert!(ends
+            .iter()
+            .all(|end| end.x.abs() <= 5.0 + 1e-4 && end.y.abs() <= 2.0 + 1e-4));
+        assert!(ends.iter().any(|end| (end.x.abs() - 5.0).abs() < 1e-4));
+        assert!(ends.iter().any(|end| (end.y.abs() - 2.0).abs() < 1e-4));
+        assert!((fill_area(&rounded(100.0)) - fill_area(&rounded(2.0))).abs() < 1e-3);
+    }
+
+    fn svg(path: &str) -> Result<Vec<PathEvent>, SvgPathError> {
+        let shape = SvgPathShape {
+            svg_doc_size_in_px: Vec2::ZERO,
+            svg_path_string: path.to_owned(),
+        };
+        Ok(shape.parse()?.0.iter().collect())
+    }
+
+    #[test]
+    fn svg_path_commands_are_parsed() {
+        let line = |event: &PathEvent| matches!(event, PathEvent::Line { .. });
+        let cubic = |event: &PathEvent| matches!(event, PathEvent::Cubic { .. });
+        let quadratic = |event: &PathEvent| matches!(event, PathEvent::Quadratic { .. });
+        let curve = |event: &PathEvent| cubic(event) || quadratic(event);
+        let cases: [(&str, &dyn Fn(&PathEvent) -> bool, Point); 16] = [
+            ("M 1 2 L 3 4", &line, point(3.0, -4.0)),
+            ("M 1 2 l 3 4", &line, point(4.0, -6.0)),
+            ("M 1 2 H 5", &line, point(5.0, -2.0)),
+            ("M 1 2 h 5", &line, point(6.0, -2.0)),
+            ("M 1 2 V 5", &line, point(1.0, -5.0)),
+            ("M 1 2 v 5", &line, point(1.0, -7.0)),
+            ("M 0 0 C 1 1 2 1 3 0", &cubic, point(3.0, 0.0)),
+            ("M 0 0 c 1 1 2 1 3 0", &cubic, point(3.0, 0.0)),
+            ("M 0 0 C 1 1 2 1 3 0 S 5 -1 6 0", &cubic, point(6.0, 0.0)),
+            ("M 0 0 c 1 1 2 1 3 0 s 2 -1 3 0", &cubic, point(6.0, 0.0)),
+            ("M 0 0 Q 1 1 2 0", &quadratic, point(2.0, 0.0)),
+            ("M 0 0 q 1 1 2 0", &quadratic, point(2.0, 0.0)),
+            ("M 0 0 Q 1 1 2 0 T 4 0", &quadratic, point(4.0, 0.0)),
+            ("M 0 0 q 1 1 2 0 t 2 0", &quadratic, point(4.0, 0.0)),
+            ("M 0 0 A 1 1 0 0 1 2 0", &curve, point(2.0, 0.0)),
+            ("M 0 0 a 1 1 0 0 1 2 0", &curve, point(2.0, 0.0)),
+        ];
+
+        for (path, is_expected, end) in cases {
+            let events = svg(path).unwrap();
+            let last = &events[events.len() - 2];
+            assert!(is_expected(last), "{}: {:?}", path, last);
+            assert!((last.to() - end).length() < 1e-4, "{}: {:?}", path, last);
+            assert!(matches!(
+                events.last(),
+                Some(PathEvent::End { close: false, .. })
+            ));
+        }
+
+        for path in ["M 0 0 L 1 0 Z", "M 0 0 L 1 0 z"] {
+            let events = svg(path).unwrap();
+            assert!(matches!(
+                events.last(),
+                Some(PathEvent::End { close: true, .. })
+            ));
+        }
+
+        let events = svg("M 1 2 L 2 2 m 3 4 l 1 0").unwrap();
+        let begins: Vec<Point> = events
+            .iter()
+            .filter_map(|event| match event {
+                PathEvent::Begin { at } => Some(*at),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(begins, [point(1.0, -2.0), point(5.0, -6.0)]);
+
+        // Invalid data is either reported or, like SVG renderers do, ignored
+        // from where it starts, but never panics.
+        for path in ["M 0 0 L 1 0 L 1", "M 0 0 L 1 0 X 1 1"] {
+            if let Ok(events) = svg(path) {
+                assert_eq!(events.len(), 3, "{}: {:?}", path, events);
+            }
+        }
+    }
+
+    #[test]
+    fn svg_triangle_round_trips() {
+        assert_eq!(
+            svg("M0 0 L10 0 L10 10 Z").unwrap(),
+            [
+                PathEvent::Begin {
+                    at: point(0.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(0.0, 0.0),
+                    to: point(10.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(10.0, 0.0),
+                    to: point(10.0, -10.0)
+                },
+                PathEvent::End {
+                    last: point(10.0, -10.0),
+                    first: point(0.0, 0.0),
+                    close: true
+                },
+            ]
+        );
+    }
+
+    /// Returns the lines of a glyph contour, given as a ring of points in font
+    /// units.
+    #[cfg(feature = "text")]
+    fn contour(ring: &[(f32, f32)]) -> Vec<ab_glyph::OutlineCurve> {
+        let at = |i: usize| ab_glyph::point(ring[i % ring.len()].0, ring[i % ring.len()].1);
+        (0..ring.len())
+            .map(|i| ab_glyph::OutlineCurve::Line(at(i), at(i + 1)))
+            .collect()
+    }
+
+    #[cfg(feature = "text")]
+    fn glyph_events(curves: Vec<ab_glyph::OutlineCurve>) -> Vec<PathEvent> {
+        let mut b = lyon_tessellation::path::Path::builder();
+        add_outline(&mut b, curves, |p| point(p.x / 10.0, p.y / 10.0));
+        b.build().iter().collect()
+    }
+
+    #[cfg(feature = "text")]
+    fn begins(events: &[PathEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, PathEvent::Begin { .. }))
+            .count()
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn glyph_with_one_contour_is_one_closed_sub_path() {
+        // An `L`.
+        let events = glyph_events(contour(&[
+            (0.0, 0.0),
+            (60.0, 0.0),
+            (60.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 70.0),
+            (0.0, 70.0),
+        ]));
+
+        assert_eq!(begins(&events), 1);
+        assert_eq!(events.len(), 6 + 2);
+        assert!(matches!(
+            events.last(),
+            Some(PathEvent::End { close: true, .. })
+        ));
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn glyph_with_a_hole_is_two_sub_paths() {
+        // An `O`, with the inner contour wound the other way.
+        let mut curves = contour(&[(0.0, 0.0), (50.0, 0.0), (50.0, 70.0), (0.0, 70.0)]);
+        curves.extend(contour(&[
+            (10.0, 10.0),
+            (10.0, 60.0),
+            (40.0, 60.0),
+            (40.0, 10.0),
+        ]));
+        let events = glyph_events(curves.clone());
+        assert_eq!(begins(&events), 2);
+        assert_eq!(events.len(), 8 + 4);
+
+        // A rounding error between two curves of a contour does not split it.
+        if let ab_glyph::OutlineCurve::Line(from, _) = &mut curves[5] {
+            from.x += 1e-5;
+        }
+        let events = glyph_events(curves);
+        assert_eq!(begins(&events), 2);
+        assert_eq!(events.len(), 8 + 4);
     }
 }
@@ -1,24 +1,392 @@
-//! Utility types and conversion traits.
+//! Utility types and functions.
 
-use bevy::math::Vec2;
-use lyon_tessellation::math::{Point, Vector};
+use lyon_tessellation::{
+    math::{Point, Vector},
+    path::{iterator::PathIterator, traits::PathBuilder, Path, PathEvent, Polygon},
+    FillRule,
+};
 
-pub trait ToPoint {
-    fn to_point(self) -> Point;
+use crate::convert::{ToPoint, ToVector};
+
+/// A sub-path made only of straight line segments.
+pub struct FlatSubPath {
+    pub points: Vec<Point>,
+    pub closed: bool,
 }
 
-pub trait ToVector {
-    fn to_vector(self) -> Vector;
+impl FlatSubPath {
+    /// Iterates over the segments of the sub-path, including the closing one.
+    pub fn segments(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let closing = self
+            .closed
+            .then(|| self.points.last().zip(self.points.first()))
+            .flatten()
+            .map(|(&last, &first)| (last, first));
+
+        self.points.windows(2).map(|w| (w[0], w[1])).chain(closing)
+    }
+
+    /// Returns the length of the sub-path, including the closing segment.
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(from, to)| (to - from).length()).sum()
+    }
+
+    /// Returns the arc length parameter, in the `[0, 1]` range, of each point
+    /// of the sub-path.
+    pub fn point_parameters(&self) -> Vec<f32> {
+        let length = self.length();
+        if length == 0.0 {
+            return vec![0.0; self.points.len()];
+        }
+
+        let mut travelled = 0.0;
+        let mut parameters = vec![0.0];
+        for (from, to) in self.points.windows(2).map(|w| (w[0], w[1])) {
+            travelled += (to - from).length();
+            parameters.push(travelled / length);
+        }
+
+        parameters
+    }
+
+    /// Returns the points at the given arc length parameters, in the `[0, 1]`
+    /// range. The parameters must be sorted.
+    pub fn sample(&self, parameters: &[f32]) -> Vec<Point> {
+        let first = match self.points.first() {
+            Some(&first) => first,
+            None => return Vec::new(),
+        };
+        let end = if self.closed {
+            first
+        } else {
+            *self.points.last().unwrap_or(&first)
+        };
+        let length = self.length();
+
+        let mut samples = Vec::with_capacity(parameters.len());
+        let mut segments = self.segments();
+        let mut current = segments.next();
+        let mut segment_start = 0.0;
+        for parameter in parameters {
+            let target = parameter * length;
+            loop {
+                match current {
+                    Some((from, to)) => {
+                        let segment_length = (to - from).length();
+                        if target <= segment_start + segment_length {
+                            let t = if segment_length > 0.0 {
+                                (target - segment_start) / segment_length
+                            } else {
+                                0.0
+                            };
+                            samples.push(from.lerp(to, t));
+                            break;
+                        }
+                        segment_start += segment_length;
+                        current = segments.next();
+                    }
+                    None => {
+                        samples.push(end);
+                        break;
+                    }
+                }
+            }
+        }
+
+        samples
+    }
+
+    /// Returns the average of the points of the sub-path.
+    pub fn centroid(&self) -> Point {
+        let sum = self
+            .points
+            .iter()
+            .fold(Vector::zero(), |sum, p| sum + p.to_vector());
+
+        (sum / self.points.len().max(1) as f32).to_point()
+    }
 }
 
-impl ToPoint for Vec2 {
-    fn to_point(self) -> Point {
-        Point::new(self.x, self.y)
+/// Approximates each sub-path of `path` with line segments, within the given
+/// tolerance.
+pub fn flatten(path: &Path, tolerance: f32) -> Vec<FlatSubPath> {
+    let mut sub_paths = Vec::new();
+    let mut points = Vec::new();
+
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                points.push(at);
+            }
+            PathEvent::Line { to, .. } => {
+                points.push(to);
+            }
+            PathEvent::End { close, .. } => {
+                sub_paths.push(FlatSubPath {
+                    points: std::mem::take(&mut points),
+                    closed: close,
+                });
+            }
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                unreachable!("flattened paths only contain line segments")
+            }
+        }
     }
+
+    sub_paths
+}
+
+/// Returns the winding number of `point` around the sub-paths, each of them
+/// being treated as closed.
+pub fn winding_number(sub_paths: &[FlatSubPath], point: Point) -> i32 {
+    let mut winding = 0;
+    for sub_path in sub_paths {
+        let points = &sub_path.points;
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            let side = (b - a).cross(point - a);
+            if a.y <= point.y {
+                if b.y > point.y && side > 0.0 {
+                    winding += 1;
+                }
+            } else if b.y <= point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+
+    winding
+}
+
+/// Returns `true` if a point with the given winding number is inside the fill.
+pub const fn is_inside(winding: i32, fill_rule: FillRule) -> bool {
+    match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Returns the points of a sub-path treated as a closed ring, without repeated
+/// consecutive points nor a last point equal to the first.
+pub fn ring_points(sub_path: &FlatSubPath) -> Vec<Point> {
+    let mut points = sub_path.points.clone();
+    points.dedup();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    points
+}
+
+/// Returns the unit normal pointing to the right of the given direction.
+pub fn right_normal(direction: Vector) -> Vector {
+    Vector::new(direction.y, -direction.x).normalize()
+}
+
+/// Returns the miter of the right normals of the two edges meeting at
+/// `current`, scaled so that the offset edges stay parallel to the originals
+/// but no longer than `miter_limit`.
+pub fn vertex_normal(prev: Point, current: Point, next: Point, miter_limit: f32) -> Vector {
+    let before = right_normal(current - prev);
+    let after = right_normal(next - current);
+    let sum = before + after;
+    if sum.square_length() < 1e-6 {
+        return after;
+    }
+
+    let miter = sum.normalize();
+    miter / miter.dot(after).max(1.0 / miter_limit)
 }
 
-impl ToVector for Vec2 {
-    fn to_vector(self) -> Vector {
-        Vector::new(self.x, self.y)
+/// Returns `1.0` if the right side of `ring` is outside of the fill of
+/// `sub_paths`, and `-1.0` otherwise. The ring must have at least two points.
+pub fn outward_side(sub_paths: &[FlatSubPath], ring: &[Point], fill_rule: FillRule) -> f32 {
+    // Check just past the middle of the first edge.
+    let probe = ring[0].lerp(ring[1], 0.5) + right_normal(ring[1] - ring[0]) * 1e-3;
+    if is_inside(winding_number(sub_paths, probe), fill_rule) {
+        -1.0
+    } else {
+        1.0
     }
 }
+
+/// Moves each closed sub-path of `path` outwards by `distance`, or inwards if
+/// `distance` is negative, according to the even-odd fill rule. Open sub-paths
+/// are kept in place.
+///
+/// Curves are approximated with line segments within the given tolerance.
+pub fn offset(path: &Path, distance: f32, tolerance: f32) -> Path {
+    const MITER_LIMIT: f32 = 4.0;
+
+    let sub_paths = flatten(path, tolerance);
+    let mut builder = Path::builder();
+    for sub_path in &sub_paths {
+        let ring = ring_points(sub_path);
+        let points = if sub_path.closed && ring.len() >= 3 {
+            let side = outward_side(&sub_paths, &ring, FillRule::EvenOdd);
+            let count = ring.len();
+            ring.iter()
+                .enumerate()
+                .map(|(i, &current)| {
+                    let prev = ring[(i + count - 1) % count];
+                    let next = ring[(i + 1) % count];
+                    current + vertex_normal(prev, current, next, MITER_LIMIT) * side * distance
+                })
+                .collect()
+        } else {
+            sub_path.points.clone()
+        };
+
+        builder.add_polygon(Polygon {
+            points: &points,
+            closed: sub_path.closed,
+        });
+    }
+
+    builder.build()
+}
+
+/// Returns the first `fraction` of the length of `path`, walking its sub-paths
+/// one after the other. Closed sub-paths that are cut are left open.
+///
+/// Curves are approximated with line segments within the given tolerance,
+/// unless the whole path is kept.
+pub fn truncate(path: &Path, fraction: f32, tolerance: f32) -> Path {
+    if fraction >= 1.0 {
+        return path.clone();
+    }
+
+    let sub_paths = flatten(path, tolerance);
+    let mut remaining = fraction.max(0.0) * sub_paths.iter().map(FlatSubPath::length).sum::<f32>();
+    let mut builder = Path::builder();
+    for sub_path in &sub_paths {
+        if remaining <= 0.0 {
+            break;
+        }
+
+        let length = sub_path.length();
+        if length <= remaining {
+            builder.add_polygon(Polygon {
+                points: &sub_path.points,
+                closed: sub_path.closed,
+            });
+            remaining -= length;
+            continue;
+        }
+
+        let mut points = vec![sub_path.points[0]];
+        for (from, to) in sub_path.segments() {
+            let segment_length = (to - from).length();
+            if segment_length >= remaining {
+                points.push(from.lerp(to, remaining / segment_length));
+                break;
+            }
+            points.push(to);
+            remaining -= segment_length;
+        }
+        remaining = 0.0;
+
+        builder.add_polygon(Polygon {
+            points: &points,
+            closed: false,
+        });
+    }
+
+    builder.build()
+}
+
+/// Returns whether all the points of `path`, including the control points of
+/// its curves, have finite coordinates.
+pub fn is_finite(path: &Path) -> bool {
+    let is_finite = |point: Point| point.x.is_finite() && point.y.is_finite();
+    path.iter().all(|event| match event {
+        PathEvent::Begin { at } => is_finite(at),
+        PathEvent::Line { to, .. } => is_finite(to),
+        PathEvent::Quadratic { ctrl, to, .. } => is_finite(ctrl) && is_finite(to),
+        PathEvent::Cubic {
+            ctrl1, ctrl2, to, ..
+        } => is_finite(ctrl1) && is_finite(ctrl2) && is_finite(to),
+        PathEvent::End { .. } => true,
+    })
+}
+
+/// Returns whether `path` has a sub-path that is not closed.
+pub fn has_open_sub_path(path: &Path) -> bool {
+    path.iter()
+        .any(|event| matches!(event, PathEvent::End { close: false, .. }))
+}
+
+/// Returns the convex hull of `points`, counter-clockwise.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Andrew's monotone chain: the lower hull, then the upper hull.
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if (b - a).cross(point - a) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point is the first one of the other pass.
+        hull.pop();
+    }
+
+    hull
+}
+
+/// Simplifies a polyline with the Ramer-Douglas-Peucker algorithm, dropping
+/// the points that lie within `epsilon` of the simplified polyline. The first
+/// and last points are always kept.
+pub fn simplify_polyline(points: &[Point], epsilon: f32) -> Vec<Point> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| (i, segment_distance(points[i], points[first], points[last])))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((i, distance)) = farthest {
+            if distance > epsilon {
+                keep[i] = true;
+                ranges.push((first, i));
+                ranges.push((i, last));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&point, keep)| keep.then(|| point))
+        .collect()
+}
+
+/// Returns the distance from `point` to the segment going from `a` to `b`.
+fn segment_distance(point: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.square_length();
+    let t = if length_squared > 0.0 {
+        ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (point - a.lerp(b, t)).length()
+}
@@ -1,6 +1,15 @@
-use bevy::render::color::Color;
+//! Vertex types used to build the meshes of shapes.
+
+use bevy::{math::Vec2, render::color::Color, utils::HashMap};
 use lyon_tessellation::{
-    self as tess, FillVertex, FillVertexConstructor, StrokeVertex, StrokeVertexConstructor,
+    self as tess, math::Point, Count, FillGeometryBuilder, FillVertex, FillVertexConstructor,
+    GeometryBuilder, GeometryBuilderError, StrokeGeometryBuilder, StrokeVertex,
+    StrokeVertexConstructor, VertexId,
+};
+
+use crate::{
+    draw::{Gradient, StrokeGradient},
+    plugin::MeshWinding,
 };
 
 /// The index type of a Bevy [`Mesh`](bevy::render::mesh::Mesh).
@@ -12,13 +21,72 @@ pub type VertexBuffers = tess::VertexBuffers<Vertex, IndexType>;
 /// [`Mesh`](bevy::render::mesh::Mesh).
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vertex {
+    /// The position of the vertex in the local space of the shape.
     pub position: [f32; 2],
+    /// The color of the vertex, packed by [`pack_color`].
     pub color: u32,
 }
 
+/// Packs a color into the `u32` stored in `Mesh::ATTRIBUTE_COLOR`.
+///
+/// The color is converted to linear space, which is what the shader outputs:
+/// the sRGB render target converts it back when writing, so shapes match
+/// sprites of the same `Color`. Each channel takes a byte, with red in the
+/// least significant one and alpha in the most significant one.
+pub fn pack_color(color: Color) -> u32 {
+    color.as_linear_rgba_u32()
+}
+
+/// Unpacks a color packed by [`pack_color`] into its linear RGBA channels.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::Color;
+/// # use bevy_prototype_lyon::vertex::{pack_color, unpack_color};
+/// #
+/// // Linear 0.2 gray (≈ sRGB 0.48).
+/// let gray = Color::rgb_linear(0.2, 0.2, 0.2);
+/// assert_eq!(pack_color(gray), 0xFF33_3333);
+/// assert_eq!(unpack_color(pack_color(gray)), [0.2, 0.2, 0.2, 1.0]);
+/// ```
+#[must_use]
+pub fn unpack_color(packed: u32) -> [f32; 4] {
+    packed
+        .to_le_bytes()
+        .map(|channel| f32::from(channel) / 255.0)
+}
+
+/// Multiplies the RGB channels of a color packed by [`pack_color`] by its
+/// alpha, rounding to the nearest byte, for materials blending with
+/// premultiplied alpha. Alpha itself is left unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::Color;
+/// # use bevy_prototype_lyon::vertex::{pack_color, premultiply_alpha};
+/// #
+/// let red = pack_color(Color::rgba(1.0, 0.0, 0.0, 0.5));
+/// // Straight alpha keeps a full red channel...
+/// assert_eq!(red, 0x7F00_00FF);
+/// // ...while premultiplied alpha scales it down to the alpha.
+/// assert_eq!(premultiply_alpha(red), 0x7F00_007F);
+/// // Opaque colors are left as they are.
+/// assert_eq!(premultiply_alpha(0xFF12_3456), 0xFF12_3456);
+/// ```
+#[must_use]
+// The scaled channels are at most 255.
+#[allow(clippy::cast_possible_truncation)]
+pub fn premultiply_alpha(packed: u32) -> u32 {
+    let [r, g, b, a] = packed.to_le_bytes();
+    let scale = |channel: u8| ((u32::from(channel) * u32::from(a) + 127) / 255) as u8;
+    u32::from_le_bytes([scale(r), scale(g), scale(b), a])
+}
+
 /// Zero-sized type used to implement various vertex construction traits from
 /// Lyon.
-pub struct VertexConstructor {
+pub(crate) struct VertexConstructor {
     pub color: Color,
 }
 
@@ -27,7 +95,7 @@ impl FillVertexConstructor<Vertex> for VertexConstructor {
     fn new_vertex(&mut self, vertex: FillVertex) -> Vertex {
         Vertex {
             position: [vertex.position().x, vertex.position().y],
-            color: self.color.as_linear_rgba_u32(),
+            color: pack_color(self.color),
         }
     }
 }
@@ -37,7 +105,522 @@ impl StrokeVertexConstructor<Vertex> for VertexConstructor {
     fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
         Vertex {
             position: [vertex.position().x, vertex.position().y],
-            color: self.color.as_linear_rgba_u32(),
+            color: pack_color(self.color),
+        }
+    }
+}
+
+/// Constructs [`Vertex`]es colored by sampling a [`Gradient`] at their
+/// position.
+pub(crate) struct GradientVertexConstructor {
+    pub gradient: Gradient,
+}
+
+/// Enables the construction of a [`Vertex`] when using a `FillTessellator`.
+impl FillVertexConstructor<Vertex> for GradientVertexConstructor {
+    fn new_vertex(&mut self, vertex: FillVertex) -> Vertex {
+        let position = vertex.position();
+        Vertex {
+            position: [position.x, position.y],
+            color: pack_color(self.gradient.color_at(Vec2::new(position.x, position.y))),
+        }
+    }
+}
+
+/// Constructs [`Vertex`]es colored by sampling a [`StrokeGradient`] at their
+/// distance along the path.
+pub(crate) struct StrokeGradientVertexConstructor {
+    pub gradient: StrokeGradient,
+    /// The distance along the path at which the stroked sub-path starts.
+    pub start: f32,
+    /// The length of the whole path.
+    pub length: f32,
+}
+
+/// Enables the construction of a [`Vertex`] when using a `StrokeTessellator`.
+impl StrokeVertexConstructor<Vertex> for StrokeGradientVertexConstructor {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
+        let t = if self.length > 0.0 {
+            (self.start + vertex.advancement()) / self.length
+        } else {
+            1.0
+        };
+        Vertex {
+            position: [vertex.position().x, vertex.position().y],
+            color: pack_color(self.gradient.color_at(t)),
         }
     }
 }
+
+/// Constructs vertices holding only their position, for computations that
+/// don't need a mesh.
+pub(crate) struct PositionConstructor;
+
+impl FillVertexConstructor<Point> for PositionConstructor {
+    fn new_vertex(&mut self, vertex: FillVertex) -> Point {
+        vertex.position()
+    }
+}
+
+impl StrokeVertexConstructor<Point> for PositionConstructor {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> Point {
+        vertex.position()
+    }
+}
+
+/// A geometry builder that only counts the vertices and triangles it is given,
+/// without storing them.
+#[derive(Default)]
+pub(crate) struct TriangleCounter {
+    vertices: u32,
+    pub triangles: usize,
+}
+
+impl TriangleCounter {
+    fn add_vertex(&mut self) -> Result<VertexId, GeometryBuilderError> {
+        let id = VertexId(self.vertices);
+        self.vertices = self
+            .vertices
+            .checked_add(1)
+            .ok_or(GeometryBuilderError::TooManyVertices)?;
+        Ok(id)
+    }
+}
+
+impl GeometryBuilder for TriangleCounter {
+    fn begin_geometry(&mut self) {}
+
+    fn end_geometry(&mut self) -> Count {
+        Count {
+            vertices: self.vertices,
+            indices: u32::try_from(self.triangles * 3).unwrap_or(u32::MAX),
+        }
+    }
+
+    fn add_triangle(&mut self, _a: VertexId, _b: VertexId, _c: VertexId) {
+        self.triangles += 1;
+    }
+
+    fn abort_geometry(&mut self) {}
+}
+
+impl FillGeometryBuilder for TriangleCounter {
+    fn add_fill_vertex(&mut self, _vertex: FillVertex) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex()
+    }
+}
+
+impl StrokeGeometryBuilder for TriangleCounter {
+    fn add_stroke_vertex(
+        &mut self,
+        _vertex: StrokeVertex,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        self.add_vertex()
+    }
+}
+
+/// Returns `true` if every triangle of `buffers` is wound counter-clockwise,
+/// with the y axis pointing up. Degenerate triangles are ignored.
+///
+/// The shape pipeline draws both faces of the triangles, so this is meant to
+/// catch regressions in custom pipelines that rely on backface culling.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_prototype_lyon::vertex::{validate_ccw, Vertex, VertexBuffers};
+/// #
+/// let mut buffers = VertexBuffers::new();
+/// for position in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]] {
+///     buffers.vertices.push(Vertex { position, color: 0 });
+/// }
+/// buffers.indices.extend([0, 1, 2]);
+/// assert!(validate_ccw(&buffers));
+///
+/// buffers.indices.reverse();
+/// assert!(!validate_ccw(&buffers));
+/// ```
+#[must_use]
+pub fn validate_ccw(buffers: &VertexBuffers) -> bool {
+    buffers.indices.chunks_exact(3).all(|triangle| {
+        let [a, b, c] = [0, 1, 2].map(|i| {
+            let [x, y] = buffers.vertices[triangle[i] as usize].position;
+            Vec2::new(x, y)
+        });
+        (b - a).perp_dot(c - a) >= -f32::EPSILON
+    })
+}
+
+/// Reorders the `indices` of each triangle so that it is wound in the given
+/// direction, with the y axis pointing up. Degenerate triangles are left as
+/// they are.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_prototype_lyon::{
+/// #     plugin::MeshWinding,
+/// #     vertex::{set_winding, validate_ccw, Vertex, VertexBuffers},
+/// # };
+/// #
+/// let mut buffers = VertexBuffers::new();
+/// for position in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]] {
+///     buffers.vertices.push(Vertex { position, color: 0 });
+/// }
+/// buffers.indices.extend([0, 1, 2]);
+///
+/// set_winding(&buffers.vertices, &mut buffers.indices, MeshWinding::Cw);
+/// assert_eq!(buffers.indices, [0, 2, 1]);
+/// assert!(!validate_ccw(&buffers));
+///
+/// set_winding(&buffers.vertices, &mut buffers.indices, MeshWinding::Ccw);
+/// assert_eq!(buffers.indices, [0, 1, 2]);
+/// assert!(validate_ccw(&buffers));
+/// ```
+pub fn set_winding(vertices: &[Vertex], indices: &mut [u32], winding: MeshWinding) {
+    for triangle in indices.chunks_exact_mut(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| {
+            let [x, y] = vertices[triangle[i] as usize].position;
+            Vec2::new(x, y)
+        });
+        let area = (b - a).perp_dot(c - a);
+        let is_reversed = match winding {
+            MeshWinding::Ccw => area < 0.0,
+            MeshWinding::Cw => area > 0.0,
+        };
+        if is_reversed {
+            triangle.swap(1, 2);
+        }
+    }
+}
+
+/// Merges the vertices of `buffers` that have the same color and lie within
+/// `epsilon` of each other, and rewrites the indices to use the merged
+/// vertices. The triangles are kept as they are, even if they become
+/// degenerate.
+///
+/// Tessellators may emit coincident vertices, for example where the sub-paths
+/// of a stroke meet, so this can shrink the vertex buffer of a mesh.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_prototype_lyon::vertex::{weld, Vertex, VertexBuffers};
+/// #
+/// // Two triangles of a square, each with its own vertices.
+/// let mut buffers = VertexBuffers::new();
+/// for position in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+///     buffers.vertices.push(Vertex { position, color: 0 });
+/// }
+/// // A vertex of another color at the same position.
+/// buffers.vertices.push(Vertex {
+///     position: [0.0, 1.0],
+///     color: 1,
+/// });
+/// buffers.indices.extend([0, 1, 2, 3, 4, 5, 6, 4, 3]);
+///
+/// weld(&mut buffers, 1e-4);
+/// assert_eq!(buffers.vertices.len(), 5);
+/// assert_eq!(buffers.indices, [0, 1, 2, 0, 2, 3, 4, 2, 0]);
+/// ```
+pub fn weld(buffers: &mut VertexBuffers, epsilon: f32) {
+    let epsilon = epsilon.max(f32::EPSILON);
+    let cell_of = |position: [f32; 2]| position.map(|c| (c / epsilon).floor() as i64);
+
+    // The merged vertices, by color and by cell of a grid of `epsilon`.
+    let mut cells: HashMap<(u32, [i64; 2]), Vec<IndexType>> = HashMap::default();
+    let mut vertices: Vec<Vertex> = Vec::with_capacity(buffers.vertices.len());
+    let mut remap = Vec::with_capacity(buffers.vertices.len());
+    for vertex in &buffers.vertices {
+        let [x, y] = cell_of(vertex.position);
+        let position = Vec2::from(vertex.position);
+        // A vertex within `epsilon` lies in one of the neighboring cells.
+        let merged = (x - 1..=x + 1)
+            .flat_map(|cx| (y - 1..=y + 1).map(move |cy| [cx, cy]))
+            .filter_map(|cell| cells.get(&(vertex.color, cell)))
+            .flatten()
+            .copied()
+            .find(|&i| Vec2::from(vertices[i as usize].position).distance(position) <= epsilon);
+
+        let index = merged.unwrap_or_else(|| {
+            let index = vertices.len() as IndexType;
+            vertices.push(*vertex);
+            cells.entry((vertex.color, [x, y])).or_default().push(index);
+            index
+        });
+        remap.push(index);
+    }
+
+    for index in &mut buffers.indices {
+        *index = remap[*index as usize];
+    }
+    buffers.vertices = vertices;
+}
+
+/// The number of radians by which the angles opposite an edge must sum past
+/// a half turn for [`refine_delaunay`] to flip it. This keeps cocircular
+/// points, like those of a regular polygon, from being flipped back and forth.
+const DELAUNAY_EPSILON: f32 = 1e-5;
+
+/// Flips the inner edges of the triangles of `buffers` until they are
+/// Delaunay, which maximizes their smallest angle and removes the sliver
+/// triangles a fill may have.
+///
+/// Only edges shared by two triangles with the same winding are flipped, and
+/// only when both new triangles keep that winding, so the outline and the
+/// holes of the shape are preserved. No vertex is added or moved, and since a
+/// flip replaces two triangles by two triangles covering the same
+/// quadrilateral, no T-junction is introduced.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::math::Vec2;
+/// # use bevy_prototype_lyon::vertex::{refine_delaunay, Vertex, VertexBuffers};
+/// #
+/// fn smallest_angle(buffers: &VertexBuffers) -> f32 {
+///     let position = |i: u32| Vec2::from(buffers.vertices[i as usize].position);
+///     buffers
+///         .indices
+///         .chunks_exact(3)
+///         .flat_map(|t| [(t[0], t[1], t[2]), (t[1], t[2], t[0]), (t[2], t[0], t[1])])
+///         .map(|(a, b, c)| (position(b) - position(a)).angle_between(position(c) - position(a)))
+///         .fold(f32::INFINITY, |min, angle| min.min(angle.abs()))
+///         .to_degrees()
+/// }
+///
+/// // A four-pointed star, with the inner square and the right spike
+/// // triangulated as a fan from the right tip, which makes slivers.
+/// let mut buffers = VertexBuffers::new();
+/// for position in [
+///     [2.0, 0.0], [0.5, 0.5], [0.0, 2.0], [-0.5, 0.5],
+///     [-2.0, 0.0], [-0.5, -0.5], [0.0, -2.0], [0.5, -0.5],
+/// ] {
+///     buffers.vertices.push(Vertex { position, color: 0 });
+/// }
+/// buffers.indices.extend([1, 2, 3, 3, 4, 5, 5, 6, 7, 0, 1, 3, 0, 3, 5, 0, 5, 7]);
+/// assert!(smallest_angle(&buffers) < 7.2);
+///
+/// refine_delaunay(&mut buffers);
+/// // The smallest angles are now those of the tips of the star.
+/// assert!(smallest_angle(&buffers) > 36.8);
+/// assert_eq!(buffers.indices.len(), 18);
+/// ```
+pub fn refine_delaunay(buffers: &mut VertexBuffers) {
+    refine_delaunay_from(buffers, 0);
+}
+
+/// Refines the triangles of `buffers` starting at the index `start`, as done
+/// by [`refine_delaunay`], leaving the earlier triangles untouched.
+pub(crate) fn refine_delaunay_from(buffers: &mut VertexBuffers, start: usize) {
+    let positions = buffers
+        .vertices
+        .iter()
+        .map(|vertex| Vec2::from(vertex.position))
+        .collect::<Vec<_>>();
+    let mut triangles = buffers.indices[start..]
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect::<Vec<_>>();
+
+    // The triangle on the left of each directed edge.
+    let mut edges: HashMap<(IndexType, IndexType), usize> = HashMap::default();
+    for (i, triangle) in triangles.iter().enumerate() {
+        insert_edges(&mut edges, *triangle, i);
+    }
+
+    // Each flip increases the smallest angles of the triangles, so this ends,
+    // but the passes are bounded in case of rounding errors.
+    for _ in 0..triangles.len() {
+        let mut flipped = false;
+        for t in 0..triangles.len() {
+            for k in 0..3 {
+                let [a, b, c] = [0, 1, 2].map(|i| triangles[t][(k + i) % 3]);
+                let u = match edges.get(&(b, a)) {
+                    Some(&u) if u != t => u,
+                    _ => continue,
+                };
+                let d = match triangles[u].iter().find(|&&v| v != a && v != b) {
+                    Some(&d) if d != c => d,
+                    _ => continue,
+                };
+                let [pa, pb, pc, pd] = [a, b, c, d].map(|i| positions[i as usize]);
+                if !should_flip(pa, pb, pc, pd) {
+                    continue;
+                }
+
+                remove_edges(&mut edges, triangles[t], t);
+                remove_edges(&mut edges, triangles[u], u);
+                triangles[t] = [a, d, c];
+                triangles[u] = [d, b, c];
+                insert_edges(&mut edges, triangles[t], t);
+                insert_edges(&mut edges, triangles[u], u);
+                flipped = true;
+                break;
+            }
+        }
+        if !flipped {
+            break;
+        }
+    }
+
+    buffers.indices.truncate(start);
+    buffers.indices.extend(triangles.into_iter().flatten());
+}
+
+/// Returns whether the edge from `a` to `b`, between the triangles `abc` and
+/// `bad`, should be replaced by the edge from `c` to `d`.
+fn should_flip(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let winding = (b - a).perp_dot(c - a);
+    let keeps_winding = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p) * winding > 0.0;
+    if !keeps_winding(a, d, c) || !keeps_winding(d, b, c) {
+        return false;
+    }
+
+    let angle_at = |p: Vec2| (a - p).angle_between(b - p).abs();
+    angle_at(c) + angle_at(d) > std::f32::consts::PI + DELAUNAY_EPSILON
+}
+
+fn insert_edges(
+    edges: &mut HashMap<(IndexType, IndexType), usize>,
+    triangle: [IndexType; 3],
+    index: usize,
+) {
+    for edge in triangle_edges(triangle) {
+        edges.insert(edge, index);
+    }
+}
+
+fn remove_edges(
+    edges: &mut HashMap<(IndexType, IndexType), usize>,
+    triangle: [IndexType; 3],
+    index: usize,
+) {
+    for edge in triangle_edges(triangle) {
+        if edges.get(&edge) == Some(&index) {
+            edges.remove(&edge);
+        }
+    }
+}
+
+/// Returns the directed edges of a triangle, in its winding order.
+const fn triangle_edges([a, b, c]: [IndexType; 3]) -> [(IndexType, IndexType); 3] {
+    [(a, b), (b, c), (c, a)]
+}
+
+/// Sorts the vertices of `buffers` by position, then by color, and rewrites
+/// the indices to match. Each triangle is then rotated to start with its
+/// smallest index, keeping its winding.
+///
+/// The order of the triangles is kept, since later triangles are drawn over
+/// earlier ones. Tessellating the same path twice always gives the same
+/// buffers, but their order depends on the internals of lyon: once
+/// canonicalized, buffers only depend on the vertices and the triangles
+/// themselves, so they can be compared with stored ones.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_prototype_lyon::{
+/// #     plugin::tessellate_fill,
+/// #     prelude::*,
+/// #     vertex::{canonicalize, Vertex, VertexBuffers},
+/// # };
+/// #
+/// // Tessellating is deterministic.
+/// let circle = ShapePath::build_as(&shapes::Circle::default());
+/// let mode = FillMode::color(Color::RED);
+/// let [a, b] = [0, 1].map(|_| tessellate_fill(&circle, &mode));
+/// assert_eq!(a.get_vertex_buffer_data(), b.get_vertex_buffer_data());
+/// assert!(a.indices().unwrap().iter().eq(b.indices().unwrap().iter()));
+///
+/// let mut buffers = VertexBuffers::new();
+/// for position in [[1.0, 1.0], [0.0, 1.0], [0.0, 0.0], [1.0, 0.0]] {
+///     buffers.vertices.push(Vertex { position, color: 0 });
+/// }
+/// buffers.indices.extend([0, 1, 2, 2, 3, 0]);
+///
+/// canonicalize(&mut buffers);
+/// let positions = buffers.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+/// assert_eq!(positions, [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]]);
+/// assert_eq!(buffers.indices, [0, 3, 1, 0, 2, 3]);
+/// ```
+pub fn canonicalize(buffers: &mut VertexBuffers) {
+    let mut order = (0..buffers.vertices.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&buffers.vertices[a], &buffers.vertices[b]);
+        a.position[0]
+            .total_cmp(&b.position[0])
+            .then(a.position[1].total_cmp(&b.position[1]))
+            .then(a.color.cmp(&b.color))
+    });
+
+    let mut remap = vec![0; order.len()];
+    for (new, &old) in order.iter().enumerate() {
+        remap[old] = new as IndexType;
+    }
+    buffers.vertices = order.iter().map(|&old| buffers.vertices[old]).collect();
+    for triangle in buffers.indices.chunks_exact_mut(3) {
+        for index in triangle.iter_mut() {
+            *index = remap[*index as usize];
+        }
+        let smallest = (0..3).min_by_key(|&i| triangle[i]).unwrap_or(0);
+        triangle.rotate_left(smallest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_are_packed_in_linear_space() {
+        let [r, g, b, a] = pack_color(Color::rgb(0.5, 0.5, 0.5)).to_le_bytes();
+
+        // A mid-gray is about 0.214 in linear space, while its sRGB byte would
+        // be 127 or 128.
+        for channel in [r, g, b] {
+            assert!((54..=55).contains(&channel), "{}", channel);
+        }
+        assert_eq!(a, 255);
+        assert_eq!(r, g);
+        assert_eq!(g, b);
+    }
+
+    /// Parses the `input` or `canonical` buffers of a fixture.
+    fn fixture_buffers(fixture: &str, section: &str) -> VertexBuffers {
+        let mut buffers = VertexBuffers::new();
+        let lines = fixture
+            .lines()
+            .skip_while(|line| *line != format!("# {}", section))
+            .skip(1)
+            .take_while(|line| !line.starts_with('#'));
+        for line in lines {
+            let fields = line.split(' ').collect::<Vec<_>>();
+            match fields[..] {
+                ["v", x, y, color] => buffers.vertices.push(Vertex {
+                    position: [x.parse().unwrap(), y.parse().unwrap()],
+                    color: color.parse().unwrap(),
+                }),
+                ["f", a, b, c] => buffers
+                    .indices
+                    .extend([a, b, c].map(|index| index.parse::<IndexType>().unwrap())),
+                _ => panic!("unexpected fixture line {:?}", line),
+            }
+        }
+        buffers
+    }
+
+    #[test]
+    fn canonical_order_matches_the_fixture() {
+        let fixture = include_str!("../fixtures/canonical_circle.txt");
+        let mut buffers = fixture_buffers(fixture, "input");
+        let expected = fixture_buffers(fixture, "canonical");
+        assert_eq!(buffers.vertices.len(), 13);
+
+        canonicalize(&mut buffers);
+        assert_eq!(buffers.vertices, expected.vertices);
+        assert_eq!(buffers.indices, expected.indices);
+    }
+}